@@ -1,45 +1,208 @@
-use criterion::{Criterion, criterion_group, criterion_main};
+use aoc2025::day12;
+use aoc2025::fixtures;
+use aoc2025::registry;
+use aoc2025::threads;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use std::hint::black_box;
 use std::time::Duration;
 
-macro_rules! make_day_bench {
-    ($fn_name:ident, $mod:ident, $day:literal) => {
-        use aoc2025::$mod;
-        fn $fn_name(c: &mut Criterion) {
-            let input = std::fs::read_to_string(concat!("resources/tests/", $day)).unwrap();
-            let puzzle = $mod::Day::create(&input);
-
-            c.bench_function(concat!("Day ", $day, " Part 1"), |b| {
-                b.iter(|| black_box(puzzle.solve_part_1()))
-            });
-            c.bench_function(concat!("Day ", $day, " Part 2"), |b| {
-                b.iter(|| black_box(puzzle.solve_part_2()))
-            });
+/// Reads the personal input for `day`, or returns `None` after saying so if it has none, so that
+/// the other days can still be measured.
+fn read_input(day: u8) -> Option<String> {
+    match fixtures::read_input(day) {
+        Ok(input) => Some(input),
+        Err(e) if e.is_absent() => {
+            eprintln!("skipping Day {day:02}: no input at {}", e.path.display());
+            None
         }
+        Err(e) => panic!("{e}"),
+    }
+}
+
+/// The number of samples to take of days too slow for the default, where `None` keeps the
+/// default.
+fn sample_size(day: u8) -> Option<usize> {
+    match day {
+        10 => Some(100),
+        12 => Some(20),
+        _ => None,
+    }
+}
+
+fn days_bench(c: &mut Criterion) {
+    for day in &registry::DAYS {
+        let Some(input) = read_input(day.number) else {
+            continue;
+        };
+        let puzzle = (day.create)(&input);
+
+        // Measured per input byte, so that the reports give each day's parse and parts in MB/s,
+        // comparable across days with inputs of very different sizes.
+        let mut group = c.benchmark_group(format!("Day {:02}", day.number));
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        if let Some(samples) = sample_size(day.number) {
+            group.sample_size(samples);
+        }
+        group.bench_function("Parse", |b| {
+            b.iter(|| black_box((day.create)(black_box(&input))))
+        });
+        group.bench_function("Part 1", |b| b.iter(|| black_box(puzzle.solve_part_1())));
+        group.bench_function("Part 2", |b| b.iter(|| black_box(puzzle.solve_part_2())));
+        group.finish();
+    }
+}
+
+fn day12_pruning_bench(c: &mut Criterion) {
+    use aoc2025::puzzle::Puzzle;
+    let Some(input) = read_input(12) else {
+        return;
+    };
+    let options = day12::Options {
+        prune_holes: true,
+        ..day12::Options::default()
     };
+    let puzzle = day12::Day::with_options(&input, options);
+
+    let mut group = c.benchmark_group("Day 12");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    if let Some(samples) = sample_size(12) {
+        group.sample_size(samples);
+    }
+    group.bench_function("Part 1 (hole pruning)", |b| {
+        b.iter(|| black_box(puzzle.solve_part_1()))
+    });
+    group.finish();
 }
 
-make_day_bench!(day01_bench, day01, "01");
-make_day_bench!(day02_bench, day02, "02");
-make_day_bench!(day03_bench, day03, "03");
-make_day_bench!(day04_bench, day04, "04");
-make_day_bench!(day05_bench, day05, "05");
-make_day_bench!(day06_bench, day06, "06");
-make_day_bench!(day07_bench, day07, "07");
-make_day_bench!(day08_bench, day08, "08");
-make_day_bench!(day09_bench, day09, "09");
-make_day_bench!(day10_bench, day10, "10");
-make_day_bench!(day11_bench, day11, "11");
-make_day_bench!(day12_bench, day12, "12");
+fn bitset_bench(c: &mut Criterion) {
+    use aoc2025::bitset::{BitGrid, BitSet};
+    let mut set = BitSet::new(1 << 16);
+    for i in (0..set.len()).step_by(3) {
+        set.set(i);
+    }
+
+    c.bench_function("BitSet count_ones", |b| {
+        b.iter(|| black_box(black_box(&set).count_ones()))
+    });
+    c.bench_function("BitSet shift", |b| {
+        b.iter(|| {
+            let mut shifted = set.clone();
+            shifted <<= black_box(77);
+            shifted >>= black_box(13);
+            black_box(shifted)
+        })
+    });
+    c.bench_function("BitGrid set and test", |b| {
+        b.iter(|| {
+            let mut grid = BitGrid::new(140, 140);
+            for i in 0..140 * 140 / 7 {
+                grid.set(i * 7 / 140, i * 7 % 140);
+            }
+            black_box((0..140).filter(|&r| grid.test(r, r)).count())
+        })
+    });
+}
+
+fn parse_bench(c: &mut Criterion) {
+    use aoc2025::fastparse;
+    let numbers: Vec<String> = (0..1000u64)
+        .map(|i| (i * 2_654_435_761 % 1_000_000_000_007).to_string())
+        .collect();
+
+    c.bench_function("parse u64 (str::parse)", |b| {
+        b.iter(|| {
+            let sum: u64 = numbers.iter().map(|s| s.parse::<u64>().unwrap()).sum();
+            black_box(sum)
+        })
+    });
+    c.bench_function("parse u64 (fastparse)", |b| {
+        b.iter(|| {
+            let sum: u64 = numbers
+                .iter()
+                .map(|s| fastparse::parse_u64(s.as_bytes()).unwrap())
+                .sum();
+            black_box(sum)
+        })
+    });
+}
+
+fn pq_bench(c: &mut Criterion) {
+    use aoc2025::pq::BucketQueue;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+    const SIZE: usize = 300;
+    let weights: Vec<usize> = (0..SIZE * SIZE)
+        .map(|i| 1 + i * 2_654_435_761 % 9)
+        .collect();
+    let neighbors = |i: usize| {
+        let (r, c) = (i / SIZE, i % SIZE);
+        [
+            (r > 0).then(|| i - SIZE),
+            (r + 1 < SIZE).then(|| i + SIZE),
+            (c > 0).then(|| i - 1),
+            (c + 1 < SIZE).then(|| i + 1),
+        ]
+        .into_iter()
+        .flatten()
+    };
+
+    c.bench_function("Grid Dijkstra (BinaryHeap)", |b| {
+        b.iter(|| {
+            let mut dist = vec![usize::MAX; SIZE * SIZE];
+            let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+            dist[0] = 0;
+            while let Some(Reverse((d, i))) = heap.pop() {
+                if d > dist[i] {
+                    continue;
+                }
+                for j in neighbors(i) {
+                    if d + weights[j] < dist[j] {
+                        dist[j] = d + weights[j];
+                        heap.push(Reverse((dist[j], j)));
+                    }
+                }
+            }
+            black_box(dist[SIZE * SIZE - 1])
+        })
+    });
+    c.bench_function("Grid Dijkstra (BucketQueue)", |b| {
+        b.iter(|| {
+            let mut dist = vec![usize::MAX; SIZE * SIZE];
+            let mut queue = BucketQueue::new();
+            queue.push(0, 0);
+            dist[0] = 0;
+            while let Some((d, i)) = queue.pop() {
+                if d > dist[i] {
+                    continue;
+                }
+                for j in neighbors(i) {
+                    if d + weights[j] < dist[j] {
+                        dist[j] = d + weights[j];
+                        queue.push(dist[j], j);
+                    }
+                }
+            }
+            black_box(dist[SIZE * SIZE - 1])
+        })
+    });
+}
+
+/// The benchmark configuration, after building the thread pool with `$AOC_THREADS` threads if it
+/// is set, so that runs on machines with different numbers of cores can be compared.
+fn criterion() -> Criterion {
+    if let Some(threads) = threads::from_env() {
+        aoc2025::configure_threads(threads).unwrap_or_else(|e| panic!("{e}"));
+    }
+    Criterion::default()
+        .sample_size(500)
+        .measurement_time(Duration::from_secs(10))
+        .nresamples(100_000)
+        .configure_from_args()
+}
 
 criterion_group! {
     name = benches;
-    config = Criterion::default()
-                 .sample_size(500)
-                 .measurement_time(Duration::from_secs(10))
-                 .nresamples(100_000)
-                 .configure_from_args();
-    targets = day01_bench, day02_bench, day03_bench, day04_bench, day05_bench, day06_bench,
-              day07_bench, day08_bench, day09_bench, day10_bench, day11_bench, day12_bench
+    config = criterion();
+    targets = days_bench, day12_pruning_bench, bitset_bench, parse_bench, pq_bench
 }
 criterion_main!(benches);
@@ -0,0 +1,19 @@
+use aoc2025::registry;
+
+/// Inputs up to this many bytes are also solved, not just parsed. Longer ones can take too long
+/// to solve for the fuzzer to make progress.
+const MAX_SOLVE_LEN: usize = 64;
+
+/// Parses `data` as an input for day `day`, and solves both parts if the input is small. Any
+/// panic is reported by the fuzzer as a crash.
+pub fn run(day: u8, data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let entry = registry::get(day).expect("no such day");
+    let puzzle = (entry.create)(input);
+    if data.len() <= MAX_SOLVE_LEN {
+        puzzle.solve_part_1();
+        puzzle.solve_part_2();
+    }
+}
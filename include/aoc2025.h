@@ -0,0 +1,44 @@
+/* The C interface to the aoc2025 solvers, built with `cargo build --release --features ffi`. */
+
+#ifndef AOC2025_H
+#define AOC2025_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The answer was written to the output buffer. */
+#define AOC2025_OK 0
+/* No day with the given number is solved. */
+#define AOC2025_UNKNOWN_DAY 1
+/* The part is neither 1 nor 2. */
+#define AOC2025_UNKNOWN_PART 2
+/* The input is not valid UTF-8. */
+#define AOC2025_INVALID_UTF8 3
+/* The day could not parse or solve the input. The output buffer holds the reason. */
+#define AOC2025_INVALID_INPUT 4
+/* The answer does not fit in the output buffer, whose length was set to the size it needs. */
+#define AOC2025_BUFFER_TOO_SMALL 5
+/* A pointer that must not be null was null. */
+#define AOC2025_NULL_POINTER 6
+
+/*
+ * Solves `part` (1 or 2) of `day` on the `input_len` bytes of UTF-8 at `input_ptr`, returning one
+ * of the status codes above.
+ *
+ * On entry `*out_len` is the capacity of `out_buf`. The answer is written to `out_buf` as UTF-8
+ * without a trailing NUL, and `*out_len` is set to its length. An answer that does not fit is not
+ * written; `*out_len` is set to the length it needs instead. For AOC2025_INVALID_INPUT the reason
+ * is written the same way, cut short to fit. For the other errors `*out_len` is set to 0.
+ */
+int32_t aoc2025_solve(uint8_t day, uint8_t part, const uint8_t *input_ptr, size_t input_len,
+                      uint8_t *out_buf, size_t *out_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
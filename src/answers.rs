@@ -0,0 +1,233 @@
+use crate::registry;
+use crate::runner::RunResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The last day of Advent of Code 2025, which has no part 2.
+pub const FINAL_DAY: u8 = 12;
+
+const HEADER: &str = "# The answers for the inputs in resources/tests, checked by \
+                      registry::tests::test_solve_answers.\n";
+
+/// Returns the path of the answers file, `resources/answers.toml` in the crate root.
+pub fn default_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/answers.toml")
+}
+
+/// The known answers for one day. A part with no answer listed is not checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayAnswers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+    /// The [`run_log::input_hash`](crate::run_log::input_hash) of the input the answers are for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+    /// When the answers were recorded, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<u64>,
+}
+
+impl DayAnswers {
+    /// Returns the known answer to `part`, if there is one.
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether these answers may be for the input with hash `input_hash`: they are unless
+    /// they were recorded for an input with a different hash.
+    pub fn is_for_input(&self, input_hash: &str) -> bool {
+        self.input_hash
+            .as_ref()
+            .is_none_or(|hash| hash == input_hash)
+    }
+
+    /// Returns a message for each part of `result` that differs from its known answer.
+    pub fn mismatches(&self, result: &RunResult) -> Vec<String> {
+        [(1, &result.part1), (2, &result.part2)]
+            .into_iter()
+            .filter_map(|(part, actual)| match self.part(part) {
+                Some(expected) if expected != actual => {
+                    Some(format!("part{part}: expected {expected}, got {actual}"))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (part, answer) in [("part1", &self.part1), ("part2", &self.part2)] {
+            if let Some(answer) = answer
+                && (answer.is_empty() || answer.contains('\n'))
+            {
+                return Err(format!("{part} must be a non-empty single line"));
+            }
+        }
+        let is_hex = |hash: &str| hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        if let Some(hash) = &self.input_hash
+            && !(hash.len() == 16 && is_hex(hash))
+        {
+            return Err(format!(
+                "input_hash must be 16 lowercase hex digits, found `{hash}`"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The answers file: the known answers for each day, in tables named `day01`, `day02`, ....
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnswerFile {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+impl AnswerFile {
+    /// Parses the contents of an answers file, checking that every table is a solved day and
+    /// every value is well formed.
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let tables: BTreeMap<String, DayAnswers> = toml::from_str(text)?;
+        let mut days = BTreeMap::new();
+        for (key, answers) in tables {
+            let day = key
+                .strip_prefix("day")
+                .filter(|n| n.len() == 2)
+                .and_then(|n| n.parse().ok())
+                .filter(|&day| registry::get(day).is_some())
+                .ok_or_else(|| format!("[{key}] is not a solved day"))?;
+            answers.validate().map_err(|e| format!("[{key}] {e}"))?;
+            days.insert(day, answers);
+        }
+        Ok(AnswerFile { days })
+    }
+
+    /// Reads the answers file at `path`. A file that does not exist yet has no answers.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()).into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(AnswerFile::default()),
+            Err(e) => Err(format!("cannot read {}: {e}", path.display()).into()),
+        }
+    }
+
+    /// Writes the answers to `path`, replacing whatever was there.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let tables: BTreeMap<String, &DayAnswers> = self
+            .days
+            .iter()
+            .map(|(day, answers)| (format!("day{day:02}"), answers))
+            .collect();
+        fs::write(path, format!("{HEADER}\n{}", toml::to_string(&tables)?))?;
+        Ok(())
+    }
+
+    /// Returns the known answers for `day`, if any are recorded.
+    pub fn get(&self, day: u8) -> Option<&DayAnswers> {
+        self.days.get(&day)
+    }
+
+    /// Returns whether every part of `day` has a known answer.
+    pub fn is_solved(&self, day: u8) -> bool {
+        self.get(day).is_some_and(|answers| {
+            answers.part1.is_some() && (answers.part2.is_some() || day == FINAL_DAY)
+        })
+    }
+
+    /// Records the answers in `result` as the known answers for its day, on the input with hash
+    /// `input_hash`, replacing any recorded before. The final day's part 2 is not recorded.
+    pub fn record(&mut self, result: &RunResult, input_hash: &str) {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let part2 = (result.day != FINAL_DAY).then(|| result.part2.clone());
+        self.days.insert(
+            result.day,
+            DayAnswers {
+                part1: Some(result.part1.clone()),
+                part2,
+                input_hash: Some(input_hash.to_string()),
+                recorded_at: Some(recorded_at),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(day: u8, part1: &str, part2: &str) -> RunResult {
+        RunResult {
+            day,
+            part1: part1.into(),
+            part2: part2.into(),
+            visualization: None,
+        }
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let answers = AnswerFile::load(&default_path()).unwrap();
+        assert!(
+            registry::DAYS
+                .iter()
+                .all(|day| answers.is_solved(day.number))
+        );
+        let day01 = answers.get(1).unwrap();
+        assert_eq!(day01.part(1), Some("1118"));
+        assert_eq!(
+            day01.mismatches(&result(1, "1118", "0")),
+            ["part2: expected 6289, got 0"]
+        );
+        assert!(
+            DayAnswers::default()
+                .mismatches(&result(1, "1", "2"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let error = |text: &str| AnswerFile::parse(text).unwrap_err().to_string();
+        assert_eq!(
+            error("[day13]\npart1 = \"1\""),
+            "[day13] is not a solved day"
+        );
+        assert_eq!(error("[day1]\npart1 = \"1\""), "[day1] is not a solved day");
+        assert_eq!(
+            error("[day01]\npart1 = \"\""),
+            "[day01] part1 must be a non-empty single line"
+        );
+        assert_eq!(
+            error("[day01]\ninput_hash = \"ABC\""),
+            "[day01] input_hash must be 16 lowercase hex digits, found `ABC`"
+        );
+    }
+
+    #[test]
+    fn test_record_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.toml");
+        let mut answers = AnswerFile::load(&path).unwrap();
+        assert_eq!(answers, AnswerFile::default());
+        answers.record(&result(3, "357", "3121910778619"), "0123456789abcdef");
+        answers.record(&result(12, "2", "Day 12 has no part 2"), "fedcba9876543210");
+        assert!(answers.is_solved(3) && answers.is_solved(12) && !answers.is_solved(1));
+        assert_eq!(answers.get(12).unwrap().part2, None);
+        assert!(answers.get(3).unwrap().is_for_input("0123456789abcdef"));
+        assert!(!answers.get(3).unwrap().is_for_input("fedcba9876543210"));
+        answers.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(HEADER), "{text}");
+        assert_eq!(AnswerFile::load(&path).unwrap(), answers);
+    }
+}
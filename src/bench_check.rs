@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The median time of one benchmark in a saved baseline and in the latest run, in nanoseconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    /// The benchmark's path under the criterion directory, such as `Day 08/Part 1`.
+    pub id: String,
+    pub baseline: f64,
+    pub latest: f64,
+}
+
+impl Comparison {
+    /// Returns how much slower the latest run is than the baseline, in percent. Speedups are
+    /// negative.
+    pub fn change_percent(&self) -> f64 {
+        (self.latest / self.baseline - 1.0) * 100.0
+    }
+}
+
+/// Compares the latest run of every benchmark under `criterion_dir` (usually `target/criterion`)
+/// with the baseline saved as `baseline`, in order of id. Benchmarks missing either are skipped.
+pub fn compare(criterion_dir: &Path, baseline: &str) -> Result<Vec<Comparison>, Box<dyn Error>> {
+    let mut comparisons = Vec::new();
+    for (id, dir) in benchmarks(criterion_dir)? {
+        let saved = dir.join(baseline).join("estimates.json");
+        if saved.is_file() {
+            comparisons.push(Comparison {
+                id,
+                baseline: read_median(&saved)?,
+                latest: read_median(&dir.join("new").join("estimates.json"))?,
+            });
+        }
+    }
+    Ok(comparisons)
+}
+
+/// Returns the median time of the latest run of every benchmark under `criterion_dir`, in
+/// nanoseconds, keyed by id. There are none if the benchmarks have never been run.
+pub fn latest_medians(criterion_dir: &Path) -> Result<BTreeMap<String, f64>, Box<dyn Error>> {
+    if !criterion_dir.is_dir() {
+        return Ok(BTreeMap::new());
+    }
+    benchmarks(criterion_dir)?
+        .into_iter()
+        .map(|(id, dir)| Ok((id, read_median(&dir.join("new").join("estimates.json"))?)))
+        .collect()
+}
+
+/// Returns the id and directory of every benchmark under `criterion_dir` with a latest run, in
+/// order of id.
+fn benchmarks(criterion_dir: &Path) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let mut found = Vec::new();
+    collect(criterion_dir, criterion_dir, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+/// Returns the comparisons that got slower by more than `threshold_percent`.
+pub fn regressions(comparisons: &[Comparison], threshold_percent: f64) -> Vec<&Comparison> {
+    comparisons
+        .iter()
+        .filter(|c| c.change_percent() > threshold_percent)
+        .collect()
+}
+
+/// Criterion keeps each benchmark's results in a directory holding one subdirectory per baseline,
+/// with the latest run in `new`.
+fn collect(
+    root: &Path,
+    dir: &Path,
+    found: &mut Vec<(String, PathBuf)>,
+) -> Result<(), Box<dyn Error>> {
+    if dir.join("new").join("estimates.json").is_file() {
+        let id = dir.strip_prefix(root)?.to_string_lossy().replace('\\', "/");
+        found.push((id, dir.to_path_buf()));
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(root, &path, found)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_median(path: &Path) -> Result<f64, Box<dyn Error>> {
+    let estimates: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    estimates["median"]["point_estimate"]
+        .as_f64()
+        .ok_or_else(|| format!("{} has no median", path.display()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_estimate(root: &Path, id: &str, baseline: &str, median: f64) {
+        let dir = root.join(id).join(baseline);
+        fs::create_dir_all(&dir).unwrap();
+        let json = format!(
+            r#"{{"mean":{{"point_estimate":1.0}},"median":{{"confidence_interval":{{"confidence_level":0.95}},"point_estimate":{median}}}}}"#
+        );
+        fs::write(dir.join("estimates.json"), json).unwrap();
+    }
+
+    #[test]
+    fn test_compare() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_estimate(root, "Day 08/Part 1", "main", 100.0);
+        write_estimate(root, "Day 08/Part 1", "new", 130.0);
+        write_estimate(root, "Day 01/Part 2", "main", 200.0);
+        write_estimate(root, "Day 01/Part 2", "new", 190.0);
+        write_estimate(root, "Day 02/Part 1", "new", 50.0);
+        write_estimate(root, "Day 02/Part 1", "other", 10.0);
+        let comparisons = compare(root, "main").unwrap();
+        assert_eq!(
+            comparisons,
+            vec![
+                Comparison {
+                    id: "Day 01/Part 2".to_string(),
+                    baseline: 200.0,
+                    latest: 190.0,
+                },
+                Comparison {
+                    id: "Day 08/Part 1".to_string(),
+                    baseline: 100.0,
+                    latest: 130.0,
+                },
+            ]
+        );
+        assert!((comparisons[0].change_percent() + 5.0).abs() < 1e-9);
+        let slower = regressions(&comparisons, 10.0);
+        assert_eq!(slower.len(), 1);
+        assert_eq!(slower[0].id, "Day 08/Part 1");
+        assert!(regressions(&comparisons, 35.0).is_empty());
+    }
+
+    #[test]
+    fn test_latest_medians() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert!(latest_medians(&root.join("criterion")).unwrap().is_empty());
+        write_estimate(root, "Day 01/Part 1", "new", 120.0);
+        write_estimate(root, "Day 01/Part 1", "main", 100.0);
+        write_estimate(root, "Day 01/Part 2", "main", 100.0);
+        let medians = latest_medians(root).unwrap();
+        assert_eq!(
+            medians,
+            BTreeMap::from([("Day 01/Part 1".to_string(), 120.0)])
+        );
+    }
+
+    #[test]
+    fn test_malformed_estimates() {
+        let dir = tempfile::tempdir().unwrap();
+        write_estimate(dir.path(), "a", "main", 1.0);
+        let latest = dir.path().join("a/new");
+        fs::create_dir_all(&latest).unwrap();
+        fs::write(latest.join("estimates.json"), r#"{"mean":{}}"#).unwrap();
+        let error = compare(dir.path(), "main").unwrap_err().to_string();
+        assert!(error.ends_with("has no median"), "{error}");
+    }
+}
@@ -0,0 +1,84 @@
+use aoc2025::bench_check;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "\
+usage: bench-check save [--baseline NAME]
+       bench-check compare [--baseline NAME] [--threshold PERCENT] [--no-run]
+
+`save` runs the benchmarks and saves them as the baseline (default `main`). `compare` runs them
+again and fails if any median is more than the threshold (default 5) percent slower than the
+baseline. With --no-run it compares the results of the last run instead.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let option = |name: &str| {
+        args.iter().position(|arg| arg == name).map(|i| {
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("{name} requires a value"))
+        })
+    };
+    let baseline = option("--baseline").map_or("main", String::as_str);
+    let threshold: f64 = option("--threshold").map_or(5.0, |t| {
+        t.parse()
+            .unwrap_or_else(|_| panic!("--threshold expects a percentage, found `{t}`"))
+    });
+    match args.first().map(String::as_str) {
+        Some("save") => run_benchmarks("--save-baseline", baseline),
+        Some("compare") => {
+            if !args.iter().any(|arg| arg == "--no-run") {
+                run_benchmarks("--baseline", baseline);
+            }
+            compare(baseline, threshold)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the criterion benchmarks with `flag` set to `baseline`, exiting if they fail.
+fn run_benchmarks(flag: &str, baseline: &str) -> ExitCode {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .args(["bench", "--bench", "aoc_bench", "--", flag, baseline])
+        .status()
+        .expect("failed to run cargo bench");
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    ExitCode::SUCCESS
+}
+
+fn compare(baseline: &str, threshold: f64) -> ExitCode {
+    let target = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let comparisons = bench_check::compare(&Path::new(&target).join("criterion"), baseline)
+        .unwrap_or_else(|e| panic!("cannot read the benchmark results: {e}"));
+    if comparisons.is_empty() {
+        eprintln!("No benchmarks have both a `{baseline}` baseline and a latest run");
+        return ExitCode::FAILURE;
+    }
+    for c in &comparisons {
+        println!(
+            "{:<40} {:>14.0} ns -> {:>14.0} ns  {:>+7.2}%",
+            c.id,
+            c.baseline,
+            c.latest,
+            c.change_percent()
+        );
+    }
+    let regressions = bench_check::regressions(&comparisons, threshold);
+    if regressions.is_empty() {
+        println!("No benchmark regressed by more than {threshold}%");
+        return ExitCode::SUCCESS;
+    }
+    for c in &regressions {
+        eprintln!(
+            "{} regressed by {:.2}% (more than {threshold}%)",
+            c.id,
+            c.change_percent()
+        );
+    }
+    ExitCode::FAILURE
+}
@@ -0,0 +1,38 @@
+use aoc2025::{seed, testgen};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: generate-input DAY SIZE [--seed SEED]
+
+Prints a random but valid input for DAY to standard output. SIZE scales the input (see
+`testgen::generate` for what it counts on each day), and the same SEED always gives the same
+input. SEED defaults to $AOC_SEED, or 0 if that is unset.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let seed: u64 = match args.iter().position(|arg| arg == "--seed") {
+        Some(i) => {
+            let seed = args.get(i + 1).expect("--seed requires a value");
+            seed.parse()
+                .unwrap_or_else(|_| panic!("--seed expects a number, found `{seed}`"))
+        }
+        None => seed::from_env().unwrap_or(0),
+    };
+    let (Some(day), Some(size)) = (
+        args.first().and_then(|day| day.parse::<u8>().ok()),
+        args.get(1).and_then(|size| size.parse::<usize>().ok()),
+    ) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    match testgen::generate(day, size, seed) {
+        Some(input) => {
+            println!("{input}");
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("There is no input generator for day {day}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use aoc2025::examples::Examples;
+use aoc2025::input_fetcher::InputFetcher;
+use aoc2025::runner::InputSource;
+use aoc2025::viz_gui::{self, Options};
+use aoc2025::{day04, day07};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: viz DAY [--example] [--cell PIXELS] [--fps FRAMES]
+
+Opens a window animating DAY's solution on your input, or with --example on the example from the
+puzzle description: day 4's rolls peeling away round by round, or day 7's beams cascading down row
+by row. Space pauses and resumes, and Escape closes the window.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let option = |name: &str, default: usize| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map_or(default, |i| {
+                let value = args
+                    .get(i + 1)
+                    .unwrap_or_else(|| panic!("{name} requires a value"));
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{name} expects a number, found `{value}`"))
+            })
+    };
+    let defaults = Options::default();
+    let options = Options {
+        cell: option("--cell", defaults.cell),
+        frames_per_second: option("--fps", defaults.frames_per_second),
+    };
+    let Some(day) = args.first().and_then(|day| day.parse::<u8>().ok()) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    if day != 4 && day != 7 {
+        eprintln!("There is no animation for day {day}");
+        return ExitCode::FAILURE;
+    }
+    let input = if args.iter().any(|arg| arg == "--example") {
+        Examples.get_input(day)
+    } else {
+        InputFetcher::create().get_input(day)
+    }
+    .unwrap_or_else(|e| panic!("cannot read the input for day {day}: {e}"));
+    let title = format!("Advent of Code 2025, day {day}");
+    let shown = match day {
+        4 => viz_gui::show(&title, day04::Day::new(&input).animate(), &options),
+        _ => viz_gui::show(&title, day07::Day::new(&input).animate(), &options),
+    };
+    match shown {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
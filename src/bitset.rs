@@ -0,0 +1,365 @@
+use std::fmt;
+use std::ops::{ShlAssign, ShrAssign};
+
+/// A fixed-size set of the integers `0..len`, stored as bits in 64-bit words: bit `i` is bit
+/// `i % 64` of word `i / 64`. Bits past `len` in the last word are always clear, so whole words can
+/// be compared, counted and combined directly.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Creates an empty set with room for `len` bits.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Returns the number of bits, set or not.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The backing words, lowest bits first.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// The backing words, lowest bits first. Callers must leave the bits past `len` clear.
+    pub fn words_mut(&mut self) -> &mut [u64] {
+        &mut self.words
+    }
+
+    #[inline(always)]
+    pub fn test(&self, i: usize) -> bool {
+        debug_assert!(i < self.len);
+        self.words[i >> 6] >> (i & 63) & 1 == 1
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, i: usize) {
+        debug_assert!(i < self.len);
+        self.words[i >> 6] |= 1 << (i & 63);
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self, i: usize) {
+        debug_assert!(i < self.len);
+        self.words[i >> 6] &= !(1 << (i & 63));
+    }
+
+    /// Flips bit `i`.
+    #[inline(always)]
+    pub fn toggle(&mut self, i: usize) {
+        debug_assert!(i < self.len);
+        self.words[i >> 6] ^= 1 << (i & 63);
+    }
+
+    /// Sets bit `i`, returning whether it was clear before.
+    #[inline(always)]
+    pub fn insert(&mut self, i: usize) -> bool {
+        let was_clear = !self.test(i);
+        self.set(i);
+        was_clear
+    }
+
+    /// Clears every bit.
+    pub fn clear_all(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns whether no bit is set in both sets, which must have the same length.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// Sets every bit that is set in `other`, which must have the same length.
+    pub fn union_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// Clears every bit that is clear in `other`, which must have the same length.
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    /// Flips every bit that is set in `other`, which must have the same length, leaving the
+    /// symmetric difference of the two sets.
+    pub fn xor_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+
+    /// Returns the set bits in increasing order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// Clears the bits past `len` in the last word.
+    fn trim(&mut self) {
+        if !self.len.is_multiple_of(64) {
+            *self.words.last_mut().unwrap() &= (1 << (self.len % 64)) - 1;
+        }
+    }
+}
+
+/// Moves every bit `i` to `i + shift`, dropping the bits moved past the end.
+impl ShlAssign<usize> for BitSet {
+    fn shl_assign(&mut self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        let n = self.words.len();
+        for i in (0..n).rev() {
+            let lo = i.checked_sub(words).map_or(0, |j| self.words[j]);
+            let carry = match i.checked_sub(words + 1) {
+                Some(j) if bits > 0 => self.words[j] >> (64 - bits),
+                _ => 0,
+            };
+            self.words[i] = lo << bits | carry;
+        }
+        self.trim();
+    }
+}
+
+/// Moves every bit `i` to `i - shift`, dropping the bits moved past the start.
+impl ShrAssign<usize> for BitSet {
+    fn shr_assign(&mut self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        let n = self.words.len();
+        for i in 0..n {
+            let hi = self.words.get(i + words).copied().unwrap_or(0);
+            let carry = match self.words.get(i + words + 1) {
+                Some(&w) if bits > 0 => w << (64 - bits),
+                _ => 0,
+            };
+            self.words[i] = hi >> bits | carry;
+        }
+    }
+}
+
+/// A `width` × `height` grid of bits, stored row by row with each row starting on a new word, so
+/// that rows can be read and combined as whole words.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// Creates a grid with every bit clear.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Self {
+            bits: BitSet::new(words_per_row * 64 * height),
+            width,
+            height,
+            words_per_row,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline(always)]
+    fn index(&self, row: usize, col: usize) -> usize {
+        debug_assert!(row < self.height && col < self.width);
+        row * self.words_per_row * 64 + col
+    }
+
+    /// Returns whether the bit at `(row, col)` is set. Positions outside the grid are clear.
+    #[inline(always)]
+    pub fn test(&self, row: isize, col: isize) -> bool {
+        (0..self.height as isize).contains(&row)
+            && (0..self.width as isize).contains(&col)
+            && self.bits.test(self.index(row as usize, col as usize))
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, row: usize, col: usize) {
+        let i = self.index(row, col);
+        self.bits.set(i);
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self, row: usize, col: usize) {
+        let i = self.index(row, col);
+        self.bits.clear(i);
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// The words of a row, lowest columns first. Bits past `width` are clear.
+    pub fn row(&self, row: usize) -> &[u64] {
+        let start = row * self.words_per_row;
+        &self.bits.words()[start..start + self.words_per_row]
+    }
+
+    /// Returns the positions of the set bits as `(row, col)`, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let row_bits = self.words_per_row * 64;
+        self.bits
+            .iter_ones()
+            .map(move |i| (i / row_bits, i % row_bits))
+    }
+}
+
+/// Draws the grid one character per bit, `#` if it is set and `.` if not, and a line per row.
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.height as isize {
+            let line: String = (0..self.width as isize)
+                .map(|col| if self.test(row, col) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn from_bools(bools: &[bool]) -> BitSet {
+        let mut set = BitSet::new(bools.len());
+        for (i, _) in bools.iter().enumerate().filter(|(_, b)| **b) {
+            set.set(i);
+        }
+        set
+    }
+
+    fn to_bools(set: &BitSet) -> Vec<bool> {
+        (0..set.len()).map(|i| set.test(i)).collect()
+    }
+
+    #[test]
+    fn test_set_and_clear() {
+        let mut set = BitSet::new(130);
+        assert_eq!(set.words().len(), 3);
+        assert!(set.insert(0));
+        assert!(set.insert(129));
+        assert!(!set.insert(129));
+        set.set(64);
+        assert_eq!(set.count_ones(), 3);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+        set.clear(64);
+        assert!(!set.test(64) && set.test(129));
+        set.clear_all();
+        assert_eq!(set.count_ones(), 0);
+        assert!(BitSet::new(0).is_empty());
+    }
+
+    #[test]
+    fn test_combining() {
+        let a = from_bools(&[true, true, false, false]);
+        let b = from_bools(&[false, true, true, false]);
+        assert!(!a.is_disjoint(&b));
+        assert!(a.is_disjoint(&from_bools(&[false, false, true, true])));
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(to_bools(&union), vec![true, true, true, false]);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(to_bools(&intersection), vec![false, true, false, false]);
+        let mut difference = a;
+        difference.xor_with(&b);
+        assert_eq!(to_bools(&difference), vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut set = BitSet::new(130);
+        set.toggle(0);
+        set.toggle(64);
+        set.toggle(129);
+        assert!(set.test(64) && set.test(129) && !set.test(63));
+        set.toggle(64);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![0, 129]);
+    }
+
+    /// Compares shifts against shifting a vector of booleans.
+    #[test]
+    fn test_shifts_match_bools() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..500 {
+            let len = rng.random_range(0..300);
+            let bools: Vec<bool> = (0..len).map(|_| rng.random_bool(0.5)).collect();
+            let shift = rng.random_range(0..len + 70);
+            let mut left = from_bools(&bools);
+            left <<= shift;
+            let expected: Vec<bool> = (0..len).map(|i| i >= shift && bools[i - shift]).collect();
+            assert_eq!(to_bools(&left), expected, "{bools:?} << {shift}");
+            assert_eq!(left.count_ones(), expected.iter().filter(|&&b| b).count());
+            let mut right = from_bools(&bools);
+            right >>= shift;
+            let expected: Vec<bool> = (0..len)
+                .map(|i| i + shift < len && bools[i + shift])
+                .collect();
+            assert_eq!(to_bools(&right), expected, "{bools:?} >> {shift}");
+        }
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(70, 3);
+        grid.set(0, 0);
+        grid.set(1, 69);
+        grid.set(2, 64);
+        assert_eq!((grid.width(), grid.height()), (70, 3));
+        assert!(grid.test(1, 69) && !grid.test(1, 68));
+        assert!(!grid.test(-1, 0) && !grid.test(0, -1) && !grid.test(3, 0) && !grid.test(0, 70));
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(grid.row(1), &[0, 1 << 5]);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 69), (2, 64)]
+        );
+        grid.clear(1, 69);
+        assert_eq!(grid.row(1), &[0, 0]);
+    }
+
+    #[test]
+    fn test_bit_grid_display() {
+        let mut grid = BitGrid::new(3, 2);
+        grid.set(0, 1);
+        grid.set(1, 2);
+        assert_eq!(grid.to_string(), ".#.\n..#\n");
+    }
+}
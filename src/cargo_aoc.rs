@@ -0,0 +1,73 @@
+use crate::puzzle::Puzzle;
+use crate::registry;
+
+/// Builds `day`'s puzzle from `input`, for the generators that [`cargo_aoc_days!`] defines.
+///
+/// # Panics
+///
+/// If `day` is not solved, or its input is malformed.
+///
+/// [`cargo_aoc_days!`]: crate::cargo_aoc_days
+pub fn generate(day: u8, input: &str) -> Box<dyn Puzzle> {
+    let entry = registry::get(day).unwrap_or_else(|| panic!("day {day} is not solved"));
+    (entry.create)(input)
+}
+
+/// Defines a module for each listed day holding a cargo-aoc generator and part solvers backed by
+/// this crate, so a crate built with `cargo aoc` can run these solutions unchanged. The invoking
+/// crate must depend on `aoc-runner` and `aoc-runner-derive`, like any cargo-aoc crate:
+///
+/// ```ignore
+/// aoc2025::cargo_aoc_days! {
+///     day1 => 1,
+///     day2 => 2,
+/// }
+///
+/// aoc_runner_derive::aoc_lib! { year = 2025 }
+/// ```
+#[macro_export]
+macro_rules! cargo_aoc_days {
+    ($($day:ident => $number:literal),* $(,)?) => {
+        $(
+            pub mod $day {
+                use $crate::puzzle::Puzzle;
+
+                #[aoc_runner_derive::aoc_generator($day)]
+                pub fn generator(input: &str) -> Box<dyn Puzzle> {
+                    $crate::cargo_aoc::generate($number, input)
+                }
+
+                #[aoc_runner_derive::aoc($day, part1)]
+                #[allow(clippy::borrowed_box)]
+                pub fn part1(puzzle: &Box<dyn Puzzle>) -> String {
+                    puzzle.solve_part_1()
+                }
+
+                #[aoc_runner_derive::aoc($day, part2)]
+                #[allow(clippy::borrowed_box)]
+                pub fn part2(puzzle: &Box<dyn Puzzle>) -> String {
+                    puzzle.solve_part_2()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn test_generate() {
+        let puzzle = generate(1, examples::DAY01);
+        assert_eq!(puzzle.solve_part_1(), "3");
+        assert_eq!(puzzle.solve_part_2(), "6");
+    }
+
+    #[test]
+    #[should_panic(expected = "day 13 is not solved")]
+    fn test_generate_unknown_day() {
+        generate(13, "");
+    }
+}
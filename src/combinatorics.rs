@@ -0,0 +1,217 @@
+/// Returns every way to choose `k` of `items`, keeping their order. Choices come in lexicographic
+/// order of the positions chosen, so sorted items give sorted choices. Choosing 0 items gives one
+/// empty choice, and choosing more than there are gives none.
+pub fn combinations_k<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        done: k > items.len(),
+    }
+}
+
+/// The iterator returned by [`combinations_k`].
+#[derive(Clone, Debug)]
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.done {
+            return None;
+        }
+        let choice = pick(self.items, &self.indices);
+        let (n, k) = (self.items.len(), self.indices.len());
+        // Advance the last position that can still move right, and pack the later ones after it.
+        match (0..k).rev().find(|&i| self.indices[i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(choice)
+    }
+}
+
+/// Returns every ordering of `items`, in lexicographic order of their positions. Equal items are
+/// still told apart, so there are always `n!` orderings.
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        indices: (0..items.len()).collect(),
+        done: false,
+    }
+}
+
+/// The iterator returned by [`permutations`].
+#[derive(Clone, Debug)]
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for Permutations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.done {
+            return None;
+        }
+        let ordering = pick(self.items, &self.indices);
+        let indices = &mut self.indices;
+        // Step to the next permutation: find the longest decreasing suffix, swap the position
+        // before it with the least larger one in the suffix, and reverse the suffix.
+        match (1..indices.len())
+            .rev()
+            .find(|&i| indices[i - 1] < indices[i])
+        {
+            Some(i) => {
+                let j = (i..indices.len())
+                    .rev()
+                    .find(|&j| indices[j] > indices[i - 1])
+                    .unwrap();
+                indices.swap(i - 1, j);
+                indices[i..].reverse();
+            }
+            None => self.done = true,
+        }
+        Some(ordering)
+    }
+}
+
+/// Returns every way to pick one item from each list, varying the last list fastest. No lists
+/// give one empty pick, and an empty list gives none.
+pub fn cartesian_product<T: Clone>(lists: &[Vec<T>]) -> CartesianProduct<'_, T> {
+    CartesianProduct {
+        lists,
+        indices: vec![0; lists.len()],
+        done: lists.iter().any(Vec::is_empty),
+    }
+}
+
+/// The iterator returned by [`cartesian_product`].
+#[derive(Clone, Debug)]
+pub struct CartesianProduct<'a, T> {
+    lists: &'a [Vec<T>],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for CartesianProduct<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.done {
+            return None;
+        }
+        let picks = self
+            .lists
+            .iter()
+            .zip(&self.indices)
+            .map(|(list, &i)| list[i].clone())
+            .collect();
+        // Count up like an odometer, with the last list as the lowest digit.
+        self.done = true;
+        for (index, list) in self.indices.iter_mut().zip(self.lists).rev() {
+            *index += 1;
+            if *index < list.len() {
+                self.done = false;
+                break;
+            }
+            *index = 0;
+        }
+        Some(picks)
+    }
+}
+
+fn pick<T: Clone>(items: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|&i| items[i].clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binomial(n: usize, k: usize) -> usize {
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn test_combinations_k() {
+        let items = ['a', 'b', 'c', 'd'];
+        let pairs: Vec<String> = combinations_k(&items, 2)
+            .map(|c| c.into_iter().collect())
+            .collect();
+        assert_eq!(pairs, vec!["ab", "ac", "ad", "bc", "bd", "cd"]);
+        assert_eq!(
+            combinations_k(&items, 0).collect::<Vec<Vec<char>>>(),
+            vec![Vec::<char>::new()]
+        );
+        assert_eq!(combinations_k(&items, 5).count(), 0);
+        assert_eq!(combinations_k::<u8>(&[], 0).count(), 1);
+        for n in 0..9 {
+            let items: Vec<usize> = (0..n).collect();
+            for k in 0..=n {
+                let all: Vec<Vec<usize>> = combinations_k(&items, k).collect();
+                assert_eq!(all.len(), binomial(n, k));
+                assert!(all.iter().all(|c| c.is_sorted() && c.len() == k));
+                assert!(all.is_sorted_by(|a, b| a < b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutations() {
+        let orderings: Vec<String> = permutations(&['x', 'y', 'z'])
+            .map(|p| p.into_iter().collect())
+            .collect();
+        assert_eq!(orderings, vec!["xyz", "xzy", "yxz", "yzx", "zxy", "zyx"]);
+        assert_eq!(
+            permutations::<u8>(&[]).collect::<Vec<Vec<u8>>>(),
+            vec![Vec::<u8>::new()]
+        );
+        assert_eq!(permutations(&[1, 1]).count(), 2);
+        let mut factorial = 1;
+        for n in 1..8 {
+            factorial *= n;
+            let items: Vec<usize> = (0..n).collect();
+            let all: Vec<Vec<usize>> = permutations(&items).collect();
+            assert_eq!(all.len(), factorial);
+            assert!(all.is_sorted_by(|a, b| a < b));
+            for p in &all {
+                let mut sorted = p.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, items);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let lists = vec![vec![1, 2], vec![10], vec![100, 200, 300]];
+        let sums: Vec<i32> = cartesian_product(&lists).map(|p| p.iter().sum()).collect();
+        assert_eq!(sums, vec![111, 211, 311, 112, 212, 312]);
+        assert_eq!(
+            cartesian_product::<u8>(&[]).collect::<Vec<Vec<u8>>>(),
+            vec![Vec::<u8>::new()]
+        );
+        assert_eq!(cartesian_product(&[vec![1], vec![]]).count(), 0);
+        let mut nested = Vec::new();
+        for a in &lists[0] {
+            for b in &lists[1] {
+                for c in &lists[2] {
+                    nested.push(vec![*a, *b, *c]);
+                }
+            }
+        }
+        assert_eq!(cartesian_product(&lists).collect::<Vec<_>>(), nested);
+    }
+}
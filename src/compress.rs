@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Compresses `values` to their ranks: returns the distinct values in increasing order, and a map
+/// from each value to its index in that order.
+///
+/// ```
+/// use aoc2025::compress::compress;
+///
+/// let (sorted, index) = compress([30, -5, 30, 7]);
+/// assert_eq!(sorted, [-5, 7, 30]);
+/// assert_eq!(index[&30], 2);
+/// ```
+///
+/// Time complexity: O(N log N)
+/// Auxiliary space complexity: O(N)
+pub fn compress<T: Ord + Hash + Clone>(
+    values: impl IntoIterator<Item = T>,
+) -> (Vec<T>, HashMap<T, usize>) {
+    let mut sorted: Vec<T> = values.into_iter().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let index = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (value.clone(), i))
+        .collect();
+    (sorted, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_compress() {
+        let (sorted, index) = compress([30, -5, 1_000_000_000_000i64, 30, 7]);
+        assert_eq!(sorted, vec![-5, 7, 30, 1_000_000_000_000]);
+        assert_eq!(index[&-5], 0);
+        assert_eq!(index[&1_000_000_000_000], 3);
+        assert_eq!(index.get(&8), None);
+        let (sorted, index) = compress(["pear", "apple", "fig"].map(String::from));
+        assert_eq!(sorted, vec!["apple", "fig", "pear"]);
+        assert_eq!(index["pear"], 2);
+        let (sorted, index) = compress(Vec::<u8>::new());
+        assert!(sorted.is_empty() && index.is_empty());
+    }
+
+    /// Checks that compression keeps exactly the order of the values.
+    #[test]
+    fn test_compress_preserves_order() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..200 {
+            let values: Vec<i32> = (0..rng.random_range(0..50))
+                .map(|_| rng.random_range(-20..20))
+                .collect();
+            let (sorted, index) = compress(values.iter().copied());
+            assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+            for &a in &values {
+                assert_eq!(sorted[index[&a]], a);
+                for &b in &values {
+                    assert_eq!(index[&a].cmp(&index[&b]), a.cmp(&b));
+                }
+            }
+        }
+    }
+}
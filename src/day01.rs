@@ -63,53 +63,11 @@ impl Day {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY01 as EXAMPLE;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            L68\n\
-            L30\n\
-            R48\n\
-            L5\n\
-            R60\n\
-            L55\n\
-            L1\n\
-            L99\n\
-            R14\n\
-            L82";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "3");
-    }
-
-    #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/01")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "1118");
-    }
-
-    #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            L68\n\
-            L30\n\
-            R48\n\
-            L5\n\
-            R60\n\
-            L55\n\
-            L1\n\
-            L99\n\
-            R14\n\
-            L82";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "6");
-    }
-
-    #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/01")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "6289");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "3",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "6",
     }
 }
@@ -1,7 +1,6 @@
+use crate::math::{ceil_div, divisors, floor_div, mobius, num_digits, pow10};
+use crate::parse::{Cursor, ParseError};
 use crate::puzzle::Puzzle;
-use cached::proc_macro::cached;
-use divisors_fixed::Divisors;
-use num::Integer;
 use std::cmp::{max, min};
 use std::ops::RangeInclusive;
 
@@ -39,70 +38,25 @@ impl Puzzle for Day {
     }
 }
 
-fn num_digits(n: u64) -> u32 {
-    if n == 0 { 1 } else { n.ilog10() + 1 }
-}
-
-#[cached]
-fn pow10(exp: u32) -> u128 {
-    10u128.pow(exp)
-}
-
-fn ceil_div<T: Integer>(a: T, b: T) -> T {
-    Integer::div_ceil(&a, &b)
-}
-
-fn floor_div<T: Integer>(a: T, b: T) -> T {
-    Integer::div_floor(&a, &b)
-}
-
-#[cached]
-fn divisors(n: u32) -> Vec<u32> {
-    n.divisors()
-}
-
-/// Möbius function μ(n) for n ≥ 0.
-///
-/// μ(0) = 0 (by convention here)
-/// μ(1) = 1
-/// μ(n) = 0 if n has a squared prime factor
-/// μ(n) = (-1)^k if n is a product of k distinct primes
-#[cached]
-fn mobius(mut n: u32) -> i32 {
-    if n == 0 {
-        return 0;
-    }
-    if n == 1 {
-        return 1;
-    }
-    let mut mu: i32 = 1;
-    let mut p: u32 = 2;
-    // Trial division up to sqrt(n)
-    while p * p <= n {
-        if n.is_multiple_of(p) {
-            let mut count = 0;
-            while n.is_multiple_of(p) {
-                n /= p;
-                count += 1;
-                if count > 1 {
-                    // Squared prime factor ⇒ μ(n) = 0
-                    return 0;
-                }
-            }
-            // Flip sign for each distinct prime factor.
-            mu = -mu;
-        }
-        p += if p == 2 { 1 } else { 2 }; // Check 2, then odd numbers only.
-    }
-    // If there is a prime factor > sqrt(original n), flip sign once more.
-    if n > 1 { -mu } else { mu }
-}
-
 fn calculate_multiplier(seed_len: u32, num_repeats: u32) -> u128 {
     (0..num_repeats).fold(0u128, |acc, i| acc + pow10(i * seed_len))
 }
 
-fn sum_doublets_in_range(start: u64, end: u64) -> u128 {
+/// Returns the sum of the doublets in `start..=end`: the numbers whose digits are some sequence
+/// written twice, like 6464 or 123123. For each half length, the doublets are the half times
+/// `10^len + 1`, so their sum is an arithmetic series.
+///
+/// ```
+/// use aoc2025::day02::sum_doublets_in_range;
+///
+/// assert_eq!(sum_doublets_in_range(11, 22), 11 + 22);
+/// assert_eq!(sum_doublets_in_range(95, 115), 99);
+/// assert_eq!(sum_doublets_in_range(1188511880, 1188511890), 1188511885);
+/// ```
+///
+/// Time complexity: O(log M) where M is `end`
+/// Auxiliary space complexity: O(1)
+pub fn sum_doublets_in_range(start: u64, end: u64) -> u128 {
     if end < 11 {
         return 0;
     }
@@ -125,7 +79,22 @@ fn sum_doublets_in_range(start: u64, end: u64) -> u128 {
 }
 
 const MAX_DIGITS: usize = 20; // because u64::MAX has 20 decimal digits
-fn sum_nonprimitives_in_range(start: u64, end: u64) -> u128 {
+
+/// Returns the sum of the non-primitive numbers in `start..=end`: the numbers whose digits are
+/// some sequence written two or more times, like 6464, 111 or 121212. Each is counted once, under
+/// its shortest repeating sequence, by Möbius inversion over the possible sequence lengths.
+///
+/// ```
+/// use aoc2025::day02::sum_nonprimitives_in_range;
+///
+/// assert_eq!(sum_nonprimitives_in_range(95, 115), 99 + 111);
+/// assert_eq!(sum_nonprimitives_in_range(824824821, 824824827), 824824824);
+/// assert_eq!(sum_nonprimitives_in_range(2121212118, 2121212124), 2121212121);
+/// ```
+///
+/// Time complexity: O(log^3 M) where M is `end`
+/// Auxiliary space complexity: O(1)
+pub fn sum_nonprimitives_in_range(start: u64, end: u64) -> u128 {
     if end < 11 {
         return 0;
     }
@@ -177,72 +146,90 @@ fn sum_nonprimitives_in_range(start: u64, end: u64) -> u128 {
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let ranges = input
-            .trim()
-            .split(',')
-            .map(|range| {
-                let mut parts = range.trim().split('-');
-                let start = parts.next().unwrap().parse::<u64>().unwrap();
-                let end = parts.next().unwrap().parse::<u64>().unwrap();
-                start..=end
-            })
-            .collect::<Vec<RangeInclusive<u64>>>();
-        Box::new(Day { ranges })
+        Box::new(Self::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self::try_new(input).unwrap_or_else(|e| panic!("Day 2: {e}"))
+    }
+
+    pub fn try_create(input: &str) -> Result<Box<dyn Puzzle>, ParseError> {
+        Ok(Box::new(Self::try_new(input)?))
+    }
+
+    /// Parses the comma-separated `start-end` ranges.
+    pub fn try_new(input: &str) -> Result<Self, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let mut ranges = Vec::new();
+        loop {
+            cursor.skip_whitespace();
+            let start = cursor.parse_u64()?;
+            cursor.expect_char('-')?;
+            let end = cursor.parse_u64()?;
+            ranges.push(start..=end);
+            cursor.skip_whitespace();
+            if !cursor.eat_char(',') {
+                break;
+            }
+        }
+        cursor.expect_end()?;
+        Ok(Day { ranges })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY02 as EXAMPLE;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            11-22,\
-            95-115,\
-            998-1012,\
-            1188511880-1188511890,\
-            222220-222224,\
-            1698522-1698528,\
-            446443-446449,\
-            38593856-38593862,\
-            565653-565659,\
-            824824821-824824827,\
-            2121212118-2121212124";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "1227775554");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "1227775554",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "4174379265",
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/02")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "28146997880");
+    fn test_concurrent_solves_agree() {
+        // Solves share no state, so solving on several threads at once gives the same answers as
+        // solving alone, whichever thread gets there first.
+        let inputs = [EXAMPLE, "1-18446744073709551615", "95-115,11-22,1-9"];
+        for input in inputs {
+            let puzzle = Day::create(input);
+            let expected = (puzzle.solve_part_1(), puzzle.solve_part_2());
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..8)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let puzzle = Day::create(input);
+                            (puzzle.solve_part_1(), puzzle.solve_part_2())
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    assert_eq!(handle.join().unwrap(), expected, "{input}");
+                }
+            });
+        }
     }
 
-    #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            11-22,\
-            95-115,\
-            998-1012,\
-            1188511880-1188511890,\
-            222220-222224,\
-            1698522-1698528,\
-            446443-446449,\
-            38593856-38593862,\
-            565653-565659,\
-            824824821-824824827,\
-            2121212118-2121212124";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "4174379265");
+    fn parse_error(input: &str) -> String {
+        Day::try_create(input).err().unwrap().to_string()
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/02")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "40028128307");
+    fn test_parse_errors() {
+        assert!(Day::try_create("11-22,\n95-115\n").is_ok());
+        assert_eq!(
+            parse_error("11-22,95_115"),
+            "line 1, column 9: expected `-`, found `_`"
+        );
+        assert_eq!(
+            parse_error("11-22,"),
+            "line 1, column 7: expected a number, found the end of the input"
+        );
+        assert_eq!(
+            parse_error("11-22 95-115"),
+            "line 1, column 7: unexpected `9`"
+        );
     }
 }
@@ -10,7 +10,7 @@ impl Puzzle for Day {
     ///
     /// Time complexity: O(n * m) where n is the number of banks, and m is the number of digits in
     /// each bank.
-    /// Auxiliary space complexity: O(m)
+    /// Auxiliary space complexity: O(1)
     fn solve_part_1(&self) -> String {
         self.banks
             .iter()
@@ -24,7 +24,7 @@ impl Puzzle for Day {
     ///
     /// Time complexity: O(n * m) where n is the number of banks, and m is the number of digits in
     /// each bank.
-    /// Auxiliary space complexity: O(m)
+    /// Auxiliary space complexity: O(1)
     fn solve_part_2(&self) -> String {
         self.banks
             .iter()
@@ -34,18 +34,42 @@ impl Puzzle for Day {
     }
 }
 
-fn max_subsequence(digits: &[u8], length: usize) -> u64 {
-    let mut deletions = digits.len() - length;
-    let mut stack: Vec<u8> = Vec::with_capacity(digits.len());
-    for &digit in digits {
-        while deletions > 0 && matches!(stack.last(), Some(&last) if last < digit) {
-            stack.pop();
-            deletions -= 1;
+/// Returns the largest number formed by `length` of `digits` kept in their original order. The
+/// digits kept so far form a stack, and a smaller digit is dropped from it whenever a larger one
+/// follows while enough digits remain to fill it up again. The stack is stored as the number its
+/// digits spell, so the top is its last decimal digit and nothing is allocated.
+///
+/// Panics if there are fewer than `length` digits.
+///
+/// ```
+/// use aoc2025::day03::max_subsequence;
+///
+/// let digits = [8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
+/// assert_eq!(max_subsequence(&digits, 2), 92);
+/// assert_eq!(max_subsequence(&digits, 12), 888911112111);
+/// ```
+///
+/// Time complexity: O(N)
+/// Auxiliary space complexity: O(1)
+pub fn max_subsequence(digits: &[u8], length: usize) -> u64 {
+    assert!(
+        length <= digits.len(),
+        "cannot keep {length} of {} digits",
+        digits.len()
+    );
+    let (mut kept, mut num_kept) = (0u64, 0usize);
+    for (i, &digit) in digits.iter().enumerate() {
+        let remaining = digits.len() - i;
+        while num_kept > 0 && num_kept + remaining > length && kept % 10 < u64::from(digit) {
+            kept /= 10;
+            num_kept -= 1;
+        }
+        if num_kept < length {
+            kept = kept * 10 + u64::from(digit);
+            num_kept += 1;
         }
-        stack.push(digit);
     }
-    stack.truncate(length);
-    stack.into_iter().fold(0u64, |acc, d| acc * 10 + d as u64)
+    kept
 }
 
 impl Day {
@@ -61,41 +85,47 @@ impl Day {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY03 as EXAMPLE;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            987654321111111\n\
-            811111111111119\n\
-            234234234234278\n\
-            818181911112111";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "357");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "357",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "3121910778619",
     }
 
-    #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/03")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "17034");
+    /// Returns the largest subsequence of `length` digits by trying every one.
+    fn reference_max_subsequence(digits: &[u8], length: usize) -> u64 {
+        (0u32..1 << digits.len())
+            .filter(|mask| mask.count_ones() as usize == length)
+            .map(|mask| {
+                (0..digits.len())
+                    .filter(|&i| mask & (1 << i) != 0)
+                    .fold(0u64, |acc, i| acc * 10 + u64::from(digits[i]))
+            })
+            .max()
+            .unwrap()
     }
 
     #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            987654321111111\n\
-            811111111111119\n\
-            234234234234278\n\
-            818181911112111";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "3121910778619");
+    fn test_max_subsequence_matches_reference() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..500 {
+            let digits: Vec<u8> = (0..rng.random_range(1..13usize))
+                .map(|_| rng.random_range(0..10u8))
+                .collect();
+            let length = rng.random_range(0..=digits.len());
+            assert_eq!(
+                max_subsequence(&digits, length),
+                reference_max_subsequence(&digits, length),
+                "{digits:?}, length {length}"
+            );
+        }
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/03")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "168798209663590");
+    #[should_panic(expected = "cannot keep 3 of 2 digits")]
+    fn test_max_subsequence_too_long() {
+        max_subsequence(&[1, 2], 3);
     }
 }
@@ -1,11 +1,12 @@
+use crate::bitset::BitGrid;
+use crate::dir::Dir8;
 use crate::puzzle::Puzzle;
-use itertools::iproduct;
+use crate::render::{Raster, Visualization, log_scale_color};
 use std::collections::VecDeque;
 
 pub struct Day {
-    grid: Vec<Vec<bool>>,
-    num_rows: usize,
-    num_cols: usize,
+    /// The occupied cells.
+    grid: BitGrid,
 }
 
 impl Puzzle for Day {
@@ -14,8 +15,9 @@ impl Puzzle for Day {
     /// Time complexity: O(M * N) where M is the number of rows and N is the number of columns
     /// Auxiliary space complexity: O(1)
     fn solve_part_1(&self) -> String {
-        iproduct!(0..self.num_rows, 0..self.num_cols)
-            .filter(|&(r, c)| self.grid[r][c] && self.count_neighbors(r, c) < MIN_NEIGHBORS)
+        self.grid
+            .iter_ones()
+            .filter(|&(r, c)| self.count_neighbors(r, c) < MIN_NEIGHBORS)
             .count()
             .to_string()
     }
@@ -31,105 +33,143 @@ impl Puzzle for Day {
     fn solve_part_2(&self) -> String {
         let mut neighbor_counts = self.build_neighbor_counts();
         let mut grid = self.grid.clone();
-        let mut in_queue = vec![vec![false; self.num_cols]; self.num_rows];
+        let mut in_queue = BitGrid::new(grid.width(), grid.height());
         let mut queue = VecDeque::<(usize, usize)>::new();
-        for (r, c) in iproduct!(0..self.num_rows, 0..self.num_cols) {
-            if grid[r][c] && neighbor_counts[r][c] < MIN_NEIGHBORS {
-                in_queue[r][c] = true;
+        for (r, c) in grid.iter_ones() {
+            if neighbor_counts[r][c] < MIN_NEIGHBORS {
+                in_queue.set(r, c);
                 queue.push_back((r, c));
             }
         }
         let mut removed = 0;
         while let Some((row, col)) = queue.pop_front() {
-            if !grid[row][col] {
+            if !grid.test(row as isize, col as isize) {
                 continue;
             }
-            grid[row][col] = false;
+            grid.clear(row, col);
             removed += 1;
-            for (dr, dc) in NEIGHBOR_DIRS {
+            for (dr, dc) in Dir8::offsets() {
                 let nr = row as isize + dr;
                 let nc = col as isize + dc;
-                if !self.in_bounds(nr, nc) {
+                if !grid.test(nr, nc) {
                     continue;
                 }
                 let ur = nr as usize;
                 let uc = nc as usize;
-                if !grid[ur][uc] {
-                    continue;
-                }
                 let count = &mut neighbor_counts[ur][uc];
                 if *count > 0 {
                     *count -= 1;
                 }
-                if *count < MIN_NEIGHBORS && !in_queue[ur][uc] {
-                    in_queue[ur][uc] = true;
+                if *count < MIN_NEIGHBORS && !in_queue.test(nr, nc) {
+                    in_queue.set(ur, uc);
                     queue.push_back((ur, uc));
                 }
             }
         }
         removed.to_string()
     }
+
+    /// Renders the grid with each cell colored by the round of peeling that removes it, from pale
+    /// yellow for the first to deep purple for the last, and the cells never removed in gray.
+    fn visualize(&self) -> Option<Visualization> {
+        self.raster().map(|raster| raster.svg(10.0))
+    }
+
+    /// Like [`Puzzle::visualize`], as a grid of cells. Each round of peeling is its own area, so
+    /// the rounds are outlined.
+    fn raster(&self) -> Option<Raster> {
+        self.animate().last()
+    }
 }
 
 const MIN_NEIGHBORS: u8 = 4;
 
-const NEIGHBOR_DIRS: &[(isize, isize); 8] = &[
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
-
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let grid: Vec<Vec<bool>> = input
-            .trim()
-            .lines()
-            .map(|line| {
-                line.trim()
-                    .chars()
-                    .map(|ch| match ch {
-                        '.' => false,
-                        '@' => true,
-                        _ => unreachable!(),
-                    })
-                    .collect()
-            })
-            .collect();
-        let num_rows = grid.len();
-        let num_cols = grid[0].len();
-        Box::new(Day {
-            grid,
-            num_rows,
-            num_cols,
-        })
+        Box::new(Day::new(input))
     }
 
-    fn in_bounds(&self, row: isize, col: isize) -> bool {
-        row >= 0 && row < self.num_rows as isize && col >= 0 && col < self.num_cols as isize
+    /// Parses the grid of rolls.
+    pub fn new(input: &str) -> Self {
+        let lines: Vec<&str> = input.trim().lines().map(str::trim).collect();
+        let mut grid = BitGrid::new(lines[0].len(), lines.len());
+        for (r, line) in lines.iter().enumerate() {
+            for (c, ch) in line.chars().enumerate() {
+                match ch {
+                    '.' => {}
+                    '@' => grid.set(r, c),
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Day { grid }
     }
 
     fn count_neighbors(&self, row: usize, col: usize) -> u8 {
-        NEIGHBOR_DIRS
-            .iter()
-            .filter(|(dr, dc)| {
-                let nr = row as isize + dr;
-                let nc = col as isize + dc;
-                self.in_bounds(nr, nc) && self.grid[nr as usize][nc as usize]
-            })
+        Dir8::offsets()
+            .filter(|(dr, dc)| self.grid.test(row as isize + dr, col as isize + dc))
             .count() as u8
     }
 
-    fn build_neighbor_counts(&self) -> Vec<Vec<u8>> {
-        let mut counts = vec![vec![0u8; self.num_cols]; self.num_rows];
-        for (r, c) in iproduct!(0..self.num_rows, 0..self.num_cols) {
-            if self.grid[r][c] {
-                counts[r][c] = self.count_neighbors(r, c);
+    /// Peels the grid in rounds, each removing every occupied cell with fewer than four occupied
+    /// neighbors at once, and returns the `(row, column)` of the cells each round removes, until
+    /// a round would remove none.
+    pub fn peel(&self) -> impl Iterator<Item = Vec<(usize, usize)>> + '_ {
+        let mut neighbor_counts = self.build_neighbor_counts();
+        let mut grid = self.grid.clone();
+        let mut frontier: Vec<(usize, usize)> = grid
+            .iter_ones()
+            .filter(|&(r, c)| neighbor_counts[r][c] < MIN_NEIGHBORS)
+            .collect();
+        std::iter::from_fn(move || {
+            if frontier.is_empty() {
+                return None;
             }
+            for &(r, c) in &frontier {
+                grid.clear(r, c);
+            }
+            let mut next = Vec::new();
+            for &(row, col) in &frontier {
+                for (dr, dc) in Dir8::offsets() {
+                    let (nr, nc) = (row as isize + dr, col as isize + dc);
+                    if !grid.test(nr, nc) {
+                        continue;
+                    }
+                    let count = &mut neighbor_counts[nr as usize][nc as usize];
+                    *count -= 1;
+                    // Each cell crosses the threshold once, so it joins one round.
+                    if *count == MIN_NEIGHBORS - 1 {
+                        next.push((nr as usize, nc as usize));
+                    }
+                }
+            }
+            Some(std::mem::replace(&mut frontier, next))
+        })
+    }
+
+    /// Returns a frame of the grid before peeling and after each round of [`Day::peel`], with the
+    /// cells colored as in [`Puzzle::visualize`].
+    pub fn animate(&self) -> impl Iterator<Item = Raster> + '_ {
+        let rounds = self.peel().count();
+        let mut raster = Raster::new(self.grid.width(), self.grid.height(), "#ffffff");
+        for (r, c) in self.grid.iter_ones() {
+            raster.set(c, r, "#7f7f7f", 0);
+        }
+        let start = raster.clone();
+        let peeled = self.peel().enumerate().map(move |(i, removed)| {
+            let color = log_scale_color(i as u128 + 1, rounds as u128);
+            for (r, c) in removed {
+                raster.set(c, r, &color, i + 1);
+            }
+            raster.clone()
+        });
+        std::iter::once(start).chain(peeled)
+    }
+
+    fn build_neighbor_counts(&self) -> Vec<Vec<u8>> {
+        let mut counts = vec![vec![0u8; self.grid.width()]; self.grid.height()];
+        for (r, c) in self.grid.iter_ones() {
+            counts[r][c] = self.count_neighbors(r, c);
         }
         counts
     }
@@ -138,53 +178,39 @@ impl Day {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
-
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            ..@@.@@@@.\n\
-            @@@.@.@.@@\n\
-            @@@@@.@.@@\n\
-            @.@@@@..@.\n\
-            @@.@@@@.@@\n\
-            .@@@@@@@.@\n\
-            .@.@.@.@@@\n\
-            @.@@@.@@@@\n\
-            .@@@@@@@@.\n\
-            @.@.@@@.@.";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "13");
-    }
+    use crate::examples::DAY04 as EXAMPLE;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/04")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "1424");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "13",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "43",
     }
 
     #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            ..@@.@@@@.\n\
-            @@@.@.@.@@\n\
-            @@@@@.@.@@\n\
-            @.@@@@..@.\n\
-            @@.@@@@.@@\n\
-            .@@@@@@@.@\n\
-            .@.@.@.@@@\n\
-            @.@@@.@@@@\n\
-            .@@@@@@@@.\n\
-            @.@.@@@.@.";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "43");
+    fn test_raster() {
+        let raster = Day::create(EXAMPLE).raster().unwrap();
+        assert_eq!((raster.width(), raster.height()), (10, 10));
+        let colors: Vec<[u8; 3]> = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .map(|(x, y)| raster.color(x, y))
+            .collect();
+        let count = |color: [u8; 3]| colors.iter().filter(|&&c| c == color).count();
+        // The first round removes the cells that part 1 counts, and all the rounds remove the
+        // cells that part 2 counts.
+        assert_eq!(count([0xfd, 0xe7, 0x25]), 13);
+        assert_eq!(count([0x7f, 0x7f, 0x7f]), EXAMPLE.matches('@').count() - 43);
+        assert_eq!(count([0xff, 0xff, 0xff]), EXAMPLE.matches('.').count());
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/04")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "8727");
+    fn test_peel() {
+        let day = Day::new(EXAMPLE);
+        let rounds: Vec<usize> = day.peel().map(|removed| removed.len()).collect();
+        assert_eq!(rounds[0], 13);
+        assert_eq!(rounds.iter().sum::<usize>(), 43);
+        assert!(rounds.iter().all(|&n| n > 0));
+        let frames: Vec<Raster> = day.animate().collect();
+        assert_eq!(frames.len(), rounds.len() + 1);
+        assert_eq!(Some(frames[rounds.len()].clone()), day.raster());
     }
 }
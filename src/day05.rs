@@ -1,5 +1,6 @@
+use crate::intervals::IntervalSet;
+use crate::parse::{Cursor, ParseError};
 use crate::puzzle::Puzzle;
-use rangemap::RangeInclusiveSet;
 use std::ops::RangeInclusive;
 
 pub struct Day {
@@ -17,7 +18,7 @@ impl Puzzle for Day {
         let ranges = self.build_range_set();
         self.available_ids
             .iter()
-            .filter(|id| ranges.contains(id))
+            .filter(|&&id| ranges.contains(id))
             .count()
             .to_string()
     }
@@ -27,96 +28,90 @@ impl Puzzle for Day {
     /// Time complexity: O(N log N) where N is the number of fresh ranges
     /// Auxiliary space complexity: O(N)
     fn solve_part_2(&self) -> String {
-        let ranges = self.build_range_set();
-        ranges
-            .iter()
-            .map(|range| range.end() - range.start() + 1)
-            .sum::<u64>()
-            .to_string()
+        self.build_range_set().len().to_string()
     }
 }
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let (ranges_part, ids_part) = input.split_once("\n\n").unwrap();
-        let fresh_id_ranges = ranges_part
-            .lines()
-            .map(|line| {
-                let (start, end) = line.split_once('-').unwrap();
-                start.parse::<u64>().unwrap()..=end.parse::<u64>().unwrap()
-            })
-            .collect();
-        let available_ids = ids_part
-            .lines()
-            .map(|line| line.parse::<u64>().unwrap())
-            .collect();
-        Box::new(Day {
+        Box::new(Self::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self::try_new(input).unwrap_or_else(|e| panic!("Day 5: {e}"))
+    }
+
+    pub fn try_create(input: &str) -> Result<Box<dyn Puzzle>, ParseError> {
+        Ok(Box::new(Self::try_new(input)?))
+    }
+
+    /// Parses a section of `start-end` fresh ID ranges, one per line, followed by a section of
+    /// available IDs, one per line.
+    pub fn try_new(input: &str) -> Result<Self, ParseError> {
+        let mut cursor = Cursor::new(input);
+        let mut sections = cursor.split_sections();
+        let [ranges_section, ids_section] = sections.as_mut_slice() else {
+            return Err(match sections.get(2) {
+                Some(extra) => extra.error("expected only ranges and IDs"),
+                None => cursor.error("expected a blank line followed by the available IDs"),
+            });
+        };
+        let mut fresh_id_ranges = Vec::new();
+        for mut line in ranges_section.lines() {
+            let start = line.parse_u64()?;
+            line.expect_char('-')?;
+            let end = line.parse_u64()?;
+            line.expect_end()?;
+            fresh_id_ranges.push(start..=end);
+        }
+        let mut available_ids = Vec::new();
+        for mut line in ids_section.lines() {
+            available_ids.push(line.parse_u64()?);
+            line.expect_end()?;
+        }
+        Ok(Day {
             fresh_id_ranges,
             available_ids,
         })
     }
 
-    fn build_range_set(&self) -> RangeInclusiveSet<u64> {
-        let mut ranges = RangeInclusiveSet::new();
-        for range in &self.fresh_id_ranges {
-            ranges.insert(range.clone());
-        }
-        ranges
+    fn build_range_set(&self) -> IntervalSet {
+        IntervalSet::merge(self.fresh_id_ranges.iter().cloned())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
-
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            3-5\n\
-            10-14\n\
-            16-20\n\
-            12-18\n\
-            \n\
-            1\n\
-            5\n\
-            8\n\
-            11\n\
-            17\n\
-            32";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "3");
-    }
+    use crate::examples::DAY05 as EXAMPLE;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/05")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "509");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "3",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "14",
     }
 
-    #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            3-5\n\
-            10-14\n\
-            16-20\n\
-            12-18\n\
-            \n\
-            1\n\
-            5\n\
-            8\n\
-            11\n\
-            17\n\
-            32";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "14");
+    fn parse_error(input: &str) -> String {
+        Day::try_create(input).err().unwrap().to_string()
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/05")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "336790092076620");
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_error("3-5\n10=14\n\n1"),
+            "line 2, column 3: expected `-`, found `=`"
+        );
+        assert_eq!(
+            parse_error("3-5\n\n1\n5 5"),
+            "line 4, column 3: unexpected `5`"
+        );
+        assert_eq!(
+            parse_error("3-5\n10-14\n"),
+            "line 3, column 1: expected a blank line followed by the available IDs"
+        );
+        assert_eq!(
+            parse_error("3-5\n\n1\n\n2"),
+            "line 5, column 1: expected only ranges and IDs"
+        );
     }
 }
@@ -1,60 +1,73 @@
 use crate::puzzle::Puzzle;
+use crate::table::{Table, to_cells};
 use std::ops::Range;
 
 pub struct Day {
-    grid: Vec<Vec<char>>,
-    num_grid: Vec<Vec<u64>>,
+    table: Table,
+    num_grid: Vec<Vec<u128>>,
     col_ranges: Vec<Range<usize>>,
     ops: Vec<Op>,
+    op_cols: Vec<usize>,
+    direction: ReadDirection,
+}
+
+/// The order in which the columns of a group are read when forming vertical numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadDirection {
+    /// Rightmost column first. This is the convention of the puzzle.
+    RightToLeft,
+    /// Leftmost column first.
+    LeftToRight,
+    /// Read towards the column holding the group's operator in the footer row, i.e. the column
+    /// farthest from the operator comes first. An operator in the left half of its group reads
+    /// right-to-left (as in the puzzle input), one in the right half reads left-to-right.
+    Footer,
 }
 
 impl Puzzle for Day {
     /// Reduces each numeric column group independently using the operator specified in the footer
-    /// row, then sums the results across groups.
+    /// row, then sums the results across groups. Each row contributes the number formed by the
+    /// digits it has within the group's columns.
+    ///
+    /// All arithmetic is carried out in checked u128, so answers beyond u64 are still exact and an
+    /// overflow is reported rather than silently wrapping.
     ///
     /// Time complexity: O(M * N), where M is the number of data rows and N is the number of
     /// operator column groups.
-    /// Auxiliary space complexity: O(M * N) for the pre-parsed numeric grid.
+    /// Auxiliary space complexity: O(M * N) for the pre-parsed numeric groups.
     fn solve_part_1(&self) -> String {
-        (0..self.ops.len())
-            .map(|col| {
-                let mut it = self.num_grid.iter().map(|row| row[col]);
-                let first = it.next().unwrap();
-                it.fold(first, |acc, n| self.ops[col].apply(acc, n))
-            })
-            .sum::<u64>()
+        self.num_grid
+            .iter()
+            .zip(self.ops.iter().copied())
+            .map(|(numbers, op)| op.reduce(numbers))
+            .try_fold(0u128, |acc, n| acc.checked_add(n?))
+            .expect(OVERFLOW)
             .to_string()
     }
 
     /// For each contiguous group of digit-bearing columns, read a number per column  by
-    /// concatenating vertical digits (top-to-bottom). Process columns right-to-left within each
-    /// group, combining with that group's operator, then sum the group results.
+    /// concatenating vertical digits (top-to-bottom). Process columns in the configured read
+    /// direction (right-to-left by default) within each group, combining with that group's
+    /// operator, then sum the group results.
+    ///
+    /// As in part 1, all arithmetic is checked u128.
     ///
     /// Time complexity: O(M * N), where M is the number of data rows and N is the number of
     /// character columns (each column scan touches all rows).
-    /// Auxiliary space complexity: O(1)
+    /// Auxiliary space complexity: O(N) for the vertical numbers.
     fn solve_part_2(&self) -> String {
-        let number_for_col = |col: usize, grid: &Vec<Vec<char>>| -> u64 {
-            grid.iter()
-                .map(|row| row[col])
-                .filter_map(|c| c.to_digit(10).map(|d| d as u64))
-                .fold(0u64, |n, d| n * 10 + d)
-        };
-        self.col_ranges
+        self.vertical_numbers(self.direction)
             .iter()
             .zip(self.ops.iter().copied())
-            .map(|(range, op)| {
-                range
-                    .clone()
-                    .map(|col| number_for_col(col, &self.grid))
-                    .reduce(|a, b| op.apply(a, b))
-                    .unwrap()
-            })
-            .sum::<u64>()
+            .map(|(numbers, op)| op.reduce(numbers))
+            .try_fold(0u128, |acc, n| acc.checked_add(n?))
+            .expect(OVERFLOW)
             .to_string()
     }
 }
 
+const OVERFLOW: &str = "Day 6 answer overflows u128";
+
 #[derive(Clone, Copy)]
 enum Op {
     Add,
@@ -70,104 +83,200 @@ impl Op {
         }
     }
 
-    fn apply(&self, a: u64, b: u64) -> u64 {
+    /// Applies the operator, returning `None` on u128 overflow.
+    fn apply(&self, a: u128, b: u128) -> Option<u128> {
+        match self {
+            Op::Add => a.checked_add(b),
+            Op::Mul => a.checked_mul(b),
+        }
+    }
+
+    /// Folds a non-empty group of numbers with the operator, returning `None` on u128 overflow.
+    fn reduce(&self, numbers: &[u128]) -> Option<u128> {
+        let (&first, rest) = numbers.split_first().unwrap();
+        rest.iter().try_fold(first, |acc, &n| self.apply(acc, n))
+    }
+}
+
+impl ReadDirection {
+    fn reads_right_to_left(&self, range: &Range<usize>, op_col: usize) -> bool {
         match self {
-            Op::Add => a + b,
-            Op::Mul => a * b,
+            ReadDirection::RightToLeft => true,
+            ReadDirection::LeftToRight => false,
+            ReadDirection::Footer => op_col.abs_diff(range.start) <= op_col.abs_diff(range.end - 1),
         }
     }
 }
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
+        Box::new(Day::new(input))
+    }
+
+    /// Parses the worksheet, reading vertical numbers right-to-left as the puzzle specifies.
+    pub fn new(input: &str) -> Self {
+        Self::with_direction(input, ReadDirection::RightToLeft)
+    }
+
+    /// Parses the worksheet, reading vertical numbers in part 2 in the given direction.
+    pub fn with_direction(input: &str, direction: ReadDirection) -> Self {
         let mut lines: Vec<&str> = input.lines().collect();
         while matches!(lines.last(), Some(l) if l.is_empty()) {
             lines.pop();
         }
         let ops_line = lines.pop().unwrap();
         let data_lines = lines;
-        let ops: Vec<Op> = ops_line
-            .chars()
-            .filter(|&c| c == '+' || c == '*')
-            .map(Op::from_char)
-            .collect();
-        let grid: Vec<Vec<char>> = data_lines.iter().map(|l| l.chars().collect()).collect();
-        let num_grid: Vec<Vec<u64>> = data_lines
+        let (op_cols, ops): (Vec<usize>, Vec<Op>) = to_cells(ops_line)
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, c)| c == '+' || c == '*')
+            .map(|(col, c)| (col, Op::from_char(c)))
+            .unzip();
+        let table = Table::new(data_lines);
+        let col_ranges = table.digit_column_ranges();
+        let num_grid: Vec<Vec<u128>> = col_ranges
             .iter()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap())
-                    .collect::<Vec<u64>>()
-            })
+            .map(|range| table.row_numbers(range.clone()).expect(OVERFLOW))
             .collect();
-        let num_rows = grid.len();
-        let mut col_ranges: Vec<Range<usize>> = Vec::new();
-        let mut start: Option<usize> = None;
-        for (col, _) in grid[0].iter().enumerate() {
-            let has_digit = (0..num_rows).any(|row| grid[row][col].is_ascii_digit());
-            match (start, has_digit) {
-                (None, true) => start = Some(col),
-                (Some(s), false) => {
-                    col_ranges.push(s..col);
-                    start = None;
-                }
-                _ => {}
-            }
-        }
-        if let Some(s) = start {
-            col_ranges.push(s..grid[0].len());
-        }
-        Box::new(Day {
-            grid,
+        Day {
+            table,
             num_grid,
             col_ranges,
             ops,
-        })
+            op_cols,
+            direction,
+        }
+    }
+
+    /// Returns, for each column group, the numbers formed by reading each column's digits
+    /// top-to-bottom, with the columns visited in the given direction.
+    ///
+    /// Panics if a number does not fit in a u128.
+    pub fn vertical_numbers(&self, direction: ReadDirection) -> Vec<Vec<u128>> {
+        self.col_ranges
+            .iter()
+            .zip(self.op_cols.iter().copied())
+            .map(|(range, op_col)| {
+                let mut cols: Vec<usize> = range.clone().collect();
+                if direction.reads_right_to_left(range, op_col) {
+                    cols.reverse();
+                }
+                cols.into_iter()
+                    .map(|col| self.table.column_number(col).expect(OVERFLOW))
+                    .collect()
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY06 as EXAMPLE;
+    use crate::puzzle::example_tests;
+
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "4277556",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "3263827",
+    }
 
     #[test]
-    fn test_part_1_example_1() {
+    fn test_part_1_exceeds_u64() {
         let input = [
-            "123 328  51 64 ",
-            " 45 64  387 23 ",
-            "  6 98  215 314",
-            "*   +   *   +  ",
+            "18446744073709551615 2",
+            "                   2 3",
+            "*                    +",
         ]
         .join("\n");
         let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "4277556");
+        assert_eq!(puzzle.solve_part_1(), "36893488147419103235");
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/06")).unwrap();
+    fn test_part_2_exceeds_u64() {
+        // Each of the five columns in the first group reads 9999 vertically.
+        let input = ["99999 9", "99999 9", "99999 9", "99999 9", "*     +"].join("\n");
+        let puzzle = Day::create(&input);
+        assert_eq!(puzzle.solve_part_2(), "99950009999000059998");
+    }
+
+    #[test]
+    #[should_panic(expected = "Day 6 answer overflows u128")]
+    fn test_part_1_overflow_is_reported() {
+        let input = [
+            "18446744073709551615",
+            "18446744073709551615",
+            "18446744073709551615",
+            "*                   ",
+        ]
+        .join("\n");
         let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "5227286044585");
+        puzzle.solve_part_1();
     }
 
     #[test]
-    fn test_part_2_example_1() {
+    fn test_full_width_characters_keep_alignment() {
+        // The full-width label occupies two display cells, so it lines up with three ASCII cells
+        // even though it is only two characters long.
         let input = [
-            "123 328  51 64 ",
-            " 45 64  387 23 ",
-            "  6 98  215 314",
-            "*   +   *   +  ",
+            "甲 123 328  51 64 ",
+            "    45 64  387 23 ",
+            "     6 98  215 314",
+            "   *   +   *   +  ",
         ]
         .join("\n");
         let puzzle = Day::create(&input);
+        assert_eq!(puzzle.solve_part_1(), "4277556");
         assert_eq!(puzzle.solve_part_2(), "3263827");
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/06")).unwrap();
+    fn test_box_drawing_decorations() {
+        let input = [
+            "│123 328  51 64 │",
+            "│ 45 64  387 23 │",
+            "│  6 98  215 314│",
+            "│*   +   *   +  │",
+        ]
+        .join("\n");
         let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "10227753257799");
+        assert_eq!(puzzle.solve_part_1(), "4277556");
+        assert_eq!(puzzle.solve_part_2(), "3263827");
+    }
+
+    #[test]
+    fn test_vertical_numbers_read_direction() {
+        let day = Day::new(EXAMPLE);
+        assert_eq!(
+            day.vertical_numbers(ReadDirection::RightToLeft),
+            vec![
+                vec![356, 24, 1],
+                vec![8, 248, 369],
+                vec![175, 581, 32],
+                vec![4, 431, 623]
+            ]
+        );
+        assert_eq!(
+            day.vertical_numbers(ReadDirection::LeftToRight),
+            vec![
+                vec![1, 24, 356],
+                vec![369, 248, 8],
+                vec![32, 581, 175],
+                vec![623, 431, 4]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vertical_numbers_footer_direction() {
+        // The first group's operator sits on its left edge and the second group's on its right
+        // edge, so the columns are read towards each operator.
+        let input = ["12 34", "56 78", "*   +"].join("\n");
+        let day = Day::with_direction(&input, ReadDirection::Footer);
+        assert_eq!(
+            day.vertical_numbers(ReadDirection::Footer),
+            vec![vec![26, 15], vec![37, 48]]
+        );
+        assert_eq!(day.solve_part_2(), "475");
     }
 }
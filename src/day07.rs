@@ -1,95 +1,475 @@
+use crate::dir::Dir4;
 use crate::puzzle::Puzzle;
-use std::collections::{HashMap, HashSet};
+use crate::render::{Raster, Svg, Visualization, log_scale_color};
+use std::iter::Peekable;
+use std::ops::Range;
+use std::slice::Iter;
 
 pub struct Day {
-    start: Pos,
-    splitters: HashSet<Pos>,
+    /// Beam sources in row-major order.
+    starts: Vec<Pos>,
+    /// The non-empty tiles of each input line, sorted by column. Columns are shifted right by one
+    /// so that beams leaving the grid on the left stay at a valid index.
+    tiles: Vec<Vec<(usize, Tile)>>,
+    /// The number of (shifted) columns, including a margin of one on either side.
+    width: usize,
+    /// The last row containing a tile or a source, after which nothing changes.
+    last_row: usize,
 }
 
 impl Puzzle for Day {
     /// Simulates a set of unique beam positions falling one row at a time.
     ///
     /// A splitter (`^`) causes a beam to branch to down-left and down-right  when the splitter is
-    /// directly below the beam's current position.
+    /// directly below the beam's current position. An absorber (`#`) stops the beam, and the
+    /// deflectors `/` and `\` move it one column to the left or right respectively.
     ///
     /// This returns the number of *unique* split events encountered by the deduplicated beam
-    /// frontier.
+    /// frontier. With several sources (`S`), beams from different sources that reach the same
+    /// cell merge into one, so every splitter is counted at most once.
     ///
-    /// Time complexity: O(N^2) where N is the larger of vertical/horizontal distance covered.
-    /// Auxiliary space complexity: O(N)
+    /// Time complexity: O(R * K + N log N) where R is the number of rows, K is the maximum number
+    /// of runs in the frontier and N is the number of tiles.
+    /// Auxiliary space complexity: O(K + T) where T is the maximum number of tiles in a row.
     fn solve_part_1(&self) -> String {
-        let mut num_splits: u64 = 0;
-        let mut beams: Vec<Pos> = Vec::new();
-        let mut next: Vec<Pos> = Vec::new();
-        beams.push(self.start);
-        for _ in self.start.0..self.last_splitter_row() {
-            next.clear();
-            for &(r, c) in &beams {
-                let nr = r + 1;
-                if self.splitters.contains(&(nr, c)) {
-                    num_splits += 1;
-                    unique_push(&mut next, (nr, c - 1));
-                    unique_push(&mut next, (nr, c + 1));
-                } else {
-                    unique_push(&mut next, (nr, c));
-                }
-            }
-            std::mem::swap(&mut beams, &mut next);
-        }
+        let (num_splits, _) = Cascade::<bool>::new(self).finish();
         num_splits.to_string()
     }
 
     /// Simulates falling particles, but tracks multiplicity of timelines.
     ///
     /// Each time a timeline hits a splitter, it branches to left/right with the full count of
-    /// timelines arriving at that position.
+    /// timelines arriving at that position. Every source (`S`) starts one timeline, absorbers end
+    /// the timelines reaching them and deflectors move them without branching.
     ///
-    /// The answer is the total number of timelines after the last splitter row.
+    /// The answer is the total number of timelines after the last row.
     ///
-    /// Time complexity: O(N^2) where N is the larger of vertical/horizontal distance covered.
-    /// Auxiliary space complexity: O(N)
+    /// Time complexity: O(R * K + N log N) where R is the number of rows, K is the maximum number
+    /// of runs in the frontier and N is the number of tiles.
+    /// Auxiliary space complexity: O(K + T) where T is the maximum number of tiles in a row.
     fn solve_part_2(&self) -> String {
-        let mut beams: HashMap<Pos, u128> = HashMap::new();
-        let mut next: HashMap<Pos, u128> = HashMap::new();
-        beams.insert(self.start, 1);
-        for _ in self.start.0..self.last_splitter_row() {
-            next.clear();
-            for (&(r, c), &count) in &beams {
-                let nr = r + 1;
-                if self.splitters.contains(&(nr, c)) {
-                    *next.entry((nr, c - 1)).or_insert(0) += count;
-                    *next.entry((nr, c + 1)).or_insert(0) += count;
-                } else {
-                    *next.entry((nr, c)).or_insert(0) += count;
+        let (_, beams) = Cascade::<u128>::new(self).finish();
+        beams
+            .iter()
+            .map(|run| run.value * run.len() as u128)
+            .sum::<u128>()
+            .to_string()
+    }
+
+    /// Draws the tiles and, for every cell a timeline passes through, a square colored by the
+    /// number of timelines there on a logarithmic scale.
+    fn visualize(&self) -> Option<Visualization> {
+        const CELL: f64 = 10.0;
+        let mut frontiers = vec![Vec::new(); self.tiles.len()];
+        for snapshot in self.simulate() {
+            frontiers[snapshot.row] = snapshot.beams;
+        }
+        let max = frontiers.iter().flatten().map(|&(_, count)| count).max();
+        let width = self.width as f64 * CELL;
+        let height = self.tiles.len() as f64 * CELL;
+        let mut svg = Svg::new(width, height);
+        svg.rect(0.0, 0.0, width, height, "#202020", None);
+        for (row, (tiles, beams)) in self.tiles.iter().zip(&frontiers).enumerate() {
+            let y = row as f64 * CELL;
+            for (cols, count) in beams {
+                let color = log_scale_color(*count, max.unwrap_or(0));
+                for col in cols.clone() {
+                    let title = format!("row {row}, column {col}: {count} timelines");
+                    svg.rect((col + 1) as f64 * CELL, y, CELL, CELL, &color, Some(&title));
                 }
             }
-            std::mem::swap(&mut beams, &mut next);
+            for &(col, tile) in tiles {
+                let x = col as f64 * CELL;
+                let (left, mid, right) = (x, x + CELL / 2.0, x + CELL);
+                let (top, bottom) = (y, y + CELL);
+                match tile {
+                    Tile::Splitter => svg.polygon(
+                        &[(mid, top), (right, bottom), (left, bottom)],
+                        "#e0e0e0",
+                        None,
+                    ),
+                    Tile::Absorber => svg.rect(x, y, CELL, CELL, "#000000", None),
+                    Tile::DeflectLeft => svg.line((left, bottom), (right, top), "#e0e0e0", 1.5),
+                    Tile::DeflectRight => svg.line((left, top), (right, bottom), "#e0e0e0", 1.5),
+                }
+            }
+        }
+        for &(row, col) in &self.starts {
+            let (x, y) = ((col + 1) as f64 * CELL, row as f64 * CELL);
+            svg.rect(x, y, CELL, CELL, "#35b779", Some("source"));
         }
-        beams.values().sum::<u128>().to_string()
+        Some(svg.finish())
     }
 }
 
 type Pos = (usize, usize);
 
-fn unique_push(vec: &mut Vec<Pos>, pos: Pos) {
-    if vec.last() != Some(&pos) {
-        vec.push(pos);
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    /// `^`: splits a beam into its left and right neighbors.
+    Splitter,
+    /// `#`: stops a beam.
+    Absorber,
+    /// `/`: moves a beam one column to the left.
+    DeflectLeft,
+    /// `\`: moves a beam one column to the right.
+    DeflectRight,
+}
+
+impl Tile {
+    /// The directions in which beams leave the tile, one step to the side of it.
+    fn exits(self) -> &'static [Dir4] {
+        match self {
+            Tile::Splitter => &[Dir4::Left, Dir4::Right],
+            Tile::Absorber => &[],
+            Tile::DeflectLeft => &[Dir4::Left],
+            Tile::DeflectRight => &[Dir4::Right],
+        }
     }
 }
 
+/// The contents of a frontier cell: either whether a beam is present, or how many timelines are.
+trait Beam: Copy + Default + PartialEq {
+    /// A single beam, as emitted by a source.
+    const ONE: Self;
+
+    /// Merges beams arriving in the same cell.
+    fn merge(&mut self, other: Self);
+}
+
+impl Beam for bool {
+    const ONE: Self = true;
+
+    fn merge(&mut self, other: Self) {
+        *self |= other;
+    }
+}
+
+impl Beam for u128 {
+    const ONE: Self = 1;
+
+    fn merge(&mut self, other: Self) {
+        *self += other;
+    }
+}
+
+/// A maximal run of adjacent (shifted) columns `start..end` that all hold the same beam value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Run<B> {
+    start: usize,
+    end: usize,
+    value: B,
+}
+
+impl<B> Run<B> {
+    /// A run covering the single column `col`.
+    fn cell(col: usize, value: B) -> Self {
+        Run {
+            start: col,
+            end: col + 1,
+            value,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Appends a run to a sorted frontier, coalescing it with the last run if they touch and hold the
+/// same value.
+fn push_run<B: Beam>(runs: &mut Vec<Run<B>>, run: Run<B>) {
+    match runs.last_mut() {
+        Some(last) if last.end == run.start && last.value == run.value => last.end = run.end,
+        _ => runs.push(run),
+    }
+}
+
+/// Takes the next point with a column before `end`, merging all points in that column.
+fn next_point<B: Beam>(points: &mut Peekable<Iter<(usize, B)>>, end: usize) -> Option<(usize, B)> {
+    let &(col, mut value) = points.next_if(|&&(col, _)| col < end)?;
+    while let Some(&(_, other)) = points.next_if(|&&(other, _)| other == col) {
+        value.merge(other);
+    }
+    Some((col, value))
+}
+
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let mut start: Option<Pos> = None;
-        let mut splitters: HashSet<Pos> = HashSet::new();
-        for (row, line) in input.trim().lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+        Box::new(Day::new(input))
+    }
+
+    /// Parses the diagram of tiles and beam sources.
+    pub fn new(input: &str) -> Self {
+        let lines: Vec<&str> = input
+            .trim()
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap();
+        let mut starts: Vec<Pos> = Vec::new();
+        let mut tiles: Vec<Vec<(usize, Tile)>> = vec![Vec::new(); lines.len()];
+        let mut last_row = 0;
+        for (row, line) in lines.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let tile = match c {
+                    'S' => {
+                        starts.push((row, col));
+                        last_row = row;
+                        continue;
+                    }
+                    '^' => Tile::Splitter,
+                    '#' => Tile::Absorber,
+                    '/' => Tile::DeflectLeft,
+                    '\\' => Tile::DeflectRight,
+                    _ => continue,
+                };
+                tiles[row].push((col + 1, tile));
+                last_row = row;
+            }
+        }
+        Day {
+            starts,
+            tiles,
+            width: width + 2,
+            last_row,
+        }
+    }
+
+    /// Returns the beam frontier row by row, from the first source down to the last row that
+    /// contains a tile or a source.
+    ///
+    /// Each snapshot is taken after the beams have passed the tiles of its row and that row's
+    /// sources have been added.
+    pub fn simulate(&self) -> impl Iterator<Item = FrontierSnapshot> + '_ {
+        let mut cascade = Cascade::<u128>::new(self);
+        std::iter::from_fn(move || {
+            let row = cascade.step()?;
+            Some(FrontierSnapshot {
+                row,
+                splits: cascade.num_splits,
+                beams: cascade
+                    .beams
+                    .iter()
+                    .map(|run| (run.start as isize - 1..run.end as isize - 1, run.value))
+                    .collect(),
+            })
+        })
+    }
+
+    /// Returns a frame of the diagram before any beam falls and after each row of
+    /// [`Day::simulate`], with one cell per cell of the input (and of the margins either side of
+    /// it) and the beams colored by their number of timelines as in [`Puzzle::visualize`].
+    pub fn animate(&self) -> impl Iterator<Item = Raster> + '_ {
+        let max = self
+            .simulate()
+            .flat_map(|snapshot| snapshot.beams)
+            .map(|(_, count)| count)
+            .max()
+            .unwrap_or(0);
+        let mut raster = Raster::new(self.width, self.tiles.len(), "#202020");
+        // Everything is one area, so no lines are drawn between cells.
+        for y in 0..raster.height() {
+            for x in 0..raster.width() {
+                raster.set(x, y, "#202020", 0);
+            }
+        }
+        for row in 0..self.tiles.len() {
+            self.draw_tiles(&mut raster, row);
+        }
+        let start = raster.clone();
+        let frames = self.simulate().map(move |snapshot| {
+            for (cols, count) in &snapshot.beams {
+                let color = log_scale_color(*count, max);
+                for col in cols.clone() {
+                    raster.set((col + 1) as usize, snapshot.row, &color, 0);
+                }
+            }
+            self.draw_tiles(&mut raster, snapshot.row);
+            raster.clone()
+        });
+        std::iter::once(start).chain(frames)
+    }
+
+    /// Draws the tiles and sources of `row` into a frame of [`Day::animate`].
+    fn draw_tiles(&self, raster: &mut Raster, row: usize) {
+        for &(col, tile) in &self.tiles[row] {
+            let color = match tile {
+                Tile::Splitter => "#e0e0e0",
+                Tile::Absorber => "#000000",
+                Tile::DeflectLeft | Tile::DeflectRight => "#a0a0a0",
+            };
+            raster.set(col, row, color, 0);
+        }
+        for &(_, col) in self.starts.iter().filter(|&&(r, _)| r == row) {
+            raster.set(col + 1, row, "#35b779", 0);
+        }
+    }
+}
+
+/// The beams in one row of the cascade.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrontierSnapshot {
+    /// The row of the input the beams are in.
+    pub row: usize,
+    /// The number of split events up to and including this row.
+    pub splits: u64,
+    /// Maximal runs of adjacent columns with the same number of timelines, as
+    /// `(columns, timelines)` in left-to-right order. Columns are those of the input, so a beam
+    /// that left the grid on the left is in column -1.
+    pub beams: Vec<(Range<isize>, u128)>,
+}
+
+impl FrontierSnapshot {
+    /// Returns the total number of timelines in the row.
+    pub fn timelines(&self) -> u128 {
+        self.beams
+            .iter()
+            .map(|(cols, count)| cols.len() as u128 * count)
+            .sum()
+    }
+}
+
+/// A simulation of the beams from every source down to the last row, advanced one row at a time.
+///
+/// The frontier is kept as sorted, non-overlapping runs of equal beam values, so its size is
+/// proportional to the number of distinct segments rather than to the width of the grid. Each row
+/// is processed by sweeping the runs and the row's tiles together: the parts of a run that fall
+/// between tiles pass straight down, while the cells on a tile turn into single-cell contributions
+/// that are merged back in. Adjacent runs that end up with equal values are coalesced. All buffers
+/// are reused from row to row.
+struct Cascade<'a, B> {
+    day: &'a Day,
+    /// The next row to process.
+    row: usize,
+    /// The sources not yet reached.
+    sources: Peekable<Iter<'a, Pos>>,
+    /// The number of split events so far.
+    num_splits: u64,
+    /// The frontier after the last processed row.
+    beams: Vec<Run<B>>,
+    next: Vec<Run<B>>,
+    straight: Vec<Run<B>>,
+    points: Vec<(usize, B)>,
+}
+
+impl<'a, B: Beam> Cascade<'a, B> {
+    fn new(day: &'a Day) -> Self {
+        Cascade {
+            day,
+            row: day.starts.first().map_or(0, |&(row, _)| row),
+            sources: day.starts.iter().peekable(),
+            num_splits: 0,
+            beams: Vec::new(),
+            next: Vec::new(),
+            straight: Vec::new(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Runs to the last row, returning the number of split events and the final frontier.
+    fn finish(mut self) -> (u64, Vec<Run<B>>) {
+        while self.step().is_some() {}
+        (self.num_splits, self.beams)
+    }
+
+    /// Moves the beams through the next row, returning its index, or `None` once past the last
+    /// row.
+    fn step(&mut self) -> Option<usize> {
+        let row = self.row;
+        if row > self.day.last_row {
+            return None;
+        }
+        self.row += 1;
+        self.straight.clear();
+        self.points.clear();
+        let mut tiles = self.day.tiles[row].iter().peekable();
+        for run in &self.beams {
+            while tiles.next_if(|&&(col, _)| col < run.start).is_some() {}
+            let mut start = run.start;
+            while let Some(&(col, tile)) = tiles.next_if(|&&(col, _)| col < run.end) {
+                if start < col {
+                    self.straight.push(Run {
+                        start,
+                        end: col,
+                        value: run.value,
+                    });
+                }
+                start = col + 1;
+                if tile == Tile::Splitter {
+                    self.num_splits += 1;
+                }
+                for dir in tile.exits() {
+                    // Columns are shifted right by one, so stepping left never goes below 0.
+                    let (_, dc) = dir.delta();
+                    self.points.push(((col as isize + dc) as usize, run.value));
+                }
+            }
+            if start < run.end {
+                self.straight.push(Run { start, ..*run });
+            }
+        }
+        while let Some(&(_, col)) = self.sources.next_if(|&&(r, _)| r == row) {
+            self.points.push((col + 1, B::ONE));
+        }
+        self.points.sort_unstable_by_key(|&(col, _)| col);
+
+        let next = &mut self.next;
+        next.clear();
+        let mut points = self.points.iter().peekable();
+        for piece in &self.straight {
+            while let Some((col, value)) = next_point(&mut points, piece.start) {
+                push_run(next, Run::cell(col, value));
+            }
+            let mut start = piece.start;
+            while let Some((col, mut value)) = next_point(&mut points, piece.end) {
+                if start < col {
+                    push_run(
+                        next,
+                        Run {
+                            start,
+                            end: col,
+                            value: piece.value,
+                        },
+                    );
+                }
+                value.merge(piece.value);
+                push_run(next, Run::cell(col, value));
+                start = col + 1;
             }
-            let chars: Vec<char> = line.chars().collect();
-            for (col, c) in chars.into_iter().enumerate() {
+            if start < piece.end {
+                push_run(next, Run { start, ..*piece });
+            }
+        }
+        while let Some((col, value)) = next_point(&mut points, usize::MAX) {
+            push_run(next, Run::cell(col, value));
+        }
+        std::mem::swap(&mut self.beams, &mut self.next);
+        Some(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::DAY07 as EXAMPLE;
+    use crate::puzzle::example_tests;
+
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "21",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "40",
+    }
+
+    /// The previous hash-based frontier, kept as a reference for the dense implementation.
+    fn reference_timelines(input: &str) -> u128 {
+        use std::collections::{HashMap, HashSet};
+        let mut start = (0, 0);
+        let mut splitters: HashSet<Pos> = HashSet::new();
+        for (row, line) in input.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
                 match c {
-                    'S' => start = Some((row, col)),
+                    'S' => start = (row, col),
                     '^' => {
                         splitters.insert((row, col));
                     }
@@ -97,77 +477,214 @@ impl Day {
                 }
             }
         }
-        let start = start.unwrap();
-        Box::new(Day { start, splitters })
+        let last_row = splitters.iter().map(|&(row, _)| row).max().unwrap();
+        let mut beams: HashMap<Pos, u128> = HashMap::from([(start, 1)]);
+        for _ in start.0..last_row {
+            let mut next: HashMap<Pos, u128> = HashMap::new();
+            for (&(r, c), &count) in &beams {
+                if splitters.contains(&(r + 1, c)) {
+                    *next.entry((r + 1, c - 1)).or_insert(0) += count;
+                    *next.entry((r + 1, c + 1)).or_insert(0) += count;
+                } else {
+                    *next.entry((r + 1, c)).or_insert(0) += count;
+                }
+            }
+            beams = next;
+        }
+        beams.values().sum()
     }
 
-    fn last_splitter_row(&self) -> usize {
-        self.splitters.iter().map(|&(row, _)| row).max().unwrap()
+    #[test]
+    fn test_part_2_matches_reference_on_random_grids() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let width = rng.random_range(3..40);
+            let height = rng.random_range(3..40);
+            let mut rows: Vec<String> = vec![".".repeat(width)];
+            rows[0].replace_range(width / 2..width / 2 + 1, "S");
+            for _ in 1..height {
+                let row: String = (0..width)
+                    .map(|col| {
+                        let interior = col > 0 && col + 1 < width;
+                        if interior && rng.random_range(0..4) == 0 {
+                            '^'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                rows.push(row);
+            }
+            rows.push(format!(".^{}", ".".repeat(width - 2)));
+            let input = rows.join("\n");
+            let puzzle = Day::create(&input);
+            assert_eq!(
+                puzzle.solve_part_2(),
+                reference_timelines(&input).to_string()
+            );
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    #[test]
+    fn test_multiple_sources() {
+        let input = "\
+            .S...S.\n\
+            .......\n\
+            .^...^.\n\
+            .......\n\
+            ..^.^..\n\
+            .......\n\
+            ...^...\n\
+            .......";
+        let puzzle = Day::create(input);
+        // Beams from both sources merge in the middle column, which is only split once.
+        assert_eq!(puzzle.solve_part_1(), "5");
+        assert_eq!(puzzle.solve_part_2(), "8");
+    }
 
     #[test]
-    fn test_part_1_example_1() {
+    fn test_sources_on_different_rows() {
         let input = "\
-            .......S.......\n\
-            ...............\n\
-            .......^.......\n\
-            ...............\n\
-            ......^.^......\n\
-            ...............\n\
-            .....^.^.^.....\n\
-            ...............\n\
-            ....^.^...^....\n\
-            ...............\n\
-            ...^.^...^.^...\n\
-            ...............\n\
-            ..^...^.....^..\n\
-            ...............\n\
-            .^.^.^.^.^...^.\n\
-            ...............";
+            ..S..\n\
+            .....\n\
+            ..^..\n\
+            .S...\n\
+            .^...\n\
+            .....";
         let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "21");
+        // The late source lands on the same cell as the left branch of the first split.
+        assert_eq!(puzzle.solve_part_1(), "2");
+        assert_eq!(puzzle.solve_part_2(), "5");
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/07")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "1711");
+    fn test_absorbers_and_deflectors() {
+        let input = "\
+            ...S...\n\
+            .......\n\
+            ...^...\n\
+            ..#....\n\
+            ....\\..\n\
+            .....^.\n\
+            .......";
+        let puzzle = Day::create(input);
+        // The left branch is absorbed, the right one is deflected onto a second splitter.
+        assert_eq!(puzzle.solve_part_1(), "2");
+        assert_eq!(puzzle.solve_part_2(), "2");
+    }
+
+    #[test]
+    fn test_deflectors_merge_beams() {
+        let input = "\
+            ..S..\n\
+            ..^..\n\
+            ./.\\.\n\
+            ..^..\n\
+            .....";
+        let puzzle = Day::create(input);
+        // Both branches are deflected outwards and miss the second splitter.
+        assert_eq!(puzzle.solve_part_1(), "1");
+        assert_eq!(puzzle.solve_part_2(), "2");
+        let input = "\
+            ..S..\n\
+            ..^..\n\
+            .\\./.\n\
+            ..^..\n\
+            .....";
+        let puzzle = Day::create(input);
+        // Both branches are deflected back into the middle column and merge before splitting.
+        assert_eq!(puzzle.solve_part_1(), "2");
+        assert_eq!(puzzle.solve_part_2(), "4");
     }
 
     #[test]
-    fn test_part_2_example_1() {
+    fn test_visualize() {
         let input = "\
-            .......S.......\n\
-            ...............\n\
-            .......^.......\n\
-            ...............\n\
-            ......^.^......\n\
-            ...............\n\
-            .....^.^.^.....\n\
-            ...............\n\
-            ....^.^...^....\n\
-            ...............\n\
-            ...^.^...^.^...\n\
-            ...............\n\
-            ..^...^.....^..\n\
-            ...............\n\
-            .^.^.^.^.^...^.\n\
-            ...............";
+            .S.\n\
+            ...\n\
+            .^.\n\
+            ...";
         let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "40");
+        let vis = puzzle.visualize().unwrap();
+        assert_eq!(vis.extension(), "svg");
+        let svg = vis.contents();
+        assert!(svg.contains("<title>row 1, column 1: 1 timelines</title>"));
+        assert!(svg.contains("<title>row 2, column 0: 1 timelines</title>"));
+        assert!(svg.contains("<title>row 2, column 2: 1 timelines</title>"));
+        assert!(!svg.contains("row 2, column 1:"));
+        assert!(svg.contains("<polygon"));
+        assert!(svg.contains("<title>source</title>"));
+    }
+
+    #[test]
+    fn test_animate() {
+        let input = "\
+            .S.\n\
+            ...\n\
+            .^.\n\
+            ...";
+        let day = Day::new(input);
+        let frames: Vec<Raster> = day.animate().collect();
+        // The diagram, then one frame per row from the source's down.
+        assert_eq!(frames.len(), 1 + day.simulate().count());
+        let (beam, dark) = ([0xfd, 0xe7, 0x25], [0x20, 0x20, 0x20]);
+        assert_eq!((frames[0].width(), frames[0].height()), (5, 4));
+        assert_eq!(frames[0].color(2, 0), [0x35, 0xb7, 0x79]);
+        assert_eq!(frames[0].color(2, 2), [0xe0, 0xe0, 0xe0]);
+        assert_eq!(frames[0].color(2, 1), dark);
+        let last = frames.last().unwrap();
+        assert_eq!(last.color(2, 1), beam);
+        assert_eq!([last.color(1, 2), last.color(3, 2)], [beam, beam]);
+        assert_eq!(last.color(2, 2), [0xe0, 0xe0, 0xe0]);
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/07")).unwrap();
+    fn test_wide_sparse_grid() {
+        let width = 50_000;
+        let mut rows: Vec<String> = Vec::new();
+        let row_with = |cols: &[usize], c: char| {
+            let mut row = vec!['.'; width];
+            for &col in cols {
+                row[col] = c;
+            }
+            row.into_iter().collect::<String>()
+        };
+        rows.push(row_with(&[10, 25_000, 49_990], 'S'));
+        rows.push(row_with(&[25_000, 49_990], '^'));
+        rows.push(row_with(&[10, 24_999, 25_001], '^'));
+        rows.push(row_with(&[], '.'));
+        let input = rows.join("\n");
         let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "36706966158365");
+        assert_eq!(puzzle.solve_part_1(), "5");
+        assert_eq!(puzzle.solve_part_2(), "8");
+    }
+
+    #[test]
+    fn test_frontier_runs_are_coalesced() {
+        let input = "\
+            .SSSS.\n\
+            ......\n\
+            ..^...\n\
+            ......";
+        let snapshots: Vec<FrontierSnapshot> = Day::new(input).simulate().collect();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[0].beams, vec![(1..5, 1)]);
+        assert_eq!(snapshots[2].beams, vec![(1..2, 2), (3..4, 2), (4..5, 1)]);
+    }
+
+    #[test]
+    fn test_simulate_intermediate_states() {
+        let snapshots: Vec<FrontierSnapshot> = Day::new(EXAMPLE).simulate().collect();
+        assert_eq!(snapshots.len(), 15);
+        assert_eq!(snapshots[0].beams, vec![(7..8, 1)]);
+        assert_eq!(snapshots[2].beams, vec![(6..7, 1), (8..9, 1)]);
+        assert_eq!(snapshots[4].beams, vec![(5..6, 1), (7..8, 2), (9..10, 1)]);
+        assert_eq!(snapshots[4].splits, 3);
+        assert_eq!(snapshots[4].timelines(), 4);
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.row, 14);
+        assert_eq!(last.splits, 21);
+        assert_eq!(last.timelines(), 40);
     }
 }
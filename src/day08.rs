@@ -1,103 +1,208 @@
+use crate::dsu::Dsu;
+use crate::fastparse;
+use crate::geom::Point3;
 use crate::puzzle::Puzzle;
-use std::collections::BinaryHeap;
+use crate::render::{Dot, Visualization, categorical_color};
+use rayon::prelude::*;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+type Point = Point3<i64>;
 
 pub struct Day {
     points: Vec<Point>,
+    search: PairSearch,
+}
+
+/// How the closest pairs of junction boxes are found.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PairSearch {
+    /// k-d tree searches pruned by the pairs found so far, with Borůvka's algorithm for the
+    /// minimum spanning tree.
+    #[default]
+    KdTree,
+    /// Every pair is measured, with Kruskal's algorithm over all of them for the minimum spanning
+    /// tree. This takes O(N^2 log N) time, and is kept to check the k-d tree against.
+    Exhaustive,
 }
 
 impl Puzzle for Day {
     /// Connect the 1000 closest pairs, then multiply sizes of the 3 largest circuits.
     ///
-    /// Time complexity: O(N^2)
-    /// Auxiliary space complexity: O(N)
+    /// The closest pairs are found with a k-d tree: every point searches its neighborhood for
+    /// partners, pruned by the distance of the worst pair kept so far, so only a small fraction of
+    /// the O(N^2) pairs is ever looked at.
+    ///
+    /// Time complexity: O(N log N + K log K) expected for well-spread points, where K is the
+    /// number of pairs to connect; O(N^2 log K) in the worst case.
+    /// Auxiliary space complexity: O(N + K)
     fn solve_part_1(&self) -> String {
-        short_connections_product(&self.points, 1000).to_string()
+        self.connections_product(1000, 3).to_string()
     }
 
     /// Keep connecting closest pairs until all junction boxes are in one circuit.
     /// Return product of X coordinates of the last edge that merges the final two components.
     ///
-    /// Time complexity: O(N^2)
+    /// That edge is the longest edge of the minimum spanning tree, which is built with Borůvka's
+    /// algorithm: each round, every circuit finds its nearest foreign neighbor with a k-d tree
+    /// search that skips subtrees lying entirely within the circuit, and all those edges are
+    /// added at once. The circuits of a round search in parallel. Ties are broken by point
+    /// indices so the tree is unique.
+    ///
+    /// Time complexity: O(N log^2 N) expected for well-spread points; O(N^2 log N) in the
+    /// worst case.
     /// Auxiliary space complexity: O(N)
     fn solve_part_2(&self) -> String {
-        let n = self.points.len();
-        let mut in_mst = vec![false; n];
-        let mut best = vec![u64::MAX; n];
-        let mut parent: Vec<Option<usize>> = vec![None; n];
-        let mut max_edge: Option<(u64, usize)> = None;
-        best[0] = 0;
-        for _ in 0..n {
-            let v = (0..n)
-                .filter(|&i| !in_mst[i])
-                .min_by_key(|&i| best[i])
-                .unwrap();
-            let v_best = best[v];
-            in_mst[v] = true;
-            if parent[v].is_some() {
-                match max_edge {
-                    None => max_edge = Some((v_best, v)),
-                    Some((d, _)) if v_best > d => max_edge = Some((v_best, v)),
-                    _ => {}
-                }
-            }
-            for u in 0..n {
-                if !in_mst[u] {
-                    let d = self.points[v].dist2(&self.points[u]);
-                    if d < best[u] {
-                        best[u] = d;
-                        parent[u] = Some(v);
-                    }
-                }
-            }
-        }
-        let (_, v) = max_edge.unwrap();
-        let p = parent[v].unwrap();
-        let a = self.points[v].x as i128;
-        let b = self.points[p].x as i128;
+        let last = self.minimum_spanning_tree().into_iter().max().unwrap();
+        let a = self.points[last.from].x as i128;
+        let b = self.points[last.to].x as i128;
         (a * b).to_string()
     }
+
+    /// Exports the circuits as a GraphViz graph; see [`Day::circuits_graph`].
+    fn visualize(&self) -> Option<Visualization> {
+        Some(self.circuits_graph(1000))
+    }
 }
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
+        Box::new(Day::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self::with_search(input, PairSearch::default())
+    }
+
+    /// Parses one `x,y,z` junction box per line, finding closest pairs with `search`.
+    pub fn with_search(input: &str, search: PairSearch) -> Self {
         let points = input
             .trim()
             .lines()
             .map(|line| {
-                let mut it = line.trim().split(',');
-                let x = it.next().unwrap().parse::<i64>().unwrap();
-                let y = it.next().unwrap().parse::<i64>().unwrap();
-                let z = it.next().unwrap().parse::<i64>().unwrap();
+                let mut it = fastparse::integers(line.as_bytes());
+                let x = it.next().unwrap();
+                let y = it.next().unwrap();
+                let z = it.next().unwrap();
                 Point { x, y, z }
             })
             .collect();
-        Box::new(Day { points })
+        Day { points, search }
     }
-}
 
-struct Point {
-    x: i64,
-    y: i64,
-    z: i64,
-}
+    /// Connects the `count` closest pairs of junction boxes and returns the sizes of the resulting
+    /// circuits, largest first. Every box belongs to exactly one circuit, so the sizes sum to the
+    /// number of boxes.
+    ///
+    /// Time complexity: O(N log N + K log K) expected, as for part 1, where K is `count`.
+    /// Auxiliary space complexity: O(N + K)
+    pub fn component_sizes(&self, count: usize) -> Vec<usize> {
+        let mut circuits = self.circuits();
+        circuits.connect(count);
+        circuits.largest_sizes(usize::MAX)
+    }
+
+    /// Returns the junction boxes as separate circuits, ready to be connected closest pair first.
+    pub fn circuits(&self) -> IncrementalCircuits<'_> {
+        IncrementalCircuits {
+            points: &self.points,
+            dsu: Dsu::new(self.points.len()),
+            search: self.search,
+            pairs: Vec::new(),
+            next: 0,
+        }
+    }
 
-impl Point {
-    /// Squared Euclidean distance
-    fn dist2(&self, other: &Point) -> u64 {
-        let dx = self.x - other.x;
-        let dy = self.y - other.y;
-        let dz = self.z - other.z;
-        (dx * dx + dy * dy + dz * dz) as u64
+    /// Connects the `count` closest pairs of junction boxes and returns the product of the sizes
+    /// of the `top_n` largest circuits (or of all of them, if there are fewer).
+    ///
+    /// Time complexity: O(N log N + K log K) expected, as for part 1, where K is `count`.
+    /// Auxiliary space complexity: O(N + K)
+    pub fn connections_product(&self, count: usize, top_n: usize) -> usize {
+        self.component_sizes(count)
+            .into_iter()
+            .take(top_n)
+            .product()
+    }
+
+    /// Renders the junction boxes as a GraphViz graph.
+    ///
+    /// Boxes are filled with the color of the circuit they end up in after connecting the `count`
+    /// closest pairs (grey if they stay on their own). Those connections are drawn as solid edges
+    /// in the circuit's color, and the remaining edges of the minimum spanning tree from part 2
+    /// as dashed grey edges.
+    pub fn circuits_graph(&self, count: usize) -> Visualization {
+        const GREY: &str = "#c0c0c0";
+        let n = self.points.len();
+        let pairs = closest_pairs(
+            &self.points,
+            count.min(n * n.saturating_sub(1) / 2),
+            self.search,
+        );
+        let mut dsu = Dsu::new(self.points.len());
+        for e in &pairs {
+            dsu.union(e.from, e.to);
+        }
+        let mut circuit_colors: HashMap<usize, &str> = HashMap::new();
+        let mut colors: Vec<&str> = Vec::with_capacity(self.points.len());
+        for i in 0..self.points.len() {
+            let root = dsu.find(i);
+            let color = if dsu.size(root) == 1 {
+                GREY
+            } else {
+                let next = circuit_colors.len();
+                circuit_colors
+                    .entry(root)
+                    .or_insert_with(|| categorical_color(next))
+            };
+            colors.push(color);
+        }
+        let mut dot = Dot::new(&[("layout", "neato"), ("overlap", "false")]);
+        for (i, (point, color)) in self.points.iter().zip(&colors).enumerate() {
+            let label = format!("{},{},{}", point.x, point.y, point.z);
+            dot.node(
+                &i.to_string(),
+                &[("label", &label), ("style", "filled"), ("fillcolor", color)],
+            );
+        }
+        for e in &pairs {
+            let (from, to) = (e.from.to_string(), e.to.to_string());
+            dot.edge(&from, &to, &[("color", colors[e.from])]);
+        }
+        let connected: HashSet<(usize, usize)> = pairs.iter().map(|e| (e.from, e.to)).collect();
+        for e in self.minimum_spanning_tree() {
+            if !connected.contains(&(e.from, e.to)) {
+                let (from, to) = (e.from.to_string(), e.to.to_string());
+                dot.edge(&from, &to, &[("color", GREY), ("style", "dashed")]);
+            }
+        }
+        dot.finish()
+    }
+
+    fn minimum_spanning_tree(&self) -> Vec<Edge> {
+        match self.search {
+            PairSearch::KdTree => minimum_spanning_tree(&self.points),
+            PairSearch::Exhaustive => minimum_spanning_tree_exhaustive(&self.points),
+        }
     }
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq)]
 struct Edge {
     from: usize,
     to: usize,
     dist2: u64,
 }
 
+impl Edge {
+    fn new(a: usize, b: usize, dist2: u64) -> Self {
+        Self {
+            from: a.min(b),
+            to: a.max(b),
+            dist2,
+        }
+    }
+}
+
 impl Ord for Edge {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.dist2
@@ -112,102 +217,353 @@ impl PartialOrd for Edge {
     }
 }
 
-struct Dsu {
-    parent: Vec<usize>,
-    size: Vec<usize>,
-    components: usize,
+/// Junction boxes being connected into circuits one pair at a time, closest pair first.
+///
+/// Pairs are found in batches: whenever the batch runs out, the closest pairs are searched for
+/// again with twice as many wanted, so connecting K pairs in total costs as much as finding the K
+/// closest pairs a constant number of times.
+pub struct IncrementalCircuits<'a> {
+    points: &'a [Point],
+    dsu: Dsu,
+    search: PairSearch,
+    /// The closest pairs found so far, in order.
+    pairs: Vec<Edge>,
+    /// The index in `pairs` of the next pair to connect.
+    next: usize,
 }
 
-impl Dsu {
-    fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            size: vec![1; n],
-            components: n,
+/// A pair of junction boxes connected by [`IncrementalCircuits::connect_next`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Connection {
+    /// The indices of the two boxes, in input order.
+    pub boxes: (usize, usize),
+    /// Whether the boxes were in different circuits, which are now merged.
+    pub merged: bool,
+}
+
+impl IncrementalCircuits<'_> {
+    /// Connects the closest pair of boxes not yet connected, or returns `None` if every pair is.
+    pub fn connect_next(&mut self) -> Option<Connection> {
+        if self.next == self.pairs.len() {
+            self.fetch((2 * self.pairs.len()).max(64));
         }
+        let edge = *self.pairs.get(self.next)?;
+        self.next += 1;
+        Some(Connection {
+            boxes: (edge.from, edge.to),
+            merged: self.dsu.union(edge.from, edge.to),
+        })
     }
 
-    fn find(&mut self, mut x: usize) -> usize {
-        let mut root = x;
-        while self.parent[root] != root {
-            root = self.parent[root];
+    /// Connects up to `count` more pairs, stopping early if every pair is connected.
+    pub fn connect(&mut self, count: usize) {
+        self.fetch(self.next + count);
+        for _ in 0..count {
+            if self.connect_next().is_none() {
+                break;
+            }
         }
-        while self.parent[x] != x {
-            let p = self.parent[x];
-            self.parent[x] = root;
-            x = p;
+    }
+
+    /// Returns the number of circuits.
+    pub fn components(&self) -> usize {
+        self.dsu.components()
+    }
+
+    /// Returns the sizes of the `n` largest circuits (or of all of them, if there are fewer),
+    /// largest first.
+    pub fn largest_sizes(&self, n: usize) -> Vec<usize> {
+        let mut sizes = self.dsu.component_sizes();
+        sizes.sort_unstable_by_key(|&size| std::cmp::Reverse(size));
+        sizes.truncate(n);
+        sizes
+    }
+
+    /// Makes sure at least the `count` closest pairs (or all pairs, if there are fewer) are known.
+    fn fetch(&mut self, count: usize) {
+        let n = self.points.len();
+        let count = count.min(n * n.saturating_sub(1) / 2);
+        if count > self.pairs.len() {
+            self.pairs = closest_pairs(self.points, count, self.search);
         }
-        root
+    }
+}
+
+/// A static k-d tree over a set of points.
+///
+/// The tree is stored implicitly: the node for the subarray `lo..hi` of `nodes` sits at its middle
+/// index `(lo + hi) / 2`, splits on `axes` at that index, and has the two halves as its subtrees.
+struct KdTree<'a> {
+    points: &'a [Point],
+    nodes: Vec<usize>,
+    axes: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    /// Builds a balanced tree, splitting each subtree at the median of its widest axis.
+    ///
+    /// Time complexity: O(N log N)
+    /// Auxiliary space complexity: O(N)
+    fn new(points: &'a [Point]) -> Self {
+        let mut tree = Self {
+            points,
+            nodes: (0..points.len()).collect(),
+            axes: vec![0; points.len()],
+        };
+        tree.build(0, points.len());
+        tree
     }
 
-    fn union(&mut self, a: usize, b: usize) -> bool {
-        let mut ra = self.find(a);
-        let mut rb = self.find(b);
-        if ra == rb {
-            return false;
+    fn build(&mut self, lo: usize, hi: usize) {
+        if hi - lo <= 1 {
+            return;
         }
-        if self.size[ra] < self.size[rb] {
-            std::mem::swap(&mut ra, &mut rb);
+        let points = self.points;
+        let axis = (0..3)
+            .max_by_key(|&axis| {
+                let coords = self.nodes[lo..hi].iter().map(|&i| points[i].coord(axis));
+                let (min, max) = coords.fold((i64::MAX, i64::MIN), |(min, max), c| {
+                    (min.min(c), max.max(c))
+                });
+                max - min
+            })
+            .unwrap();
+        let mid = (lo + hi) / 2;
+        self.nodes[lo..hi].select_nth_unstable_by_key(mid - lo, |&i| points[i].coord(axis));
+        self.axes[mid] = axis;
+        self.build(lo, mid);
+        self.build(mid + 1, hi);
+    }
+
+    /// Visits the points within squared distance `bound` of `target`, nearer subtrees first.
+    ///
+    /// `visit` is called with each candidate point and its squared distance, and returns the new
+    /// (never larger) bound. Subtrees whose root node `prune` accepts are skipped entirely.
+    fn search(
+        &self,
+        target: &Point,
+        bound: u64,
+        prune: &impl Fn(usize) -> bool,
+        visit: &mut impl FnMut(usize, u64) -> u64,
+    ) {
+        self.search_range(0, self.nodes.len(), target, bound, prune, visit);
+    }
+
+    fn search_range(
+        &self,
+        lo: usize,
+        hi: usize,
+        target: &Point,
+        mut bound: u64,
+        prune: &impl Fn(usize) -> bool,
+        visit: &mut impl FnMut(usize, u64) -> u64,
+    ) -> u64 {
+        if lo >= hi {
+            return bound;
+        }
+        let mid = (lo + hi) / 2;
+        if prune(mid) {
+            return bound;
+        }
+        let point = self.nodes[mid];
+        let dist2 = target.dist2(&self.points[point]);
+        if dist2 <= bound {
+            bound = visit(point, dist2);
+        }
+        let axis = self.axes[mid];
+        let diff = target.coord(axis) - self.points[point].coord(axis);
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        bound = self.search_range(near.0, near.1, target, bound, prune, visit);
+        if (diff * diff) as u64 <= bound {
+            bound = self.search_range(far.0, far.1, target, bound, prune, visit);
         }
-        self.parent[rb] = ra;
-        self.size[ra] += self.size[rb];
-        self.components -= 1;
-        true
+        bound
     }
 
-    fn component_sizes(&mut self) -> Vec<usize> {
-        let n = self.parent.len();
-        for i in 0..n {
-            self.find(i);
+    /// For every node, records the component shared by all points of its subtree, or `None` if
+    /// the subtree spans several components.
+    fn uniform_components(&self, component: &[usize], uniform: &mut [Option<usize>]) {
+        if !self.nodes.is_empty() {
+            self.uniform_range(0, self.nodes.len(), component, uniform);
         }
-        let mut sizes = Vec::new();
-        for i in 0..n {
-            if self.parent[i] == i {
-                sizes.push(self.size[i]);
+    }
+
+    fn uniform_range(
+        &self,
+        lo: usize,
+        hi: usize,
+        component: &[usize],
+        uniform: &mut [Option<usize>],
+    ) -> Option<usize> {
+        let mid = (lo + hi) / 2;
+        let mut label = Some(component[self.nodes[mid]]);
+        for (lo, hi) in [(lo, mid), (mid + 1, hi)] {
+            if lo < hi && self.uniform_range(lo, hi, component, uniform) != label {
+                label = None;
             }
         }
-        sizes
+        uniform[mid] = label;
+        label
     }
 }
 
-fn short_connections_product(points: &[Point], count: usize) -> usize {
-    let n = points.len();
+/// Returns the `count` closest pairs of points, ordered by distance and then by indices.
+///
+/// Pairs are normally streamed through a bounded max-heap of size `count`, so at most `count`
+/// edges are ever held. When `count` is a sizeable fraction of all pairs, the k-d tree can prune
+/// little and the heap only adds overhead, so all pairs are materialized and selected with
+/// `select_nth_unstable` instead; memory then stays within a small multiple of `count`. With
+/// [`PairSearch::Exhaustive`] they always are.
+fn closest_pairs(points: &[Point], count: usize, search: PairSearch) -> Vec<Edge> {
+    let num_pairs = points.len() * points.len().saturating_sub(1) / 2;
+    if count == 0 {
+        Vec::new()
+    } else if search == PairSearch::Exhaustive
+        || count.saturating_mul(EXHAUSTIVE_FRACTION) >= num_pairs
+    {
+        closest_pairs_exhaustive(points, count)
+    } else {
+        closest_pairs_streaming(points, count)
+    }
+}
+
+/// `closest_pairs` materializes all pairs once `count` reaches one in this many of them.
+const EXHAUSTIVE_FRACTION: usize = 16;
+
+/// Finds the closest pairs with a bounded max-heap fed by k-d tree searches.
+fn closest_pairs_streaming(points: &[Point], count: usize) -> Vec<Edge> {
+    let tree = KdTree::new(points);
     let mut edges: BinaryHeap<Edge> = BinaryHeap::with_capacity(count + 1);
-    for i in 0..n {
-        for j in (i + 1)..n {
-            let d = points[i].dist2(&points[j]);
-            if edges.len() < count {
-                edges.push(Edge {
-                    from: i,
-                    to: j,
-                    dist2: d,
-                });
-            } else if let Some(top) = edges.peek()
-                && d < top.dist2
-            {
-                edges.pop();
-                edges.push(Edge {
-                    from: i,
-                    to: j,
-                    dist2: d,
-                });
+    let bound = |edges: &BinaryHeap<Edge>| match edges.peek() {
+        Some(top) if edges.len() == count => top.dist2,
+        _ => u64::MAX,
+    };
+    for (i, point) in points.iter().enumerate() {
+        tree.search(point, bound(&edges), &|_| false, &mut |j, dist2| {
+            if j > i {
+                let edge = Edge::new(i, j, dist2);
+                if edges.len() < count {
+                    edges.push(edge);
+                } else if edge < *edges.peek().unwrap() {
+                    edges.pop();
+                    edges.push(edge);
+                }
             }
-        }
+            bound(&edges)
+        });
     }
+    edges.into_sorted_vec()
+}
+
+/// Finds the closest pairs by selecting from the full list of pairs.
+///
+/// Rows of pairs `(i, i + 1..)` are generated in parallel on the global rayon pool. Whenever a
+/// task's buffer grows past twice `count`, it keeps only the `count` smallest pairs. The partial
+/// results are merged the same way, and the survivors are sorted in parallel.
+fn closest_pairs_exhaustive(points: &[Point], count: usize) -> Vec<Edge> {
+    let n = points.len();
+    let keep_smallest = |edges: &mut Vec<Edge>, limit: usize| {
+        if edges.len() > limit {
+            edges.select_nth_unstable(count);
+            edges.truncate(count);
+        }
+    };
+    let mut edges = (0..n)
+        .into_par_iter()
+        .fold(Vec::new, |mut edges: Vec<Edge>, i| {
+            let a = &points[i];
+            let row = points.iter().enumerate().skip(i + 1);
+            edges.extend(row.map(|(j, b)| Edge::new(i, j, a.dist2(b))));
+            keep_smallest(&mut edges, 2 * count);
+            edges
+        })
+        .reduce(Vec::new, |mut a, b| {
+            a.extend(b);
+            keep_smallest(&mut a, 2 * count);
+            a
+        });
+    keep_smallest(&mut edges, count);
+    edges.par_sort_unstable();
+    edges
+}
+
+/// Builds the minimum spanning tree of the points with Kruskal's algorithm, joining every pair
+/// in order of distance unless it closes a cycle.
+///
+/// Time complexity: O(N^2 log N)
+/// Auxiliary space complexity: O(N^2)
+fn minimum_spanning_tree_exhaustive(points: &[Point]) -> Vec<Edge> {
+    let n = points.len();
+    let mut dsu = Dsu::new(n);
+    closest_pairs_exhaustive(points, n * n.saturating_sub(1) / 2)
+        .into_iter()
+        .filter(|e| dsu.union(e.from, e.to))
+        .collect()
+}
+
+/// Builds the minimum spanning tree of the points with Borůvka's algorithm.
+///
+/// Within a round the circuits are independent, so each searches for its cheapest outgoing edge
+/// on its own rayon task. The edges are then added sequentially.
+fn minimum_spanning_tree(points: &[Point]) -> Vec<Edge> {
+    let n = points.len();
+    let tree = KdTree::new(points);
     let mut dsu = Dsu::new(n);
-    for e in edges {
-        dsu.union(e.from, e.to);
+    let mut mst: Vec<Edge> = Vec::with_capacity(n.saturating_sub(1));
+    let mut component = vec![0; n];
+    let mut uniform: Vec<Option<usize>> = vec![None; n];
+    let mut order: Vec<usize> = (0..n).collect();
+    while dsu.components() > 1 {
+        for (i, c) in component.iter_mut().enumerate() {
+            *c = dsu.find(i);
+        }
+        tree.uniform_components(&component, &mut uniform);
+        order.sort_unstable_by_key(|&i| component[i]);
+        let members: Vec<&[usize]> = order
+            .chunk_by(|&a, &b| component[a] == component[b])
+            .collect();
+        let cheapest: Vec<Edge> = members
+            .par_iter()
+            .filter_map(|members| {
+                let c = component[members[0]];
+                let prune = |node: usize| uniform[node] == Some(c);
+                let mut cheapest: Option<Edge> = None;
+                for &i in members.iter() {
+                    let bound = cheapest.map_or(u64::MAX, |e| e.dist2);
+                    tree.search(&points[i], bound, &prune, &mut |j, dist2| {
+                        let edge = Edge::new(i, j, dist2);
+                        if component[j] != c && cheapest.is_none_or(|best| edge < best) {
+                            cheapest = Some(edge);
+                        }
+                        cheapest.map_or(u64::MAX, |e| e.dist2)
+                    });
+                }
+                cheapest
+            })
+            .collect();
+        for edge in cheapest {
+            if dsu.union(edge.from, edge.to) {
+                mst.push(edge);
+            }
+        }
     }
-    let mut sizes = dsu.component_sizes();
-    sizes.select_nth_unstable_by_key(2, |&x| std::cmp::Reverse(x));
-    sizes.truncate(3);
-    sizes.into_iter().take(3).product()
+    mst
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY08 as EXAMPLE;
+    use crate::puzzle::example_tests;
+    use rand::Rng;
+
+    example_tests! {
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "25272",
+    }
 
     #[test]
     fn test_part_1_example_1() {
@@ -313,47 +669,151 @@ mod tests {
                 z: 689,
             },
         ];
-        assert_eq!(short_connections_product(&points, 10), 40);
+        for search in [PairSearch::KdTree, PairSearch::Exhaustive] {
+            let day = Day {
+                points: points.clone(),
+                search,
+            };
+            assert_eq!(day.connections_product(10, 3), 40);
+        }
+    }
+
+    /// All pairs in order, as the previous exhaustive implementation considered them.
+    fn all_edges(points: &[Point]) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                edges.push(Edge::new(i, j, points[i].dist2(&points[j])));
+            }
+        }
+        edges.sort();
+        edges
+    }
+
+    fn random_points(rng: &mut impl Rng, n: usize, range: i64) -> Vec<Point> {
+        (0..n)
+            .map(|_| Point {
+                x: rng.random_range(0..range),
+                y: rng.random_range(0..range),
+                z: rng.random_range(0..range),
+            })
+            .collect()
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/08")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "26400");
+    fn test_closest_pairs_match_exhaustive_search() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let n = rng.random_range(2..80);
+            // A small coordinate range produces many ties.
+            let range = if rng.random_range(0..2) == 0 {
+                8
+            } else {
+                100_000
+            };
+            let points = random_points(&mut rng, n, range);
+            let count = rng.random_range(0..n * (n - 1) / 2 + 2);
+            let mut expected = all_edges(&points);
+            expected.truncate(count);
+            assert!(closest_pairs(&points, count, PairSearch::KdTree) == expected);
+        }
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_matches_kruskal() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let n = rng.random_range(2..80);
+            let range = if rng.random_range(0..2) == 0 {
+                8
+            } else {
+                100_000
+            };
+            let points = random_points(&mut rng, n, range);
+            let mut dsu = Dsu::new(n);
+            let mut expected: Vec<Edge> = all_edges(&points)
+                .into_iter()
+                .filter(|e| dsu.union(e.from, e.to))
+                .collect();
+            let mut mst = minimum_spanning_tree(&points);
+            mst.sort();
+            expected.sort();
+            assert!(mst == expected);
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_strategies_agree() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..20 {
+            let n = rng.random_range(2..200);
+            let points = random_points(&mut rng, n, 50);
+            let count = rng.random_range(1..n * (n - 1) / 2 + 1);
+            assert!(
+                closest_pairs_streaming(&points, count) == closest_pairs_exhaustive(&points, count)
+            );
+        }
+    }
+
+    #[test]
+    fn test_component_sizes() {
+        let day = Day::new(EXAMPLE);
+        assert_eq!(
+            day.component_sizes(10),
+            vec![5, 4, 2, 2, 1, 1, 1, 1, 1, 1, 1]
+        );
+        assert_eq!(day.connections_product(10, 2), 20);
+        assert_eq!(day.connections_product(10, 100), 80);
+        assert_eq!(day.component_sizes(0), vec![1; 20]);
+        assert_eq!(day.connections_product(190, 3), 20);
     }
 
     #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            162,817,812\n\
-            57,618,57\n\
-            906,360,560\n\
-            592,479,940\n\
-            352,342,300\n\
-            466,668,158\n\
-            542,29,236\n\
-            431,825,988\n\
-            739,650,466\n\
-            52,470,668\n\
-            216,146,977\n\
-            819,987,18\n\
-            117,168,530\n\
-            805,96,715\n\
-            346,949,466\n\
-            970,615,88\n\
-            941,993,340\n\
-            862,61,35\n\
-            984,92,344\n\
-            425,690,689";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "25272");
+    fn test_circuits_graph() {
+        let graph = Day::new(EXAMPLE).circuits_graph(10);
+        let dot = graph.contents();
+        assert_eq!(dot.matches("fillcolor=").count(), 20);
+        assert_eq!(dot.matches("fillcolor=\"#c0c0c0\"").count(), 7);
+        // The 10 connections, one of which closes a loop, plus the 10 other spanning tree edges.
+        assert_eq!(dot.matches(" -- ").count(), 20);
+        assert_eq!(dot.matches("style=\"dashed\"").count(), 10);
+        assert!(dot.contains(r#""0" [label="162,817,812", "#));
+        // Asking for more connections than there are pairs connects every pair.
+        let graph = Day::new(EXAMPLE).circuits_graph(usize::MAX);
+        assert_eq!(graph.contents().matches(" -- ").count(), 190);
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/08")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "8199963486");
+    fn test_incremental_circuits() {
+        let day = Day::new(EXAMPLE);
+        let mut circuits = day.circuits();
+        assert_eq!(circuits.components(), 20);
+        assert_eq!(
+            circuits.connect_next(),
+            Some(Connection {
+                boxes: (0, 19),
+                merged: true
+            })
+        );
+        assert_eq!(circuits.components(), 19);
+        circuits.connect(9);
+        assert_eq!(circuits.largest_sizes(3), vec![5, 4, 2]);
+        assert_eq!(circuits.components(), 11);
+        // Keep going past the first batch until everything is one circuit; the last merge is the
+        // part 2 connection.
+        let mut last_merge = None;
+        while circuits.components() > 1 {
+            let connection = circuits.connect_next().unwrap();
+            if connection.merged {
+                last_merge = Some(connection.boxes);
+            }
+        }
+        let (a, b) = last_merge.unwrap();
+        assert_eq!(day.points[a].x * day.points[b].x, 25272);
+        assert_eq!(circuits.largest_sizes(3), vec![20]);
+        for _ in 0..200 {
+            circuits.connect_next();
+        }
+        assert_eq!(circuits.connect_next(), None);
     }
 }
@@ -1,7 +1,18 @@
+use crate::geom::{Point2, inside_cells_prefix_sum};
+use crate::parse::{Cursor, ParseError};
 use crate::puzzle::Puzzle;
-use std::collections::HashMap;
+use crate::render::{Svg, Visualization};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type Point = Point2<i64>;
 
 pub struct Day {
+    /// The closed loops in input order: an outer boundary and, optionally, holes or further
+    /// islands.
+    loops: Vec<Vec<Point>>,
+    /// The vertices of all loops.
     points: Vec<Point>,
 }
 
@@ -9,27 +20,18 @@ impl Puzzle for Day {
     /// Find the maximum inclusive-tile area of an axis-aligned rectangle whose
     /// two opposite corners are red tiles (points).
     ///
-    /// This checks all pairs with light pruning after sorting by X.
+    /// A rectangle spans either from a lower-left to an upper-right corner or from an upper-left
+    /// to a lower-right one; the second case is the first with the y-axis mirrored. For the first,
+    /// only lower-left corners with no other point below-left of them, and upper-right corners
+    /// with no other point above-right of them, can be optimal. Both form staircases sorted by x,
+    /// and the best partner of a lower-left corner moves monotonically along the upper staircase,
+    /// so divide and conquer over the lower staircase finds the best pair. The halves of each
+    /// step are searched in parallel.
     ///
-    /// Time complexity: O(N^2)
+    /// Time complexity: O(N log N)
     /// Auxiliary space complexity: O(N)
     fn solve_part_1(&self) -> String {
-        let min_y = self.points.iter().map(|p| p.1).min().unwrap();
-        let max_y = self.points.iter().map(|p| p.1).max().unwrap();
-        let max_possible_height = (max_y - min_y).abs() as i128 + 1;
-        let pts = self.sorted_points();
-        let x_last = pts.last().unwrap().0;
-        let mut best: i128 = 0;
-        for (i, &(x1, y1)) in pts.iter().enumerate() {
-            let max_possible_width = (x_last - x1).abs() as i128 + 1;
-            if max_possible_width * max_possible_height <= best {
-                continue;
-            }
-            for &(x2, y2) in pts.iter().skip(i + 1) {
-                best = std::cmp::max(best, inclusive_area((x1, y1), (x2, y2)));
-            }
-        }
-        best.to_string()
+        self.best_corner_rectangle().0.to_string()
     }
 
     /// Interprets the input as a rectilinear polygonal loop (points in order),
@@ -37,203 +39,711 @@ impl Puzzle for Day {
     /// opposite corners are vertices and whose interior lies completely inside
     /// the polygon.
     ///
+    /// Several loops separated by blank lines describe a polygon with holes (or several
+    /// islands): a cell is inside if it is enclosed by an odd number of loops.
+    ///
     /// Uses coordinate compression and a scanline parity fill to build a grid of
     /// inside-cells, then a 2D prefix sum for O(1) area-inside queries.
     ///
+    /// Candidate first corners are searched in parallel. Tasks share the best area found so far
+    /// so that each can skip pairs that cannot beat it.
+    ///
     /// Time complexity: O(N^2)
     /// Auxiliary space complexity: O(N^2)
     fn solve_part_2(&self) -> String {
-        let (xs, ys, x_index, y_index) = compress_axes(&self.points);
-        let v_edges = build_vertical_edges(&self.points, &x_index);
-        let pref = build_prefix_sums(&xs, &ys, &v_edges);
-        let pts = self.sorted_points();
-        let x_last = pts.last().unwrap().0;
-        let max_possible_height = (ys.last().unwrap() - ys[0]).abs() as i128 + 1;
-        let mut best: i128 = 0;
-        for (i, &(x1, y1)) in pts.iter().enumerate() {
-            let max_possible_width = (x_last - x1).abs() as i128 + 1;
-            if max_possible_width * max_possible_height <= best {
-                continue;
-            }
-            let xi1 = *x_index.get(&x1).unwrap();
-            let yi1 = *y_index.get(&y1).unwrap();
-            for &(x2, y2) in pts.iter().skip(i + 1) {
-                let area = inclusive_area((x1, y1), (x2, y2));
-                if area <= best {
+        self.best_inside_rectangle().0.to_string()
+    }
+
+    /// Draws the loop over its interior, with the best rectangles of part 1 (blue) and part 2
+    /// (orange) outlined. One tile is one user unit.
+    fn visualize(&self) -> Option<Visualization> {
+        let cells = inside_cells_prefix_sum(&self.loops);
+        let (xs, ys) = (cells.xs(), cells.ys());
+        let (x0, y0) = (xs[0], ys[0]);
+        // Tile (x, y) covers the unit square whose top-left corner is at (x - x0, y - y0).
+        let corner = |x: i64, y: i64| ((x - x0) as f64, (y - y0) as f64);
+        let center = |p: &Point| ((p.x - x0) as f64 + 0.5, (p.y - y0) as f64 + 0.5);
+        let (width, height) = corner(xs[xs.len() - 1] + 1, ys[ys.len() - 1] + 1);
+        let stroke = width.max(height) / 500.0;
+        let mut svg = Svg::new(width, height);
+        svg.rect(0.0, 0.0, width, height, "#ffffff", None);
+        // Interior cells, merged into horizontal runs.
+        let inside = |r: usize, c: usize| cells.is_inside(r, c);
+        for r in 0..ys.len() - 1 {
+            let mut c = 0;
+            while c < xs.len() - 1 {
+                if !inside(r, c) {
+                    c += 1;
                     continue;
                 }
-                let xi2 = *x_index.get(&x2).unwrap();
-                let yi2 = *y_index.get(&y2).unwrap();
-                let x_min = xi1.min(xi2);
-                let x_max = xi1.max(xi2);
-                let y_min = yi1.min(yi2);
-                let y_max = yi1.max(yi2);
-                let target_cells = ((x_max - x_min) * (y_max - y_min)) as i128;
-                let actual_cells = rect_sum(&pref, x_min, x_max, y_min, y_max);
-                if actual_cells == target_cells {
-                    best = area;
+                let start = c;
+                while c < xs.len() - 1 && inside(r, c) {
+                    c += 1;
                 }
+                let (x, y) = center(&Point::new(xs[start], ys[r]));
+                let (w, h) = ((xs[c] - xs[start]) as f64, (ys[r + 1] - ys[r]) as f64);
+                svg.rect(x, y, w, h, "#c8e6c9", None);
             }
         }
-        best.to_string()
+        for points in &self.loops {
+            let outline: Vec<(f64, f64)> = points.iter().map(center).collect();
+            svg.outline(&outline, "#2e7d32", stroke, None);
+        }
+        let best = [
+            ("part 1", self.best_corner_rectangle(), "#1565c0"),
+            ("part 2", self.best_inside_rectangle(), "#ef6c00"),
+        ];
+        for (part, (area, a, b), color) in best {
+            let (left, top) = corner(a.x.min(b.x), a.y.min(b.y));
+            let (right, bottom) = corner(a.x.max(b.x) + 1, a.y.max(b.y) + 1);
+            let rect = [(left, top), (right, top), (right, bottom), (left, bottom)];
+            let title = format!("{part}: {area} tiles");
+            svg.outline(&rect, color, 2.0 * stroke, Some(&title));
+        }
+        Some(svg.finish())
     }
 }
 
-type Point = (i64, i64);
+/// An inclusive-tile area together with the two opposite corners spanning it.
+pub type Rectangle = (i128, Point, Point);
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let points: Vec<Point> = input
-            .trim()
-            .lines()
-            .map(|line| {
-                let mut it = line.trim().split(',');
-                let x = it.next().unwrap().parse::<i64>().unwrap();
-                let y = it.next().unwrap().parse::<i64>().unwrap();
-                (x, y)
+        Box::new(Self::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self::try_new(input).unwrap_or_else(|e| panic!("Day 9: {e}"))
+    }
+
+    pub fn try_create(input: &str) -> Result<Box<dyn Puzzle>, ParseError> {
+        Ok(Box::new(Self::try_new(input)?))
+    }
+
+    /// Parses the loops, reporting input that does not describe closed rectilinear loops: a line
+    /// that is not `x,y`, a loop with fewer than four vertices, a repeated vertex, a segment that
+    /// is not axis-aligned, or two segments that touch or cross anywhere other than at the vertex
+    /// joining them.
+    pub fn try_new(input: &str) -> Result<Self, ParseError> {
+        let mut loops: Vec<Vec<Point>> = Vec::new();
+        let mut line_numbers: Vec<Vec<usize>> = Vec::new();
+        for mut section in Cursor::new(input).split_sections() {
+            let mut points = Vec::new();
+            let mut numbers = Vec::new();
+            for mut line in section.lines() {
+                numbers.push(line.line());
+                line.skip_whitespace();
+                let x = line.parse_i64()?;
+                line.skip_whitespace();
+                line.expect_char(',')?;
+                line.skip_whitespace();
+                let y = line.parse_i64()?;
+                line.expect_end()?;
+                points.push(Point::new(x, y));
+            }
+            loops.push(points);
+            line_numbers.push(numbers);
+        }
+        if loops.is_empty() {
+            return Err(ParseError::new(
+                1,
+                "expected at least one loop of `x,y` points",
+            ));
+        }
+        validate_loops(&loops, &line_numbers)?;
+        let points = loops.concat();
+        Ok(Day { loops, points })
+    }
+
+    /// Returns the largest rectangle with red tiles at two opposite corners (part 1).
+    fn best_corner_rectangle(&self) -> Rectangle {
+        let mirrored: Vec<Point> = self.points.iter().map(|p| Point::new(p.x, -p.y)).collect();
+        let (area, a, b) = max_corner_rectangle(&mirrored);
+        let mirrored = (area, Point::new(a.x, -a.y), Point::new(b.x, -b.y));
+        std::cmp::max_by_key(max_corner_rectangle(&self.points), mirrored, |r| r.0)
+    }
+
+    /// Returns the largest rectangle with red tiles at two opposite corners that lies inside the
+    /// loop (part 2).
+    fn best_inside_rectangle(&self) -> Rectangle {
+        let cells = inside_cells_prefix_sum(&self.loops);
+        let ys = cells.ys();
+        let pts = self.sorted_points();
+        let x_last = pts.last().unwrap().x;
+        let max_possible_height = (ys.last().unwrap() - ys[0]).abs() as i128 + 1;
+        // The best area found by any task so far, shared for pruning.
+        let shared_best = AtomicU64::new(0);
+        pts.par_iter()
+            .enumerate()
+            .filter_map(|(i, &a)| {
+                let mut best: Option<Rectangle> = None;
+                let bound = |best: Option<Rectangle>| {
+                    let shared = shared_best.load(Ordering::Relaxed) as i128;
+                    best.map_or(0, |r| r.0).max(shared)
+                };
+                let max_possible_width = (x_last - a.x).abs() as i128 + 1;
+                if max_possible_width * max_possible_height <= bound(best) {
+                    return best;
+                }
+                let corner1 = (cells.x_index(a.x).unwrap(), cells.y_index(a.y).unwrap());
+                for &b in pts.iter().skip(i + 1) {
+                    let area = inclusive_area(a, b);
+                    if area <= bound(best) {
+                        continue;
+                    }
+                    let corner2 = (cells.x_index(b.x).unwrap(), cells.y_index(b.y).unwrap());
+                    if cells.contains_rect(corner1, corner2) {
+                        best = Some((area, a, b));
+                        let hint = u64::try_from(area).unwrap_or(u64::MAX);
+                        shared_best.fetch_max(hint, Ordering::Relaxed);
+                    }
+                }
+                best
             })
-            .collect();
-        Box::new(Day { points })
+            .max_by_key(|r| r.0)
+            .unwrap()
+    }
+
+    /// Returns the largest rectangle that lies inside the loops, with corners anywhere rather than
+    /// only at red tiles.
+    ///
+    /// A largest rectangle cannot grow in any direction, so each of its sides runs along a
+    /// coordinate of the loops. Each row of the compressed grid is therefore the base of a
+    /// histogram whose bars are the runs of inside cells above it, and the classic monotonic stack
+    /// finds the widest span under each bar. Widths and heights are measured in tiles rather than
+    /// cells, which keeps the area of a span increasing with its width.
+    ///
+    /// Time complexity: O(N^2)
+    /// Auxiliary space complexity: O(N^2)
+    pub fn largest_inscribed_rectangle(&self) -> Rectangle {
+        let cells = inside_cells_prefix_sum(&self.loops);
+        let (xs, ys) = (cells.xs(), cells.ys());
+        let w = xs.len() - 1;
+        // The compressed row at which the run of inside cells in each column starts.
+        let mut tops: Vec<Option<usize>> = vec![None; w];
+        let mut best: Rectangle = (0, self.points[0], self.points[0]);
+        for r in 0..ys.len() - 1 {
+            for (c, top) in tops.iter_mut().enumerate() {
+                *top = if cells.is_inside(r, c) {
+                    top.or(Some(r))
+                } else {
+                    None
+                };
+            }
+            let height = |c: usize| tops[c].map_or(0, |top| (ys[r + 1] - ys[top]) as i128 + 1);
+            // Columns with increasing heights, each with the leftmost column of its span.
+            let mut stack: Vec<(usize, usize)> = Vec::new();
+            for c in 0..=w {
+                let h = if c < w { height(c) } else { 0 };
+                let mut left = c;
+                while let Some(&(col, start)) = stack.last() {
+                    if height(col) < h {
+                        break;
+                    }
+                    stack.pop();
+                    left = start;
+                    let top = tops[col].unwrap();
+                    let area = height(col) * ((xs[c] - xs[start]) as i128 + 1);
+                    if area > best.0 {
+                        best = (
+                            area,
+                            Point::new(xs[start], ys[top]),
+                            Point::new(xs[c], ys[r + 1]),
+                        );
+                    }
+                }
+                if h > 0 {
+                    stack.push((c, left));
+                }
+            }
+        }
+        best
     }
 
     /// Returns points sorted by x-coordinate.
     fn sorted_points(&self) -> Vec<Point> {
         let mut pts = self.points.clone();
-        pts.sort_unstable_by_key(|p| p.0);
+        pts.sort_unstable_by_key(|p| p.x);
         pts
     }
 }
 
-/// Inclusive tile-area for two opposite corners.
-fn inclusive_area(a: Point, b: Point) -> i128 {
-    let dx = (a.0 - b.0).abs() as i128 + 1;
-    let dy = (a.1 - b.1).abs() as i128 + 1;
-    dx * dy
+/// Returns the largest rectangle with a lower-left corner and an upper-right corner among
+/// `points`.
+fn max_corner_rectangle(points: &[Point]) -> Rectangle {
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable();
+    // Points with nothing below-left of them, by increasing x and decreasing y.
+    let mut lower: Vec<Point> = Vec::new();
+    for &p in &sorted {
+        if lower.last().is_none_or(|q| p.y < q.y) {
+            lower.push(p);
+        }
+    }
+    // Points with nothing above-right of them, by increasing x and decreasing y.
+    let mut upper: Vec<Point> = Vec::new();
+    for &p in sorted.iter().rev() {
+        if upper.last().is_none_or(|q| p.y > q.y) {
+            upper.push(p);
+        }
+    }
+    upper.reverse();
+    max_staircase_rectangle(&lower, &upper).unwrap()
 }
-type CompressedAxes = (Vec<i64>, Vec<i64>, HashMap<i64, usize>, HashMap<i64, usize>);
-
-/// Builds sorted unique coordinate axes and index maps.
-fn compress_axes(points: &[Point]) -> CompressedAxes {
-    let mut xs: Vec<i64> = points.iter().map(|p| p.0).collect();
-    let mut ys: Vec<i64> = points.iter().map(|p| p.1).collect();
-    xs.sort_unstable();
-    xs.dedup();
-    ys.sort_unstable();
-    ys.dedup();
-    let x_index = xs
-        .iter()
-        .copied()
-        .enumerate()
-        .map(|(i, x)| (x, i))
-        .collect();
-    let y_index = ys
+
+/// Finds the best upper corner for the middle lower corner, then recurses on either side: lower
+/// corners further left pair best with upper corners no further right, and vice versa. The two
+/// sides are searched in parallel.
+fn max_staircase_rectangle(lower: &[Point], upper: &[Point]) -> Option<Rectangle> {
+    if lower.is_empty() {
+        return None;
+    }
+    let mid = lower.len() / 2;
+    let (best_j, best) = upper
         .iter()
-        .copied()
+        .map(|&b| (spanning_area(lower[mid], b), lower[mid], b))
         .enumerate()
-        .map(|(i, y)| (y, i))
-        .collect();
-    (xs, ys, x_index, y_index)
+        .max_by_key(|&(_, r)| r.0)
+        .unwrap();
+    let (left, right) = rayon::join(
+        || max_staircase_rectangle(&lower[..mid], &upper[..=best_j]),
+        || max_staircase_rectangle(&lower[mid + 1..], &upper[best_j..]),
+    );
+    [Some(best), left, right]
+        .into_iter()
+        .flatten()
+        .max_by_key(|r| r.0)
+}
+
+/// Inclusive tile-area of the rectangle from lower-left corner `a` to upper-right corner `b`. This
+/// is negative if `b` lies on only one wrong side of `a`, and `i128::MIN` if it lies on both.
+fn spanning_area(a: Point, b: Point) -> i128 {
+    let dx = (b.x - a.x) as i128 + 1;
+    let dy = (b.y - a.y) as i128 + 1;
+    if dx <= 0 && dy <= 0 {
+        i128::MIN
+    } else {
+        dx * dy
+    }
+}
+
+/// A side of a loop, from vertex `index` to the next one.
+struct Segment {
+    loop_index: usize,
+    index: usize,
+    from: Point,
+    to: Point,
+    /// The line of the `from` vertex.
+    line: usize,
 }
 
-/// Extract vertical edges from an ordered polygonal chain.
-fn build_vertical_edges(points: &[Point], x_index: &HashMap<i64, usize>) -> Vec<Vec<(i64, i64)>> {
-    let mut xs: Vec<i64> = x_index.keys().copied().collect();
-    xs.sort_unstable();
-    let mut v_edges: Vec<Vec<(i64, i64)>> = vec![vec![]; xs.len()];
-    let n = points.len();
-    for i in 0..n {
-        let p1 = points[i];
-        let p2 = points[(i + 1) % n];
-        if p1.0 == p2.0
-            && let Some(&xi) = x_index.get(&p1.0)
-        {
-            let y_min = p1.1.min(p2.1);
-            let y_max = p1.1.max(p2.1);
-            v_edges[xi].push((y_min, y_max));
-        }
-    }
-
-    v_edges
+impl Segment {
+    fn is_horizontal(&self) -> bool {
+        self.from.y == self.to.y
+    }
+
+    /// Whether the two segments are consecutive sides of the same loop.
+    fn is_adjacent(&self, other: &Segment, loops: &[Vec<Point>]) -> bool {
+        let n = loops[self.loop_index].len();
+        self.loop_index == other.loop_index
+            && (other.index == (self.index + 1) % n || self.index == (other.index + 1) % n)
+    }
+
+    /// The error for two segments that touch, reported on the later line.
+    fn touch_error(&self, other: &Segment) -> ParseError {
+        let (first, second) = if self.line <= other.line {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        ParseError::new(
+            second.line,
+            format!(
+                "segment from {} to {} touches the segment from {} to {} on line {}",
+                second.from, second.to, first.from, first.to, first.line
+            ),
+        )
+    }
 }
 
-/// Build a 2D prefix sum over compressed cells indicating interior of the polygon.
-fn build_prefix_sums(xs: &[i64], ys: &[i64], v_edges: &[Vec<(i64, i64)>]) -> Vec<Vec<i128>> {
-    let w = xs.len() - 1;
-    let h = ys.len() - 1;
-    let mut pref = vec![vec![0i128; w + 1]; h + 1];
-    for r in 0..h {
-        let y_start = ys[r];
-        let y_end = ys[r + 1];
-        let mut inside = false;
-        let mut row_sum: i128 = 0;
-        for c in 0..w {
-            if let Some(edges) = v_edges.get(c) {
-                for &(ey_min, ey_max) in edges {
-                    if ey_min <= y_start && ey_max >= y_end {
-                        inside = !inside;
+/// Checks that the loops are closed rectilinear loops that neither touch themselves nor each
+/// other.
+///
+/// Touching horizontal and vertical segments are found with a sweep over x that keeps the
+/// horizontal segments spanning the current x ordered by y. Collinear segments need no separate
+/// check: where one overlaps another, the loop turns at a vertex inside the other, and the side
+/// leaving that vertex touches it (or the vertex repeats one of the other's).
+///
+/// Time complexity: O(N log N)
+/// Auxiliary space complexity: O(N)
+fn validate_loops(loops: &[Vec<Point>], line_numbers: &[Vec<usize>]) -> Result<(), ParseError> {
+    let mut seen: HashMap<Point, usize> = HashMap::new();
+    let mut segments: Vec<Segment> = Vec::new();
+    for (loop_index, (points, lines)) in loops.iter().zip(line_numbers).enumerate() {
+        if points.len() < 4 {
+            return Err(ParseError::new(
+                lines[0],
+                format!(
+                    "loop has {} vertices, but a closed rectilinear loop needs at least 4",
+                    points.len()
+                ),
+            ));
+        }
+        for (index, (&point, &line)) in points.iter().zip(lines).enumerate() {
+            if let Some(first) = seen.insert(point, line) {
+                return Err(ParseError::new(
+                    line,
+                    format!("vertex {point} repeats the vertex on line {first}"),
+                ));
+            }
+            let to = points[(index + 1) % points.len()];
+            if point.x != to.x && point.y != to.y {
+                return Err(ParseError::new(
+                    line,
+                    format!("segment from {point} to {to} is not axis-aligned"),
+                ));
+            }
+            segments.push(Segment {
+                loop_index,
+                index,
+                from: point,
+                to,
+                line,
+            });
+        }
+    }
+
+    // Perpendicular segments: add horizontal segments at their left end, check vertical segments,
+    // then remove horizontal segments at their right end.
+    let mut events: Vec<(i64, u8, usize)> = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let (x_min, x_max) = min_max(segment.from.x, segment.to.x);
+        if segment.is_horizontal() {
+            events.push((x_min, 0, i));
+            events.push((x_max, 2, i));
+        } else {
+            events.push((x_min, 1, i));
+        }
+    }
+    events.sort_unstable();
+    let mut active: BTreeSet<(i64, usize)> = BTreeSet::new();
+    for (_, kind, i) in events {
+        let segment = &segments[i];
+        match kind {
+            0 => {
+                active.insert((segment.from.y, i));
+            }
+            2 => {
+                active.remove(&(segment.from.y, i));
+            }
+            _ => {
+                let (y_min, y_max) = min_max(segment.from.y, segment.to.y);
+                for &(_, j) in active.range((y_min, 0)..=(y_max, usize::MAX)) {
+                    if !segment.is_adjacent(&segments[j], loops) {
+                        return Err(segment.touch_error(&segments[j]));
                     }
                 }
             }
-            if inside {
-                row_sum += 1;
-            }
-            pref[r + 1][c + 1] = pref[r][c + 1] + row_sum;
         }
     }
-    pref
+
+    Ok(())
 }
 
-/// Query number of inside cells in rectangle of compressed cell indices.
-fn rect_sum(pref: &[Vec<i128>], x_min: usize, x_max: usize, y_min: usize, y_max: usize) -> i128 {
-    pref[y_max][x_max] - pref[y_min][x_max] - pref[y_max][x_min] + pref[y_min][x_min]
+fn min_max(a: i64, b: i64) -> (i64, i64) {
+    (a.min(b), a.max(b))
+}
+
+/// Inclusive tile-area for two opposite corners.
+fn inclusive_area(a: Point, b: Point) -> i128 {
+    let dx = (a.x - b.x).abs() as i128 + 1;
+    let dy = (a.y - b.y).abs() as i128 + 1;
+    dx * dy
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY09 as EXAMPLE;
+    use crate::geom::{self, BoundingBox};
+    use crate::puzzle::example_tests;
+
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "50",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "24",
+    }
+
+    /// The previous all-pairs search, kept as a reference for the staircase search.
+    fn brute_force_part_1(points: &[Point]) -> i128 {
+        let mut best = 0;
+        for (i, &a) in points.iter().enumerate() {
+            for &b in &points[i + 1..] {
+                best = best.max(inclusive_area(a, b));
+            }
+        }
+        best
+    }
 
     #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            7,1\n\
-            11,1\n\
-            11,7\n\
-            9,7\n\
-            9,5\n\
-            2,5\n\
-            2,3\n\
-            7,3";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "50");
+    fn test_part_1_matches_brute_force() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..500 {
+            let n = rng.random_range(2..60);
+            let range = if rng.random_range(0..2) == 0 {
+                10
+            } else {
+                1_000_000
+            };
+            let points: Vec<Point> = (0..n)
+                .map(|_| Point::new(rng.random_range(0..range), rng.random_range(0..range)))
+                .collect();
+            let day = Day {
+                loops: vec![points.clone()],
+                points,
+            };
+            let (area, a, b) = day.best_corner_rectangle();
+            assert_eq!(area, brute_force_part_1(&day.points), "{:?}", day.points);
+            assert_eq!(area, inclusive_area(a, b));
+            assert!(day.points.contains(&a) && day.points.contains(&b));
+        }
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/09")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "4786902990");
+    fn test_visualize() {
+        let puzzle = Day::create(EXAMPLE);
+        let vis = puzzle.visualize().unwrap();
+        let svg = vis.contents();
+        assert!(svg.contains(r#"viewBox="0 0 10 7""#));
+        assert!(svg.contains("<title>part 1: 50 tiles</title>"));
+        assert!(svg.contains("<title>part 2: 24 tiles</title>"));
+        // The interior runs of the three compressed rows: x 7..11, then 2..11, then 9..11.
+        assert_eq!(svg.matches(r##"fill="#c8e6c9""##).count(), 3);
+        assert!(svg.contains(r#"<polygon points="5.5,0.5 9.5,0.5 9.5,6.5 "#));
+    }
+
+    #[test]
+    fn test_part_2_with_hole() {
+        let outer = "0,0\n10,0\n10,10\n0,10";
+        let puzzle = Day::create(outer);
+        assert_eq!(puzzle.solve_part_2(), "121");
+        let input = "\
+            0,0\n\
+            10,0\n\
+            10,10\n\
+            0,10\n\
+            \n\
+            4,4\n\
+            6,4\n\
+            6,6\n\
+            4,6";
+        let puzzle = Day::create(input);
+        assert_eq!(puzzle.solve_part_1(), "121");
+        // No rectangle may cover the hole, so the best ones run from a corner of the outer loop to
+        // the far side of the hole, e.g. (0,0) to (6,4).
+        assert_eq!(puzzle.solve_part_2(), "35");
     }
 
     #[test]
-    fn test_part_2_example_1() {
+    fn test_part_2_with_islands() {
+        // Two separate squares: rectangles spanning the gap between them are not inside.
         let input = "\
-            7,1\n\
-            11,1\n\
-            11,7\n\
-            9,7\n\
-            9,5\n\
-            2,5\n\
-            2,3\n\
-            7,3";
+            0,0\n\
+            3,0\n\
+            3,3\n\
+            0,3\n\
+            \n\
+            \n\
+            10,0\n\
+            15,0\n\
+            15,5\n\
+            10,5";
         let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "24");
+        assert_eq!(puzzle.solve_part_1(), "96");
+        assert_eq!(puzzle.solve_part_2(), "36");
+    }
+
+    #[test]
+    fn test_largest_inscribed_rectangle() {
+        let example = "7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3";
+        let day = Day::new(example);
+        // The band from x 2 to 11 between y 3 and 5 has no red tiles at its top corners.
+        assert_eq!(
+            day.largest_inscribed_rectangle(),
+            (30, Point::new(2, 3), Point::new(11, 5))
+        );
+        assert_eq!(day.best_inside_rectangle().0, 24);
+        let hole = "0,0\n10,0\n10,10\n0,10\n\n4,4\n6,4\n6,6\n4,6";
+        let day = Day::new(hole);
+        assert_eq!(day.largest_inscribed_rectangle().0, 55);
+        assert_eq!(day.best_inside_rectangle().0, 35);
+    }
+
+    /// Returns the largest rectangle inside the loops by trying every pair of corners. The loops
+    /// are scaled by two so that checking the integer points of a rectangle also checks the
+    /// middle of every tile edge.
+    fn brute_force_inscribed(loops: &[Vec<Point>]) -> i128 {
+        let scaled: Vec<Vec<Point>> = loops
+            .iter()
+            .map(|points| {
+                points
+                    .iter()
+                    .map(|p| Point::new(2 * p.x, 2 * p.y))
+                    .collect()
+            })
+            .collect();
+        let BoundingBox { min, max } = BoundingBox::of(loops.iter().flatten()).unwrap();
+        let ((x_lo, y_lo), (x_hi, y_hi)) = ((min.x, min.y), (max.x, max.y));
+        let mut best = 0;
+        for x1 in x_lo..=x_hi {
+            for x2 in x1..=x_hi {
+                for y1 in y_lo..=y_hi {
+                    for y2 in y1..=y_hi {
+                        let area = inclusive_area(Point::new(x1, y1), Point::new(x2, y2));
+                        if area <= best {
+                            continue;
+                        }
+                        let inside = (2 * x1..=2 * x2).all(|x| {
+                            (2 * y1..=2 * y2)
+                                .all(|y| geom::contains_point(&scaled, Point::new(x, y)))
+                        });
+                        if inside {
+                            best = area;
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_largest_inscribed_rectangle_matches_brute_force() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..200 {
+            // A column chart with a ragged top and bottom, where neighbouring columns overlap.
+            let n = rng.random_range(1..6);
+            let mut xs: Vec<i64> = vec![0];
+            let mut columns: Vec<(i64, i64)> = Vec::new();
+            while columns.len() < n {
+                let bottom: i64 = rng.random_range(0..6);
+                let top = rng.random_range(bottom + 1..8);
+                if let Some(&(b, t)) = columns.last()
+                    && (bottom.max(b) >= top.min(t) || (bottom, top) == (b, t))
+                {
+                    continue;
+                }
+                columns.push((bottom, top));
+                xs.push(xs.last().unwrap() + rng.random_range(1..4i64));
+            }
+            let mut points = Vec::new();
+            for (i, &(bottom, _)) in columns.iter().enumerate() {
+                if i == 0 || columns[i - 1].0 != bottom {
+                    points.push((xs[i], bottom));
+                }
+                if i + 1 == n || columns[i + 1].0 != bottom {
+                    points.push((xs[i + 1], bottom));
+                }
+            }
+            for (i, &(_, top)) in columns.iter().enumerate().rev() {
+                if i + 1 == n || columns[i + 1].1 != top {
+                    points.push((xs[i + 1], top));
+                }
+                if i == 0 || columns[i - 1].1 != top {
+                    points.push((xs[i], top));
+                }
+            }
+            let input: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+            let day = Day::new(&input.join("\n"));
+            let (area, a, b) = day.largest_inscribed_rectangle();
+            assert_eq!(area, brute_force_inscribed(&day.loops), "{points:?}");
+            assert_eq!(area, inclusive_area(a, b));
+            assert!(area >= day.best_inside_rectangle().0);
+        }
+    }
+
+    fn parse_error(input: &str) -> ParseError {
+        Day::try_create(input).err().unwrap()
+    }
+
+    #[test]
+    fn test_valid_loops_are_accepted() {
+        let input = "7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3";
+        assert!(Day::try_create(input).is_ok());
+        // A vertex in the middle of a straight side is fine.
+        assert!(Day::try_create("0,0\n5,0\n10,0\n10,10\n0,10").is_ok());
+        assert!(Day::try_create("0,0\n10,0\n10,10\n0,10\n\n4,4\n6,4\n6,6\n4,6").is_ok());
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let error = parse_error("0,0\n10;0\n10,10\n0,10");
+        assert_eq!(error, ParseError::at(2, 3, "expected `,`, found `;`"));
+        let error = parse_error(" -3 , 4 \n5,x");
+        assert_eq!(error, ParseError::at(2, 3, "expected a number, found `x`"));
+        assert_eq!(parse_error("").line, 1);
+    }
+
+    #[test]
+    fn test_too_few_vertices() {
+        let error = parse_error("0,0\n10,0\n\n0,5\n10,5\n10,10\n0,10");
+        assert_eq!(error.line, 1);
+        assert!(error.message.contains("needs at least 4"));
+    }
+
+    #[test]
+    fn test_repeated_vertex() {
+        let error = parse_error("0,0\n10,0\n10,10\n10,0\n0,10");
+        assert_eq!(
+            error,
+            ParseError::new(4, "vertex (10, 0) repeats the vertex on line 2")
+        );
+    }
+
+    #[test]
+    fn test_diagonal_segment() {
+        let error = parse_error("0,0\n10,0\n10,10\n5,5\n0,10");
+        assert_eq!(
+            error,
+            ParseError::new(3, "segment from (10, 10) to (5, 5) is not axis-aligned")
+        );
+    }
+
+    #[test]
+    fn test_self_intersection() {
+        // The side from (5,-5) to (5,5) crosses the side from (0,0) to (10,0).
+        let error = parse_error("0,0\n10,0\n10,10\n0,10\n0,5\n5,5\n5,-5\n-1,-5\n-1,0");
+        assert_eq!(
+            error,
+            ParseError::new(
+                6,
+                "segment from (5, 5) to (5, -5) touches the segment from (0, 0) to (10, 0) on line 1"
+            )
+        );
+        // Backtracking along a side overlaps it.
+        let error = parse_error("20,0\n30,0\n25,0\n25,-5\n20,-5");
+        assert_eq!(
+            error,
+            ParseError::new(
+                3,
+                "segment from (25, 0) to (25, -5) touches the segment from (20, 0) to (30, 0) on line 1"
+            )
+        );
+        // A hole touching the outer boundary.
+        let error = parse_error("0,0\n10,0\n10,10\n0,10\n\n0,4\n6,4\n6,6\n0,6");
+        assert_eq!(
+            error,
+            ParseError::new(
+                6,
+                "segment from (0, 4) to (6, 4) touches the segment from (0, 10) to (0, 0) on line 4"
+            )
+        );
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/09")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "1571016172");
+    #[should_panic(expected = "Day 9: line 2, column 1: expected a number, found `n`")]
+    fn test_create_reports_errors() {
+        Day::create("0,0\nnot a point");
     }
 }
@@ -1,6 +1,9 @@
+use crate::bitset::BitSet;
+use crate::parse::{Cursor, ParseError};
 use crate::puzzle::Puzzle;
+use crate::search;
+use num::{Rational64, Zero};
 use rayon::prelude::*;
-use std::collections::VecDeque;
 
 pub struct Day {
     machines: Vec<Machine>,
@@ -8,19 +11,22 @@ pub struct Day {
 
 impl Puzzle for Day {
     /// For each machine, compute the minimum number of button presses needed to reach the target
-    /// lighting pattern (treating each button as a bitmask toggle), then sum these minima across
-    /// all machines.
+    /// lighting pattern (treating each button as a toggle), then sum these minima across all
+    /// machines.
     ///
-    /// This uses a BFS over the state space of light configurations.
+    /// Pressing a button twice undoes it, so this is a linear system over GF(2) with one equation
+    /// per light and one variable per button. Gaussian elimination on bitset rows leaves F free
+    /// buttons, and we walk the 2^F solutions in Gray code order for the one with fewest presses.
     ///
-    /// Time complexity: O(N * B * 2^L) where N is the number of machines, B is the number of
-    /// buttons per machine, and L is the number of lights per machine.
-    /// Auxiliary space complexity: O(2^L)
+    /// Time complexity: O(N * (L * B^2 / W + 2^F * B / W)) where N is the number of machines, B is
+    /// the number of buttons per machine, L is the number of lights per machine, and W is the word
+    /// size.
+    /// Auxiliary space complexity: O(L * B / W + F * B / W)
     fn solve_part_1(&self) -> String {
         self.machines
-            .iter()
+            .par_iter()
             .map(|m| m.min_lighting_presses().unwrap())
-            .sum::<usize>()
+            .sum::<u64>()
             .to_string()
     }
 
@@ -29,10 +35,17 @@ impl Puzzle for Day {
     ///
     /// We form this problem as a system of linear equations and solve for non-negative integer
     /// solutions that minimize the sum of variables by solving for the Reduced Row Echelon Form.
+    /// The elimination is carried out over exact rationals, so no tolerance is needed to decide
+    /// whether a pivot is zero or a derived press count is a whole number. The presses of the
+    /// buttons left free by elimination are then found by branch-and-bound, using the simplex
+    /// method on the LP relaxation for lower bounds.
     ///
     /// Time complexity: Constraint construction is O(N * B * L^2) where N is the number of
     /// machines, B is the number of buttons per machine, and L is the number of lights per machine.
-    /// Auxiliary space complexity: O(B * L)
+    /// Each branch-and-bound node solves an LP over O(B) constraints; the number of nodes is
+    /// exponential in the worst case, but small in practice since the relaxation prunes most
+    /// branches.
+    /// Auxiliary space complexity: O(B * L + B^2 * D) where D is the depth of the search.
     fn solve_part_2(&self) -> String {
         self.machines
             .par_iter()
@@ -42,96 +55,227 @@ impl Puzzle for Day {
     }
 }
 
-struct Machine {
+/// A machine's indicator lights, its buttons, and the joltage each counter must reach.
+pub struct Machine {
     num_lights: usize,
-    lighting_goal: u16,
-    button_masks: Vec<u16>,
+    lighting_goal: Vec<bool>,
     button_wires: Vec<Vec<usize>>,
     joltage_goal: Vec<usize>,
 }
 
-struct SearchContext<'a> {
-    free_vars: &'a [usize],
-    bounds: &'a [u64],
-    matrix: &'a Vec<Vec<f64>>,
-    pivot_cols: &'a [usize],
-}
-
 impl Machine {
-    fn from_line(line: &str) -> Self {
-        let (rest, joltage_part) = line.split_once('{').unwrap();
-        let (lights_part, buttons_part) = rest.split_once(']').unwrap();
-        let lights_str = lights_part.trim_start_matches('[');
-        let num_lights = lights_str.len();
-        let mut lighting_goal = 0;
-        for (i, c) in lights_str.chars().enumerate() {
-            if c == '#' {
-                lighting_goal |= 1 << (num_lights - i - 1);
-            }
-        }
-        let joltage_goal: Vec<usize> = joltage_part
-            .trim_end_matches('}')
-            .split(',')
-            .map(|s| s.trim().parse().unwrap())
-            .collect();
-        let mut button_masks = Vec::new();
+    /// Parses a line such as `[.##.] (3) (1,3) (2) {3,5,4}`: the lighting pattern, the lights or
+    /// counters wired to each button, and the joltage targets.
+    ///
+    /// Panics if the line is malformed; see [`Machine::parse`].
+    pub fn from_line(line: &str) -> Self {
+        Self::parse(Cursor::new(line)).unwrap_or_else(|e| panic!("Day 10: {e}"))
+    }
+
+    /// Parses a machine from the line under `line`, reporting where the line goes wrong and what
+    /// was expected there. Spaces around tokens are ignored.
+    pub fn parse(mut line: Cursor) -> Result<Self, ParseError> {
+        line.skip_whitespace();
+        if !line.eat_char('[') {
+            return Err(line.expected("`[` to start the lights"));
+        }
+        let mut lighting_goal = Vec::new();
+        while !line.eat_char(']') {
+            match line.peek() {
+                Some('.') => lighting_goal.push(false),
+                Some('#') => lighting_goal.push(true),
+                _ => return Err(line.expected("`.` or `#` in the lights, or `]` to close them")),
+            }
+            line.bump();
+        }
+        let num_lights = lighting_goal.len();
         let mut button_wires = Vec::new();
-        for segment in buttons_part.split('(').skip(1) {
-            let content = segment.split(')').next().unwrap();
-            let wires: Vec<usize> = content
-                .split(',')
-                .map(|s| s.trim().parse().unwrap())
-                .collect();
-            let mut mask = 0;
-            for &wire in &wires {
-                mask |= 1 << (num_lights - wire - 1);
+        line.skip_whitespace();
+        while line.eat_char('(') {
+            let mut wires = Vec::new();
+            loop {
+                line.skip_whitespace();
+                let start = line;
+                let wire = parse_usize(&mut line)?;
+                if wire >= num_lights {
+                    return Err(start.error(format!(
+                        "button wires light {wire}, but there are only {num_lights} lights"
+                    )));
+                }
+                wires.push(wire);
+                line.skip_whitespace();
+                if !line.eat_char(',') {
+                    break;
+                }
+            }
+            if !line.eat_char(')') {
+                return Err(line.expected("`,` or `)` to close a button"));
             }
             button_wires.push(wires);
-            button_masks.push(mask);
+            line.skip_whitespace();
+        }
+        let targets = line;
+        if !line.eat_char('{') {
+            return Err(line.expected("`(` to start a button or `{` to start the joltage targets"));
+        }
+        let mut joltage_goal = Vec::new();
+        loop {
+            line.skip_whitespace();
+            joltage_goal.push(parse_usize(&mut line)?);
+            line.skip_whitespace();
+            if !line.eat_char(',') {
+                break;
+            }
         }
-        Machine {
+        if !line.eat_char('}') {
+            return Err(line.expected("`,` or `}` to close the joltage targets"));
+        }
+        if joltage_goal.len() != num_lights {
+            return Err(targets.error(format!(
+                "expected {num_lights} joltage targets, found {}",
+                joltage_goal.len()
+            )));
+        }
+        line.expect_end()?;
+        Ok(Machine {
             num_lights,
             lighting_goal,
-            button_masks,
             button_wires,
             joltage_goal,
-        }
+        })
+    }
+
+    pub fn num_lights(&self) -> usize {
+        self.num_lights
+    }
+
+    /// For each light, whether it must end up on.
+    pub fn lighting_goal(&self) -> &[bool] {
+        &self.lighting_goal
     }
 
-    fn min_lighting_presses(&self) -> Option<usize> {
-        let target = self.lighting_goal;
-        let limit = 1 << self.num_lights;
-        let mut visited = vec![false; limit];
-        let mut queue = VecDeque::new();
-        queue.push_back((0, 0));
-        visited[0] = true;
-        while let Some((current, steps)) = queue.pop_front() {
-            for &mask in &self.button_masks {
-                let next_val = current ^ mask;
-                if next_val == target {
-                    return Some(steps + 1);
+    /// For each button, the lights (and counters) it is wired to.
+    pub fn button_wires(&self) -> &[Vec<usize>] {
+        &self.button_wires
+    }
+
+    /// For each counter, the joltage it must reach.
+    pub fn joltage_goal(&self) -> &[usize] {
+        &self.joltage_goal
+    }
+
+    /// Returns which lights are on after pressing each button the given number of times, starting
+    /// with every light off.
+    pub fn lights_after(&self, presses: &[u64]) -> Vec<bool> {
+        let mut lights = vec![false; self.num_lights];
+        for (wires, &count) in self.button_wires.iter().zip(presses) {
+            if count % 2 == 1 {
+                for &light in wires {
+                    lights[light] = !lights[light];
                 }
-                let next_idx = next_val as usize;
-                if next_idx < limit && !visited[next_idx] {
-                    visited[next_idx] = true;
-                    queue.push_back((next_val, steps + 1));
+            }
+        }
+        lights
+    }
+
+    /// Returns the joltage of each counter after pressing each button the given number of times,
+    /// starting from zero.
+    pub fn joltage_after(&self, presses: &[u64]) -> Vec<u64> {
+        let mut joltage = vec![0; self.joltage_goal.len()];
+        for (wires, &count) in self.button_wires.iter().zip(presses) {
+            for &counter in wires {
+                joltage[counter] += count;
+            }
+        }
+        joltage
+    }
+
+    /// Returns the fewest button presses that produce the lighting goal, or `None` if it cannot be
+    /// produced.
+    pub fn min_lighting_presses(&self) -> Option<u64> {
+        self.lighting_solution().map(|presses| presses.iter().sum())
+    }
+
+    /// Returns how many times to press each button to produce the lighting goal with the fewest
+    /// presses in total, or `None` if it cannot be produced. No button is pressed more than once.
+    ///
+    /// After elimination, the search takes time exponential in the smaller of the number of free
+    /// buttons and the rank: it tries every combination of free buttons, or searches the patterns
+    /// of the independent lights breadth first. It also returns `None` for machines with 64 or
+    /// more free buttons and a rank above 20, which neither search can cover.
+    pub fn lighting_solution(&self) -> Option<Vec<u64>> {
+        let num_buttons = self.button_wires.len();
+        // One row per light: the buttons wired to it, and whether it must end up on.
+        let mut rows: Vec<(BitSet, bool)> = self
+            .lighting_goal
+            .iter()
+            .map(|&on| (BitSet::new(num_buttons), on))
+            .collect();
+        for (btn_idx, wires) in self.button_wires.iter().enumerate() {
+            for &light in wires {
+                rows[light].0.toggle(btn_idx);
+            }
+        }
+        let mut pivot_cols = Vec::new();
+        for col in 0..num_buttons {
+            let pivot_row = pivot_cols.len();
+            let Some(selection) = (pivot_row..rows.len()).find(|&r| rows[r].0.test(col)) else {
+                continue;
+            };
+            rows.swap(pivot_row, selection);
+            let (pivot_bits, pivot_on) = rows[pivot_row].clone();
+            for (i, (bits, on)) in rows.iter_mut().enumerate() {
+                if i != pivot_row && bits.test(col) {
+                    bits.xor_with(&pivot_bits);
+                    *on ^= pivot_on;
                 }
             }
+            pivot_cols.push(col);
+        }
+        if rows.iter().skip(pivot_cols.len()).any(|&(_, on)| on) {
+            return None;
+        }
+        // The solution with every free button unpressed.
+        let mut presses = BitSet::new(num_buttons);
+        for (&(_, on), &col) in rows.iter().zip(&pivot_cols) {
+            if on {
+                presses.toggle(col);
+            }
         }
-        None
+        let free_cols: Vec<usize> = (0..num_buttons)
+            .filter(|col| !pivot_cols.contains(col))
+            .collect();
+        let rank = pivot_cols.len();
+        let best = if free_cols.len() <= rank || rank > MAX_SYNDROME_BITS {
+            if free_cols.len() >= 64 {
+                return None;
+            }
+            fewest_presses_by_free_buttons(&rows, &pivot_cols, &free_cols, presses)
+        } else {
+            fewest_presses_by_syndrome(&rows[..rank], num_buttons)
+        };
+        Some((0..num_buttons).map(|col| best.test(col) as u64).collect())
+    }
+
+    /// Returns the fewest button presses that bring every counter to its joltage goal, or `None`
+    /// if the goals cannot be reached.
+    pub fn min_joltage_presses(&self) -> Option<u64> {
+        self.joltage_solution().map(|presses| presses.iter().sum())
     }
 
-    fn min_joltage_presses(&self) -> Option<u64> {
+    /// Returns how many times to press each button to bring every counter to its joltage goal
+    /// with the fewest presses in total, or `None` if the goals cannot be reached.
+    pub fn joltage_solution(&self) -> Option<Vec<u64>> {
         let num_vars = self.button_wires.len();
         let num_eqs = self.num_lights;
-        let mut matrix = vec![vec![0.0; num_vars + 1]; num_eqs];
+        let mut matrix = vec![vec![Rational64::zero(); num_vars + 1]; num_eqs];
         for (btn_idx, wires) in self.button_wires.iter().enumerate() {
             for &light_idx in wires {
-                matrix[light_idx][btn_idx] = 1.0;
+                matrix[light_idx][btn_idx] = Rational64::from_integer(1);
             }
         }
         for (light_idx, &goal) in self.joltage_goal.iter().enumerate() {
-            matrix[light_idx][num_vars] = goal as f64;
+            matrix[light_idx][num_vars] = Rational64::from_integer(goal as i64);
         }
         let mut pivot_row = 0;
         let mut pivot_cols = Vec::new();
@@ -140,7 +284,7 @@ impl Machine {
                 break;
             }
             let mut selection = pivot_row;
-            while selection < num_eqs && matrix[selection][col].abs() < 1e-9 {
+            while selection < num_eqs && matrix[selection][col].is_zero() {
                 selection += 1;
             }
             if selection < num_eqs {
@@ -153,7 +297,7 @@ impl Machine {
                 for (i, row) in matrix.iter_mut().enumerate() {
                     if i != pivot_row {
                         let factor = row[col];
-                        if factor.abs() > 1e-9 {
+                        if !factor.is_zero() {
                             for (target, &source) in row
                                 .iter_mut()
                                 .skip(col)
@@ -169,7 +313,7 @@ impl Machine {
             }
         }
         for row in matrix.iter().skip(pivot_row) {
-            if row[num_vars].abs() > 1e-4 {
+            if !row[num_vars].is_zero() {
                 return None;
             }
         }
@@ -179,120 +323,834 @@ impl Machine {
                 free_vars.push(col);
             }
         }
-        let mut best_total = None;
-        let mut bounds = vec![u64::MAX; num_vars];
+        let mut bounds = vec![0; num_vars];
         for (btn_idx, wires) in self.button_wires.iter().enumerate() {
-            for &light in wires {
-                let limit = self.joltage_goal[light] as u64;
-                if limit < bounds[btn_idx] {
-                    bounds[btn_idx] = limit;
+            bounds[btn_idx] = wires
+                .iter()
+                .map(|&light| self.joltage_goal[light] as i64)
+                .min()
+                .unwrap_or(0);
+        }
+        // Every press count is an affine function of the free variables: a free variable is
+        // itself, and a pivot variable is its row's target minus the free variables' shares.
+        let mut vars = vec![AffineVar::default(); num_vars];
+        for (k, &col) in free_vars.iter().enumerate() {
+            vars[col] = AffineVar {
+                offset: Rational64::zero(),
+                coeffs: (0..free_vars.len())
+                    .map(|i| Rational64::from_integer((i == k) as i64))
+                    .collect(),
+            };
+        }
+        for (row, &col) in pivot_cols.iter().enumerate() {
+            vars[col] = AffineVar {
+                offset: matrix[row][num_vars],
+                coeffs: free_vars.iter().map(|&f| -matrix[row][f]).collect(),
+            };
+        }
+        let mut program = LinearProgram {
+            objective: vec![Rational64::zero(); free_vars.len()],
+            constraints: Vec::new(),
+        };
+        let mut constant = Rational64::zero();
+        for (var, &bound) in vars.iter().zip(&bounds) {
+            constant += var.offset;
+            for (total, &coeff) in program.objective.iter_mut().zip(&var.coeffs) {
+                *total += coeff;
+            }
+            program.add_at_least(var, Rational64::zero());
+            program.add_at_most(var, Rational64::from_integer(bound));
+        }
+        let ranges: Vec<(i64, i64)> = free_vars.iter().map(|&f| (0, bounds[f])).collect();
+        let mut best = None;
+        branch_and_bound(&vars, constant, &mut program, ranges, &mut best);
+        best.map(|(_, presses)| presses)
+    }
+}
+
+/// A press count written as `offset + coeffs · y` over the free variables `y`.
+#[derive(Clone, Default)]
+struct AffineVar {
+    offset: Rational64,
+    coeffs: Vec<Rational64>,
+}
+
+impl AffineVar {
+    fn eval(&self, point: &[Rational64]) -> Rational64 {
+        self.coeffs
+            .iter()
+            .zip(point)
+            .fold(self.offset, |acc, (&c, &y)| acc + c * y)
+    }
+}
+
+/// Minimizes `objective · y` subject to `coeffs · y <= limit` for each constraint and `y >= 0`.
+struct LinearProgram {
+    objective: Vec<Rational64>,
+    constraints: Vec<(Vec<Rational64>, Rational64)>,
+}
+
+impl LinearProgram {
+    /// Adds the constraint `var <= value`.
+    fn add_at_most(&mut self, var: &AffineVar, value: Rational64) {
+        self.constraints
+            .push((var.coeffs.clone(), value - var.offset));
+    }
+
+    /// Adds the constraint `var >= value`.
+    fn add_at_least(&mut self, var: &AffineVar, value: Rational64) {
+        let coeffs = var.coeffs.iter().map(|&c| -c).collect();
+        self.constraints.push((coeffs, var.offset - value));
+    }
+
+    /// Narrows the integer range of each variable to the values that every constraint allows given
+    /// the ranges of the others, and returns `false` if some constraint cannot be met at all.
+    ///
+    /// For a constraint `coeffs · y <= limit`, the other variables contribute at least their
+    /// smallest term over their ranges, which leaves a residual budget for each variable. Ranges
+    /// shrink as budgets are rounded to integers, which the LP relaxation alone cannot do.
+    fn tighten(&self, ranges: &mut [(i64, i64)]) -> bool {
+        let term_min = |c: Rational64, (lo, hi): (i64, i64)| (c * lo).min(c * hi);
+        // Each round can only shave a little off a range, so stop early and let the LP finish.
+        for _ in 0..TIGHTEN_ROUNDS {
+            let mut changed = false;
+            for (coeffs, limit) in &self.constraints {
+                let min_sum = coeffs
+                    .iter()
+                    .zip(ranges.iter())
+                    .fold(Rational64::zero(), |acc, (&c, &r)| acc + term_min(c, r));
+                if min_sum > *limit {
+                    return false;
                 }
+                for (k, &c) in coeffs.iter().enumerate() {
+                    if c.is_zero() {
+                        continue;
+                    }
+                    let budget = (*limit - (min_sum - term_min(c, ranges[k]))) / c;
+                    let (lo, hi) = &mut ranges[k];
+                    if c > Rational64::zero() {
+                        let bound = budget.floor().to_integer();
+                        if bound < *hi {
+                            *hi = bound;
+                            changed = true;
+                        }
+                    } else {
+                        let bound = budget.ceil().to_integer();
+                        if bound > *lo {
+                            *lo = bound;
+                            changed = true;
+                        }
+                    }
+                    if lo > hi {
+                        return false;
+                    }
+                }
+            }
+            if !changed {
+                break;
             }
         }
-        let free_var_bounds: Vec<u64> = free_vars.iter().map(|&idx| bounds[idx]).collect();
-        let ctx = SearchContext {
-            free_vars: &free_vars,
-            bounds: &free_var_bounds,
-            matrix: &matrix,
-            pivot_cols: &pivot_cols,
+        true
+    }
+
+    /// Minimizes over the given variable ranges, keeping only the constraints that the ranges do
+    /// not already imply.
+    fn minimize_within(&self, ranges: &[(i64, i64)]) -> Option<(Rational64, Vec<Rational64>)> {
+        // Substitute `y = lo + z` so that each range becomes `0 <= z <= hi - lo`.
+        let mut shifted = LinearProgram {
+            objective: self.objective.clone(),
+            constraints: Vec::new(),
         };
-        self.recursive_search(0, &ctx, &mut vec![0; num_vars], &mut best_total);
-        best_total
-    }
-
-    fn recursive_search(
-        &self,
-        free_idx: usize,
-        ctx: &SearchContext,
-        current_sol: &mut Vec<u64>,
-        best_total: &mut Option<u64>,
-    ) {
-        let current_sum: u64 = current_sol.iter().sum();
-        if best_total.is_some_and(|best| current_sum >= best) {
-            return;
-        }
-        if free_idx == ctx.free_vars.len() {
-            let num_vars = current_sol.len();
-            let mut valid = true;
-            let mut derived_sol = current_sol.clone();
-            for (row_idx, &p_col) in ctx.pivot_cols.iter().enumerate() {
-                let mut val = ctx.matrix[row_idx][num_vars];
-                for &f_col in ctx.free_vars {
-                    val -= ctx.matrix[row_idx][f_col] * (current_sol[f_col] as f64);
-                }
-                if val < -1e-4 {
-                    valid = false;
-                    break;
+        for (coeffs, limit) in &self.constraints {
+            let mut limit = *limit;
+            let mut max_sum = Rational64::zero();
+            for (&c, &(lo, hi)) in coeffs.iter().zip(ranges) {
+                limit -= c * lo;
+                max_sum += (c * (hi - lo)).max(Rational64::zero());
+            }
+            if max_sum > limit {
+                shifted.constraints.push((coeffs.clone(), limit));
+            }
+        }
+        for (k, &(lo, hi)) in ranges.iter().enumerate() {
+            let mut coeffs = vec![Rational64::zero(); ranges.len()];
+            coeffs[k] = Rational64::from_integer(1);
+            shifted
+                .constraints
+                .push((coeffs, Rational64::from_integer(hi - lo)));
+        }
+        let (value, mut point) = shifted.minimize()?;
+        for (y, &(lo, _)) in point.iter_mut().zip(ranges) {
+            *y += lo;
+        }
+        let offset = self
+            .objective
+            .iter()
+            .zip(ranges)
+            .fold(Rational64::zero(), |acc, (&c, &(lo, _))| acc + c * lo);
+        Some((value + offset, point))
+    }
+
+    /// Returns the minimum objective value and a point attaining it, or `None` if the
+    /// constraints are infeasible. The feasible region must be bounded.
+    ///
+    /// This is the two-phase tableau simplex method with Bland's rule, which cannot cycle.
+    fn minimize(&self) -> Option<(Rational64, Vec<Rational64>)> {
+        let n = self.objective.len();
+        let m = self.constraints.len();
+        let num_artificial = self
+            .constraints
+            .iter()
+            .filter(|c| c.1 < Rational64::zero())
+            .count();
+        let cols = n + m + num_artificial;
+        let mut tableau = Tableau {
+            rows: Vec::with_capacity(m),
+            basis: Vec::with_capacity(m),
+        };
+        let mut next_artificial = n + m;
+        for (i, (coeffs, limit)) in self.constraints.iter().enumerate() {
+            // The row `coeffs · y + slack = limit`, negated if needed so that its right-hand
+            // side is non-negative, in which case an artificial variable starts in the basis.
+            let mut row = vec![Rational64::zero(); cols + 1];
+            row[..n].copy_from_slice(coeffs);
+            row[n + i] = Rational64::from_integer(1);
+            row[cols] = *limit;
+            if *limit < Rational64::zero() {
+                for val in row.iter_mut() {
+                    *val = -*val;
                 }
-                let rounded = val.round();
-                if (val - rounded).abs() > 1e-4 {
-                    valid = false;
-                    break;
+                row[next_artificial] = Rational64::from_integer(1);
+                tableau.basis.push(next_artificial);
+                next_artificial += 1;
+            } else {
+                tableau.basis.push(n + i);
+            }
+            tableau.rows.push(row);
+        }
+        if num_artificial > 0 {
+            let mut cost = vec![Rational64::zero(); cols];
+            for c in &mut cost[n + m..] {
+                *c = Rational64::from_integer(1);
+            }
+            if !tableau.optimize(&cost, cols).is_zero() {
+                return None;
+            }
+            // Drive the artificial variables, all now zero, out of the basis; a row with no other
+            // non-zero entry is redundant.
+            let mut i = 0;
+            while i < tableau.rows.len() {
+                if tableau.basis[i] >= n + m {
+                    match (0..n + m).find(|&j| !tableau.rows[i][j].is_zero()) {
+                        Some(j) => tableau.pivot(i, j),
+                        None => {
+                            tableau.rows.swap_remove(i);
+                            tableau.basis.swap_remove(i);
+                            continue;
+                        }
+                    }
                 }
-                derived_sol[p_col] = rounded as u64;
+                i += 1;
             }
-            if valid {
-                let total: u64 = derived_sol.iter().sum();
-                match best_total {
-                    Some(b) => *b = (*b).min(total),
-                    None => *best_total = Some(total),
+        }
+        let mut cost = vec![Rational64::zero(); cols];
+        cost[..n].copy_from_slice(&self.objective);
+        let value = tableau.optimize(&cost, n + m);
+        let mut point = vec![Rational64::zero(); n];
+        for (row, &var) in tableau.rows.iter().zip(&tableau.basis) {
+            if var < n {
+                point[var] = row[cols];
+            }
+        }
+        Some((value, point))
+    }
+}
+
+/// A simplex tableau: each row holds the coefficients of every variable followed by the
+/// right-hand side, and `basis[i]` is the variable that row `i` solves for.
+struct Tableau {
+    rows: Vec<Vec<Rational64>>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    /// Minimizes `cost` by letting only the first `allowed` variables enter the basis, and returns
+    /// the minimum.
+    fn optimize(&mut self, cost: &[Rational64], allowed: usize) -> Rational64 {
+        let rhs = cost.len();
+        loop {
+            let reduced = |j: usize| {
+                self.rows
+                    .iter()
+                    .zip(&self.basis)
+                    .fold(cost[j], |acc, (row, &b)| acc - cost[b] * row[j])
+            };
+            let Some(col) = (0..allowed).find(|&j| reduced(j) < Rational64::zero()) else {
+                break;
+            };
+            let row = (0..self.rows.len())
+                .filter(|&i| self.rows[i][col] > Rational64::zero())
+                .min_by_key(|&i| (self.rows[i][rhs] / self.rows[i][col], self.basis[i]))
+                .expect("the linear program is bounded");
+            self.pivot(row, col);
+        }
+        self.rows
+            .iter()
+            .zip(&self.basis)
+            .fold(Rational64::zero(), |acc, (row, &b)| {
+                acc + cost[b] * row[rhs]
+            })
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let pivot_val = self.rows[row][col];
+        for val in self.rows[row].iter_mut() {
+            *val /= pivot_val;
+        }
+        let pivot_row = self.rows[row].clone();
+        for (i, other) in self.rows.iter_mut().enumerate() {
+            let factor = other[col];
+            if i != row && !factor.is_zero() {
+                for (target, &source) in other.iter_mut().zip(&pivot_row) {
+                    *target -= factor * source;
                 }
             }
-            return;
         }
-        let f_var_idx = ctx.free_vars[free_idx];
-        let limit = ctx.bounds[free_idx];
-        for val in 0..=limit {
-            current_sol[f_var_idx] = val;
-            self.recursive_search(free_idx + 1, ctx, current_sol, best_total);
-            current_sol[f_var_idx] = 0;
+        self.basis[row] = col;
+    }
+}
+
+/// The most independent lights for which [`Machine::lighting_solution`] searches their patterns.
+const MAX_SYNDROME_BITS: usize = 20;
+
+/// Finds the fewest presses solving the reduced lighting system by trying every combination of
+/// the free buttons, starting from `presses`, the solution with none of them pressed.
+fn fewest_presses_by_free_buttons(
+    rows: &[(BitSet, bool)],
+    pivot_cols: &[usize],
+    free_cols: &[usize],
+    mut presses: BitSet,
+) -> BitSet {
+    // For each free button, the change to the solution from pressing it.
+    let nullspace: Vec<BitSet> = free_cols
+        .iter()
+        .map(|&free| {
+            let mut delta = BitSet::new(presses.len());
+            delta.toggle(free);
+            for ((bits, _), &col) in rows.iter().zip(pivot_cols) {
+                if bits.test(free) {
+                    delta.toggle(col);
+                }
+            }
+            delta
+        })
+        .collect();
+    let mut best = presses.clone();
+    for step in 1..1u64 << free_cols.len() {
+        // Successive Gray codes differ in the free button given by the lowest set bit.
+        presses.xor_with(&nullspace[step.trailing_zeros() as usize]);
+        if presses.count_ones() < best.count_ones() {
+            best.clone_from(&presses);
         }
     }
+    best
+}
+
+/// Finds the fewest presses solving the reduced lighting system, whose `rows` are the independent
+/// lights, by breadth-first search over the patterns of those lights. A shortest path never
+/// presses a button twice, as the two presses would cancel out.
+fn fewest_presses_by_syndrome(rows: &[(BitSet, bool)], num_buttons: usize) -> BitSet {
+    // Bit i of a pattern is whether the ith independent light is on.
+    let syndromes: Vec<u32> = (0..num_buttons)
+        .map(|col| {
+            rows.iter()
+                .enumerate()
+                .filter(|(_, (bits, _))| bits.test(col))
+                .fold(0, |acc, (i, _)| acc | 1 << i)
+        })
+        .collect();
+    let target = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, on))| *on)
+        .fold(0, |acc, (i, _)| acc | 1 << i);
+    let path = search::bfs(
+        0u32,
+        |&lights| syndromes.iter().map(move |&syndrome| lights ^ syndrome),
+        |&lights| lights == target,
+    )
+    .expect("the reduced rows are independent, so every pattern can be reached");
+    let mut presses = BitSet::new(num_buttons);
+    for step in path.states.windows(2) {
+        let col = syndromes
+            .iter()
+            .position(|&syndrome| syndrome == step[0] ^ step[1]);
+        presses.toggle(col.unwrap());
+    }
+    presses
+}
+
+/// The most rounds of range tightening per branch-and-bound node.
+const TIGHTEN_ROUNDS: usize = 8;
+
+/// Searches for the integer solution with the fewest presses, branching on a press count that is
+/// fractional at the optimum of the LP relaxation and pruning nodes whose relaxation cannot beat
+/// the best total found so far. Before solving the relaxation, each node narrows the free
+/// variables' `ranges` by the residual joltage every constraint leaves, which can rule the node
+/// out without an LP at all.
+fn branch_and_bound(
+    vars: &[AffineVar],
+    constant: Rational64,
+    program: &mut LinearProgram,
+    mut ranges: Vec<(i64, i64)>,
+    best: &mut Option<(u64, Vec<u64>)>,
+) {
+    if !program.tighten(&mut ranges) {
+        return;
+    }
+    let Some((value, point)) = program.minimize_within(&ranges) else {
+        return;
+    };
+    // The total of an integer solution is an integer, so the relaxation bounds it from below by
+    // its ceiling.
+    let lower = (constant + value).ceil().to_integer() as u64;
+    if best.as_ref().is_some_and(|&(total, _)| lower >= total) {
+        return;
+    }
+    let fractional = vars
+        .iter()
+        .map(|var| (var, var.eval(&point)))
+        .find(|(_, val)| !val.is_integer());
+    let Some((var, val)) = fractional else {
+        let presses = vars.iter().map(|var| var.eval(&point).to_integer() as u64);
+        *best = Some((lower, presses.collect()));
+        return;
+    };
+    program.add_at_most(var, val.floor());
+    branch_and_bound(vars, constant, program, ranges.clone(), best);
+    program.constraints.pop();
+    program.add_at_least(var, val.ceil());
+    branch_and_bound(vars, constant, program, ranges, best);
+    program.constraints.pop();
+}
+
+/// Consumes a decimal number, which must fit in a `usize`.
+fn parse_usize(cursor: &mut Cursor) -> Result<usize, ParseError> {
+    let start = *cursor;
+    let n = cursor.parse_u64()?;
+    usize::try_from(n).map_err(|_| start.error(format!("`{n}` is too large for a usize")))
 }
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let machines: Vec<Machine> = input.trim().lines().map(Machine::from_line).collect();
-        Box::new(Day { machines })
+        Box::new(Self::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self::try_new(input).unwrap_or_else(|e| panic!("Day 10: {e}"))
+    }
+
+    pub fn try_create(input: &str) -> Result<Box<dyn Puzzle>, ParseError> {
+        Ok(Box::new(Self::try_new(input)?))
+    }
+
+    /// Parses one machine per non-blank line, reporting the first malformed line.
+    pub fn try_new(input: &str) -> Result<Self, ParseError> {
+        let machines = Cursor::new(input)
+            .lines()
+            .into_iter()
+            .map(Machine::parse)
+            .collect::<Result<Vec<Machine>, _>>()?;
+        if machines.is_empty() {
+            return Err(ParseError::new(1, "expected at least one machine"));
+        }
+        Ok(Day { machines })
+    }
+
+    pub fn machines(&self) -> &[Machine] {
+        &self.machines
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY10 as EXAMPLE;
+    use crate::puzzle::example_tests;
+    use crate::search;
+
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "7",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "33",
+    }
+
+    /// Finds the fewest presses by breadth-first search over the light patterns.
+    fn bfs_lighting_presses(machine: &Machine) -> Option<u64> {
+        let target = machine
+            .lighting_goal
+            .iter()
+            .rev()
+            .fold(0, |acc, &on| acc << 1 | on as usize);
+        let masks: Vec<usize> = machine
+            .button_wires
+            .iter()
+            .map(|wires| wires.iter().fold(0, |acc, &w| acc ^ 1 << w))
+            .collect();
+        search::bfs(
+            0,
+            |&lights| masks.iter().map(move |&mask| lights ^ mask),
+            |&lights| lights == target,
+        )
+        .map(|path| path.cost as u64)
+    }
 
     #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n\
-            [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}\n\
-            [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "7");
+    fn test_lighting_matches_bfs() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..300 {
+            let num_lights = rng.random_range(1..12);
+            let num_buttons = rng.random_range(1..16);
+            let buttons: Vec<String> = (0..num_buttons)
+                .map(|_| {
+                    let wires: Vec<String> = (0..num_lights)
+                        .filter(|_| rng.random_range(0..3) == 0)
+                        .map(|w| w.to_string())
+                        .collect();
+                    if wires.is_empty() {
+                        "(0)".to_string()
+                    } else {
+                        format!("({})", wires.join(","))
+                    }
+                })
+                .collect();
+            let lights: String = (0..num_lights)
+                .map(|_| {
+                    if rng.random_range(0..2) == 0 {
+                        '.'
+                    } else {
+                        '#'
+                    }
+                })
+                .collect();
+            let targets = vec!["0"; num_lights].join(",");
+            let line = format!("[{lights}] {} {{{targets}}}", buttons.join(" "));
+            let machine = Machine::from_line(&line);
+            assert_eq!(
+                machine.min_lighting_presses(),
+                bfs_lighting_presses(&machine),
+                "{line}"
+            );
+            if let Some(presses) = machine.lighting_solution() {
+                assert_eq!(machine.lights_after(&presses), machine.lighting_goal());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lighting_with_many_lights() {
+        // 100 lights and 101 buttons: one per light, and one that toggles every light.
+        let single: Vec<String> = (0..100).map(|i| format!("({i})")).collect();
+        let all: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let wiring = format!(
+            "{} ({}) {{{}}}",
+            single.join(" "),
+            all.join(","),
+            vec!["0"; 100].join(",")
+        );
+        let machine = Machine::from_line(&format!("[{}] {wiring}", "#".repeat(100)));
+        assert_eq!(machine.min_lighting_presses(), Some(1));
+        let pattern = "#".repeat(60) + &".".repeat(40);
+        let machine = Machine::from_line(&format!("[{pattern}] {wiring}"));
+        // Pressing the shared button and then the 40 lights that should stay off beats pressing
+        // the 60 that should be on.
+        assert_eq!(machine.min_lighting_presses(), Some(41));
+        let machine = Machine::from_line(&format!("[{}#] {wiring}", ".".repeat(99)));
+        assert_eq!(machine.min_lighting_presses(), Some(1));
+        let machine = Machine::from_line(&format!("[{}] {wiring}", ".".repeat(100)));
+        assert_eq!(machine.min_lighting_presses(), Some(0));
+    }
+
+    #[test]
+    fn test_lighting_with_many_free_buttons() {
+        // 70 buttons on 3 lights leave at least 67 free buttons, too many to try every
+        // combination of, so the patterns of the 3 lights are searched instead.
+        let wiring = format!("{} (0) (2) {{0,0,0}}", vec!["(1)"; 68].join(" "));
+        let machine = Machine::from_line(&format!("[###] {wiring}"));
+        let presses = machine.lighting_solution().unwrap();
+        assert_eq!(presses.iter().sum::<u64>(), 3);
+        assert_eq!(machine.lights_after(&presses), machine.lighting_goal());
+        let machine = Machine::from_line(&format!("[#.#] {wiring}"));
+        assert_eq!(machine.min_lighting_presses(), Some(2));
+        // With 21 independent lights as well, neither search is attempted.
+        let single: Vec<String> = (0..21).map(|i| format!("({i})")).collect();
+        let line = format!(
+            "[{}] {} {} {{{}}}",
+            "#".repeat(21),
+            single.join(" "),
+            vec!["(0)"; 64].join(" "),
+            vec!["0"; 21].join(",")
+        );
+        assert_eq!(Machine::from_line(&line).lighting_solution(), None);
+    }
+
+    #[test]
+    fn test_lighting_unreachable() {
+        let machine = Machine::from_line("[##.] (0,1) (1,2) {0,0,0}");
+        assert_eq!(machine.min_lighting_presses(), Some(1));
+        let machine = Machine::from_line("[#..] (0,1) (1,2) {0,0,0}");
+        assert_eq!(machine.min_lighting_presses(), None);
+    }
+
+    #[test]
+    fn test_solution_vectors() {
+        let day = Day::new(EXAMPLE);
+        let mut lighting_total = 0;
+        let mut joltage_total = 0;
+        for machine in day.machines() {
+            let presses = machine.lighting_solution().unwrap();
+            assert_eq!(presses.len(), machine.button_wires().len());
+            assert_eq!(machine.lights_after(&presses), machine.lighting_goal());
+            lighting_total += presses.iter().sum::<u64>();
+            let presses = machine.joltage_solution().unwrap();
+            let goal: Vec<u64> = machine.joltage_goal().iter().map(|&g| g as u64).collect();
+            assert_eq!(machine.joltage_after(&presses), goal);
+            joltage_total += presses.iter().sum::<u64>();
+        }
+        assert_eq!((lighting_total, joltage_total), (7, 33));
+        assert_eq!(day.machines()[0].num_lights(), 4);
+        // Lighting solutions never press a button twice.
+        let second = day.machines()[1].lighting_solution().unwrap();
+        assert!(second.iter().all(|&p| p <= 1));
+    }
+
+    #[test]
+    fn test_parse_wide_machine() {
+        let lights: String = (0..70)
+            .map(|i| if i % 3 == 0 { '#' } else { '.' })
+            .collect();
+        let line = format!("[{lights}] (0,69) (3,66) {{{}}}", vec!["1"; 70].join(","));
+        let machine = Machine::from_line(&line);
+        assert_eq!(machine.num_lights(), 70);
+        assert!(machine.lighting_goal()[69] && !machine.lighting_goal()[68]);
+        assert_eq!(machine.button_wires(), &[vec![0, 69], vec![3, 66]]);
+        assert_eq!(machine.lighting_solution(), None);
+    }
+
+    fn parse_error(line: &str) -> ParseError {
+        Machine::parse(Cursor::new(line)).err().unwrap()
+    }
+
+    #[test]
+    fn test_parse_tolerates_spaces() {
+        let machine = Machine::from_line("  [.#]  ( 0 , 1 )(1)  { 3 , 4 }  ");
+        assert_eq!(machine.lighting_goal(), &[false, true]);
+        assert_eq!(machine.button_wires(), &[vec![0, 1], vec![1]]);
+        assert_eq!(machine.joltage_goal(), &[3, 4]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let lights = "`.` or `#` in the lights, or `]` to close them";
+        let cases = [
+            (
+                ".#] (0) {1,2}",
+                1,
+                "expected `[` to start the lights, found `.`".to_string(),
+            ),
+            ("[.# (0) {1,2}", 4, format!("expected {lights}, found ` `")),
+            ("[.x] (0) {1,2}", 3, format!("expected {lights}, found `x`")),
+            (
+                "[.#] (0) 1,2}",
+                10,
+                "expected `(` to start a button or `{` to start the joltage targets, found `1`"
+                    .to_string(),
+            ),
+            (
+                "[.#] (0) {1,2",
+                14,
+                "expected `,` or `}` to close the joltage targets, found the end of the input"
+                    .to_string(),
+            ),
+            (
+                "[.#] (0) {1,two}",
+                13,
+                "expected a number, found `t`".to_string(),
+            ),
+            (
+                "[.#] (0) {1}",
+                10,
+                "expected 2 joltage targets, found 1".to_string(),
+            ),
+            (
+                "[.#] (0 {1,2}",
+                9,
+                "expected `,` or `)` to close a button, found `{`".to_string(),
+            ),
+            (
+                "[.#] (0,) {1,2}",
+                9,
+                "expected a number, found `)`".to_string(),
+            ),
+            (
+                "[.#] (0,2) {1,2}",
+                9,
+                "button wires light 2, but there are only 2 lights".to_string(),
+            ),
+            ("[.#] (0) {1,2} x", 16, "unexpected `x`".to_string()),
+        ];
+        for (line, col, message) in cases {
+            assert_eq!(parse_error(line), ParseError::at(1, col, message), "{line}");
+        }
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/10")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "527");
+    fn test_create_reports_line_numbers() {
+        let input = "[.#] (1) {0,1}\n\n[.#] (1 {0,1}\n";
+        let message = "expected `,` or `)` to close a button, found `{`";
+        let error = Day::try_create(input).err().unwrap();
+        assert_eq!(error, ParseError::at(3, 9, message));
+        let result = std::panic::catch_unwind(|| Day::create(input));
+        let panic = *result.err().unwrap().downcast::<String>().unwrap();
+        assert_eq!(panic, format!("Day 10: line 3, column 9: {message}"));
+        let error = Day::try_create("\n\n").err().unwrap();
+        assert_eq!(error, ParseError::new(1, "expected at least one machine"));
     }
 
     #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n\
-            [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}\n\
-            [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "33");
+    fn test_joltage_with_fractional_pivots() {
+        // Each pair of counters shares a button, so eliminating leaves halves in the pivot rows.
+        let machine = Machine::from_line("[...] (0,1) (1,2) (0,2) {2,2,2}");
+        assert_eq!(machine.min_joltage_presses(), Some(3));
+        // An odd total cannot be split evenly between the three buttons.
+        let machine = Machine::from_line("[...] (0,1) (1,2) (0,2) {1,1,1}");
+        assert_eq!(machine.min_joltage_presses(), None);
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/10")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "19810");
+    fn test_joltage_beyond_f64_precision() {
+        // The targets exceed 2^53, so a floating-point elimination cannot represent them exactly.
+        let (a, b, c) = (
+            100_000_000_000_000_001,
+            100_000_000_000_000_003,
+            99_999_999_999_999_998,
+        );
+        let line = format!("[...] (0,1) (1,2) (0,2) {{{},{},{}}}", a + b, b + c, a + c);
+        let machine = Machine::from_line(&line);
+        assert_eq!(machine.min_joltage_presses(), Some(a + b + c));
+    }
+
+    #[test]
+    fn test_joltage_with_nearly_dependent_buttons() {
+        // Buttons that each skip a different counter only separate after dividing by three.
+        let machine =
+            Machine::from_line("[....] (0,1,2) (0,1,3) (0,2,3) (1,2,3) (0) {13,13,13,13}");
+        assert_eq!(
+            machine.min_joltage_presses(),
+            brute_force_joltage_presses(&machine)
+        );
+    }
+
+    #[test]
+    fn test_joltage_with_large_targets() {
+        // Enumerating the three free variables up to their bounds would take 10^18 steps.
+        let machine =
+            Machine::from_line("[...] (0) (1) (2) (0,1) (1,2) (0,1,2) {1000000,1000000,1000000}");
+        assert_eq!(machine.min_joltage_presses(), Some(1_000_000));
+        let machine = Machine::from_line(
+            "[....] (0,1) (1,2) (2,3) (0,3) (0,2) (1,3) {999999,1000001,999999,1000001}",
+        );
+        assert_eq!(machine.min_joltage_presses(), Some(2_000_000));
+    }
+
+    #[test]
+    fn test_tighten_rounds_ranges() {
+        let r = |n: i64, d: i64| Rational64::new(n, d);
+        // y0 + y1 <= 5/2 and y0 - y1 >= 1 over 0 <= y <= 10.
+        let program = LinearProgram {
+            objective: vec![r(1, 1), r(1, 1)],
+            constraints: vec![
+                (vec![r(1, 1), r(1, 1)], r(5, 2)),
+                (vec![r(-1, 1), r(1, 1)], r(-1, 1)),
+            ],
+        };
+        let mut ranges = vec![(0, 10), (0, 10)];
+        assert!(program.tighten(&mut ranges));
+        assert_eq!(ranges, vec![(1, 2), (0, 1)]);
+        // 2 y0 = 1 has a fractional solution that the LP accepts, but no integer one.
+        let program = LinearProgram {
+            objective: vec![r(1, 1)],
+            constraints: vec![(vec![r(2, 1)], r(1, 1)), (vec![r(-2, 1)], r(-1, 1))],
+        };
+        assert!(program.minimize_within(&[(0, 10)]).is_some());
+        assert!(!program.tighten(&mut [(0, 10)]));
+    }
+
+    /// Tries every combination of presses up to each button's smallest target.
+    fn brute_force_joltage_presses(machine: &Machine) -> Option<u64> {
+        fn search(machine: &Machine, button: usize, remaining: &mut [usize]) -> Option<u64> {
+            if button == machine.button_wires.len() {
+                return remaining.iter().all(|&r| r == 0).then_some(0);
+            }
+            let wires = &machine.button_wires[button];
+            let limit = wires.iter().map(|&w| remaining[w]).min().unwrap_or(0);
+            let mut best = None;
+            for presses in 0..=limit {
+                for &w in wires {
+                    remaining[w] -= presses;
+                }
+                if let Some(rest) = search(machine, button + 1, remaining) {
+                    let total = rest + presses as u64;
+                    best = Some(best.map_or(total, |b: u64| b.min(total)));
+                }
+                for &w in wires {
+                    remaining[w] += presses;
+                }
+            }
+            best
+        }
+        search(machine, 0, &mut machine.joltage_goal.clone())
+    }
+
+    #[test]
+    fn test_joltage_matches_brute_force() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        for _ in 0..300 {
+            let num_lights = rng.random_range(1..5);
+            let num_buttons = rng.random_range(1..6);
+            let buttons: Vec<String> = (0..num_buttons)
+                .map(|_| {
+                    let wires: Vec<String> = (0..num_lights)
+                        .filter(|_| rng.random_range(0..2) == 0)
+                        .map(|w| w.to_string())
+                        .collect();
+                    if wires.is_empty() {
+                        "(0)".to_string()
+                    } else {
+                        format!("({})", wires.join(","))
+                    }
+                })
+                .collect();
+            let goals: Vec<String> = (0..num_lights)
+                .map(|_| rng.random_range(0..8usize).to_string())
+                .collect();
+            let line = format!(
+                "[{}] {} {{{}}}",
+                ".".repeat(num_lights),
+                buttons.join(" "),
+                goals.join(",")
+            );
+            let machine = Machine::from_line(&line);
+            assert_eq!(
+                machine.min_joltage_presses(),
+                brute_force_joltage_presses(&machine),
+                "{line}"
+            );
+            if let Some(presses) = machine.joltage_solution() {
+                let goal: Vec<u64> = machine.joltage_goal().iter().map(|&g| g as u64).collect();
+                assert_eq!(machine.joltage_after(&presses), goal, "{line}");
+            }
+        }
     }
 }
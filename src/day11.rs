@@ -1,5 +1,7 @@
+use crate::graph::Graph;
 use crate::puzzle::Puzzle;
-use std::collections::{HashMap, HashSet};
+use crate::render::{Visualization, categorical_color};
+use std::slice;
 
 pub struct Day {
     graph: Graph,
@@ -22,115 +24,436 @@ impl Puzzle for Day {
     /// connections.
     /// Auxiliary space complexity: O(V)
     fn solve_part_2(&self) -> String {
-        let svr_to_dac = self.count_paths("svr", "dac");
-        let dac_to_fft = self.count_paths("dac", "fft");
-        let fft_to_out = self.count_paths("fft", "out");
-        let dac_before_fft = svr_to_dac * dac_to_fft * fft_to_out;
-        let svr_to_fft = self.count_paths("svr", "fft");
-        let fft_to_dac = self.count_paths("fft", "dac");
-        let dac_to_out = self.count_paths("dac", "out");
-        let fft_before_dac = svr_to_fft * fft_to_dac * dac_to_out;
-        (dac_before_fft + fft_before_dac).to_string()
+        self.count_paths_through("svr", "out", &["dac", "fft"])
+            .to_string()
+    }
+
+    /// Draws the device graph, with the edges labelled by how many paths from "svr" (or "you" if
+    /// there is no "svr") to "out" use them.
+    fn visualize(&self) -> Option<Visualization> {
+        let start = if self.graph.id("svr").is_some() {
+            "svr"
+        } else {
+            "you"
+        };
+        Some(self.device_graph(start, "out"))
     }
 }
 
-type Graph = HashMap<String, HashSet<String>>;
+/// The devices named in the puzzle, highlighted in the device graph.
+const WAYPOINTS: [&str; 5] = ["you", "svr", "dac", "fft", "out"];
+
+/// Parses the device graph. A device listed on several lines keeps the connections of the last.
+fn parse_graph(input: &str) -> Graph {
+    let mut graph = Graph::new();
+    for line in input.trim().lines() {
+        let (from, to_part) = line.trim().split_once(": ").unwrap();
+        let from = graph.intern(from);
+        let to: Vec<u32> = to_part
+            .split_whitespace()
+            .map(|t| graph.intern(t))
+            .collect();
+        graph.set_successors(from, to);
+    }
+    graph
+}
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
-        let graph: Graph = input
-            .trim()
-            .lines()
-            .map(|line| {
-                let (from, to_part) = line.trim().split_once(": ").unwrap();
-                let to = to_part.split_whitespace().map(|t| t.to_string()).collect();
-                (from.to_string(), to)
-            })
-            .collect();
-        Box::new(Day { graph })
-    }
-
-    fn count_paths(&self, start: &str, end: &str) -> usize {
-        fn dfs<'a>(
-            node: &'a str,
-            end: &str,
-            edges: &'a Graph,
-            memo: &mut HashMap<&'a str, usize>,
-        ) -> usize {
-            if let Some(&cached) = memo.get(node) {
-                return cached;
+        Box::new(Self::new(input))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Day {
+            graph: parse_graph(input),
+        }
+    }
+
+    /// Counts the paths from `start` to `end`. A device that does not appear in the input has no
+    /// paths.
+    ///
+    /// Panics if there are more paths than fit in a `usize`.
+    pub fn count_paths(&self, start: &str, end: &str) -> usize {
+        match (self.graph.id(start), self.graph.id(end)) {
+            (Some(start), Some(end)) => self
+                .graph
+                .count_paths(start, end)
+                .expect("more paths than fit in a usize"),
+            _ => 0,
+        }
+    }
+
+    /// Counts the paths from `start` to `end` that visit every one of `waypoints`, in any order.
+    ///
+    /// A path visits devices in topological order, so only one order of the waypoints can be
+    /// followed: the paths are the products of the path counts between consecutive waypoints in
+    /// that order.
+    ///
+    /// Panics if there are more paths than fit in a `usize`.
+    ///
+    /// Time complexity: O(K * (V + E)) where K is the number of waypoints.
+    /// Auxiliary space complexity: O(V)
+    pub fn count_paths_through(&self, start: &str, end: &str, waypoints: &[&str]) -> usize {
+        let mut position = vec![0; self.graph.len()];
+        for (i, node) in self
+            .graph
+            .topological_order()
+            .expect("the device graph has a cycle")
+            .into_iter()
+            .enumerate()
+        {
+            position[node as usize] = i;
+        }
+        let Some(mut stops) = waypoints
+            .iter()
+            .map(|&w| self.graph.id(w))
+            .collect::<Option<Vec<u32>>>()
+        else {
+            return 0;
+        };
+        stops.sort_unstable_by_key(|&w| (position[w as usize], w));
+        stops.dedup();
+        let (Some(start), Some(end)) = (self.graph.id(start), self.graph.id(end)) else {
+            return 0;
+        };
+        let mut total: usize = 1;
+        let mut from = start;
+        for to in stops.into_iter().chain([end]) {
+            if total == 0 {
+                break;
             }
-            if node == end {
-                memo.insert(node, 1);
-                return 1;
+            total = self
+                .graph
+                .count_paths(from, to)
+                .and_then(|paths| total.checked_mul(paths))
+                .expect("more paths than fit in a usize");
+            from = to;
+        }
+        total
+    }
+
+    /// Lazily yields every path from `start` to `end`, as the devices along it.
+    ///
+    /// The search only steps into devices from which `end` can be reached, so each path costs
+    /// O(L * D) to find, where L is its length and D the largest number of connections of a device,
+    /// and taking the first few paths never explores the whole graph. The iterator's size hint is
+    /// the exact number of paths left, or unbounded if that does not fit in a `usize`.
+    pub fn paths(&self, start: &str, end: &str) -> impl Iterator<Item = Vec<&str>> + '_ {
+        let mut paths = Paths {
+            graph: &self.graph,
+            end: 0,
+            reaches_end: vec![false; self.graph.len()],
+            stack: Vec::new(),
+            remaining: Some(0),
+        };
+        let (Some(start), Some(end)) = (self.graph.id(start), self.graph.id(end)) else {
+            return paths;
+        };
+        paths.reaches_end = self.graph.reaching(end);
+        paths.end = end;
+        paths.remaining = self.graph.count_paths(start, end);
+        if paths.reaches_end[start as usize] {
+            paths
+                .stack
+                .push((start, self.graph.successors(start).iter()));
+        }
+        paths
+    }
+
+    /// Renders the device graph in GraphViz format. The puzzle's named devices are filled in, and
+    /// each connection is labelled with the number of paths from `start` to `end` that use it;
+    /// connections that no such path uses are grey.
+    pub fn device_graph(&self, start: &str, end: &str) -> Visualization {
+        let order = self
+            .graph
+            .topological_order()
+            .expect("the device graph has a cycle");
+        let (start, end) = (self.graph.id(start), self.graph.id(end));
+        // Paths from `start` to each device, and from each device to `end`, saturating rather
+        // than overflowing.
+        let mut from_start = vec![0u128; self.graph.len()];
+        if let Some(start) = start {
+            from_start[start as usize] = 1;
+        }
+        for &node in &order {
+            let count = from_start[node as usize];
+            if count == 0 || Some(node) == end {
+                continue;
             }
-            let mut total = 0;
-            if let Some(neighbors) = edges.get(node) {
-                for neighbor in neighbors {
-                    total += dfs(neighbor, end, edges, memo);
+            for &next in self.graph.successors(node) {
+                from_start[next as usize] = from_start[next as usize].saturating_add(count);
+            }
+        }
+        let mut to_end = vec![0u128; self.graph.len()];
+        for &node in order.iter().rev() {
+            to_end[node as usize] = if Some(node) == end {
+                1
+            } else {
+                self.graph
+                    .successors(node)
+                    .iter()
+                    .fold(0u128, |acc, &next| {
+                        acc.saturating_add(to_end[next as usize])
+                    })
+            };
+        }
+        self.graph.to_dot(
+            &[("rankdir", "LR")],
+            |node| match WAYPOINTS.iter().position(|&w| w == self.graph.name(node)) {
+                Some(i) => vec![
+                    ("style", "filled".to_string()),
+                    ("fillcolor", categorical_color(i).to_string()),
+                ],
+                None => Vec::new(),
+            },
+            |from, next| {
+                let count = from_start[from as usize].saturating_mul(to_end[next as usize]);
+                if count == 0 || Some(from) == end {
+                    vec![("color", "#c0c0c0".to_string())]
+                } else {
+                    vec![("label", count.to_string())]
                 }
+            },
+        )
+    }
+}
+
+/// A depth-first search over the paths to a device, paused after each path it finds.
+struct Paths<'a> {
+    graph: &'a Graph,
+    end: u32,
+    /// Whether `end` can be reached from each device.
+    reaches_end: Vec<bool>,
+    /// The path so far, with the successors of each device that are still to be tried.
+    stack: Vec<(u32, slice::Iter<'a, u32>)>,
+    /// The number of paths left to yield, if it fits in a `usize`.
+    remaining: Option<usize>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, successors) = self.stack.last_mut()?;
+            if *node == self.end {
+                let path = self
+                    .stack
+                    .iter()
+                    .map(|&(node, _)| self.graph.name(node))
+                    .collect();
+                self.stack.pop();
+                self.remaining = self.remaining.map(|n| n - 1);
+                return Some(path);
             }
-            memo.insert(node, total);
-            total
+            match successors.find(|&&next| self.reaches_end[next as usize]) {
+                Some(&next) => {
+                    let successors = self.graph.successors(next).iter();
+                    self.stack.push((next, successors));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(n) => (n, Some(n)),
+            None => (usize::MAX, None),
         }
-        let mut memo: HashMap<&str, usize> = HashMap::new();
-        dfs(start, end, &self.graph, &mut memo)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::examples::DAY11_PART_1 as EXAMPLE_1;
+    use crate::examples::DAY11_PART_2 as EXAMPLE_2;
+    use crate::puzzle::example_tests;
 
-    #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            aaa: you hhh\n\
-            you: bbb ccc\n\
-            bbb: ddd eee\n\
-            ccc: ddd eee fff\n\
-            ddd: ggg\n\
-            eee: out\n\
-            fff: out\n\
-            ggg: out\n\
-            hhh: ccc fff iii\n\
-            iii: out";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_1(), "5");
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE_1) == "5",
+        test_part_2_example_1: solve_part_2(EXAMPLE_2) == "2",
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/11")).unwrap();
+    fn test_deep_graph() {
+        // A chain 100,000 devices deep, with a dead end branching off every device.
+        let depth = 100_000;
+        let mut input = String::from("you: n0\n");
+        for i in 0..depth {
+            input += &format!("n{i}: n{} x{i}\n", i + 1);
+        }
+        input += &format!("n{depth}: a b\na: out\nb: out\n");
         let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "470");
+        assert_eq!(puzzle.solve_part_1(), "2");
     }
 
     #[test]
-    fn test_part_2_example_1() {
-        let input = "\
-            svr: aaa bbb\n\
-            aaa: fft\n\
-            fft: ccc\n\
-            bbb: tty\n\
-            tty: ccc\n\
-            ccc: ddd eee\n\
-            ddd: hub\n\
-            hub: fff\n\
-            eee: dac\n\
-            dac: fff\n\
-            fff: ggg hhh\n\
-            ggg: out\n\
-            hhh: out";
-        let puzzle = Day::create(input);
-        assert_eq!(puzzle.solve_part_2(), "2");
+    fn test_interned_graph() {
+        let graph = parse_graph("aaa: you hhh\nyou: hhh out\nhhh: out\n");
+        assert_eq!(graph.len(), 4);
+        let ids: Vec<u32> = ["aaa", "you", "hhh", "out"]
+            .iter()
+            .map(|name| graph.id(name).unwrap())
+            .collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+        assert_eq!(graph.name(2), "hhh");
+        assert_eq!(graph.successors(1), &[2, 3]);
+        assert!(graph.successors(3).is_empty());
+        assert_eq!(graph.id("zzz"), None);
+        let day = Day::new("aaa: you hhh\nyou: hhh out\nhhh: out\n");
+        assert_eq!(day.count_paths("aaa", "out"), 3);
+        assert_eq!(day.count_paths("zzz", "out"), 0);
     }
 
     #[test]
-    fn test_solve_part_2() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/11")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_2(), "384151614084875");
+    fn test_count_paths_through() {
+        let day = Day::new(EXAMPLE_2);
+        assert_eq!(day.count_paths_through("svr", "out", &[]), 8);
+        assert_eq!(day.count_paths_through("svr", "out", &["fft"]), 4);
+        assert_eq!(day.count_paths_through("svr", "out", &["fft", "dac"]), 2);
+        assert_eq!(
+            day.count_paths_through("svr", "out", &["dac", "fft", "dac"]),
+            2
+        );
+        assert_eq!(
+            day.count_paths_through("svr", "out", &["fft", "dac", "ggg"]),
+            1
+        );
+        assert_eq!(day.count_paths_through("svr", "out", &["fft", "tty"]), 0);
+        assert_eq!(day.count_paths_through("svr", "out", &["nowhere"]), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "more paths than fit in a usize")]
+    fn test_count_paths_through_overflow() {
+        // 2^40 paths from n0 to the waypoint n40, and another 2^40 from there to n80: each leg
+        // fits in a usize, but their product does not.
+        let mut input = String::new();
+        for i in 0..80 {
+            input += &format!("n{i}: a{i} b{i}\na{i}: n{j}\nb{i}: n{j}\n", j = i + 1);
+        }
+        let day = Day::new(&input);
+        assert_eq!(day.count_paths("n0", "n40"), 1 << 40);
+        day.count_paths_through("n0", "n80", &["n40"]);
+    }
+
+    #[test]
+    fn test_count_paths_through_matches_brute_force() {
+        use rand::Rng;
+        fn brute_force(day: &Day, node: &str, end: &str, waypoints: &[&str], seen: usize) -> usize {
+            let seen = seen + waypoints.iter().filter(|&&w| w == node).count();
+            if node == end {
+                return (seen == waypoints.len()) as usize;
+            }
+            let id = day.graph.id(node);
+            let successors = id.map_or(&[][..], |id| day.graph.successors(id));
+            successors
+                .iter()
+                .map(|&next| brute_force(day, day.graph.name(next), end, waypoints, seen))
+                .sum()
+        }
+        let mut rng = crate::seed::rng();
+        let names: Vec<String> = (0..9).map(|i| format!("n{i}")).collect();
+        for _ in 0..200 {
+            // Edges only go from lower to higher numbers, so the graph is acyclic.
+            let mut input = String::new();
+            for i in 0..names.len() {
+                let to: Vec<&str> = (i + 1..names.len())
+                    .filter(|_| rng.random_range(0..3) == 0)
+                    .map(|j| names[j].as_str())
+                    .collect();
+                if !to.is_empty() {
+                    input += &format!("{}: {}\n", names[i], to.join(" "));
+                }
+            }
+            if input.is_empty() {
+                continue;
+            }
+            let day = Day::new(&input);
+            let mut waypoints: Vec<&str> = (1..8)
+                .filter(|_| rng.random_range(0..4) == 0)
+                .map(|i| names[i].as_str())
+                .collect();
+            // The order in which waypoints are given does not matter.
+            waypoints.reverse();
+            assert_eq!(
+                day.count_paths_through("n0", "n8", &waypoints),
+                brute_force(&day, "n0", "n8", &waypoints, 0),
+                "{input} through {waypoints:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        let day = Day::new(EXAMPLE_1);
+        let mut paths: Vec<Vec<&str>> = day.paths("you", "out").collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                vec!["you", "bbb", "ddd", "ggg", "out"],
+                vec!["you", "bbb", "eee", "out"],
+                vec!["you", "ccc", "ddd", "ggg", "out"],
+                vec!["you", "ccc", "eee", "out"],
+                vec!["you", "ccc", "fff", "out"],
+            ]
+        );
+        let mut paths = day.paths("you", "out");
+        assert_eq!(paths.size_hint(), (5, Some(5)));
+        paths.next();
+        assert_eq!(paths.size_hint(), (4, Some(4)));
+        assert_eq!(
+            day.paths("out", "out").collect::<Vec<_>>(),
+            vec![vec!["out"]]
+        );
+        assert_eq!(day.paths("you", "hhh").count(), 0);
+    }
+
+    #[test]
+    fn test_paths_are_lazy() {
+        // 2^200 paths: each device has two ways to reach the next, and every device also leads
+        // into a dead end that the search must not wander into.
+        let mut input = String::new();
+        for i in 0..200 {
+            input += &format!("n{i}: a{i} b{i} x{i}\na{i}: n{j}\nb{i}: n{j}\n", j = i + 1);
+            input += &format!("x{i}: y{i}\n");
+        }
+        let day = Day::new(&input);
+        let first: Vec<Vec<&str>> = day.paths("n0", "n200").take(3).collect();
+        assert_eq!(first.len(), 3);
+        assert_eq!(day.paths("n0", "n200").size_hint(), (usize::MAX, None));
+        for path in &first {
+            assert_eq!(path.len(), 401);
+            assert!(path.iter().all(|device| !device.starts_with('x')));
+        }
+    }
+
+    #[test]
+    fn test_device_graph() {
+        let input = EXAMPLE_2;
+        let puzzle = Day::create(input);
+        let vis = puzzle.visualize().unwrap();
+        assert_eq!(vis.extension(), "dot");
+        let dot = vis.contents();
+        assert!(dot.starts_with("digraph {\n"));
+        // "you" is not in this graph; the other four named devices are highlighted.
+        assert_eq!(dot.matches("fillcolor").count(), 4);
+        assert!(!dot.contains("\"you\""));
+        assert!(dot.contains(r#""svr" -> "aaa" [label="4"];"#));
+        assert!(dot.contains(r#""ccc" -> "ddd" [label="4"];"#));
+        assert!(dot.contains(r#""fff" -> "ggg" [label="4"];"#));
+        assert_eq!(dot.matches(" -> ").count(), 16);
+        // From "ccc", no path to "out" leaves through "aaa".
+        let dot = Day::new(input).device_graph("ccc", "out");
+        assert!(
+            dot.contents()
+                .contains(r##""svr" -> "aaa" [color="#c0c0c0"];"##)
+        );
+        assert!(dot.contents().contains(r#""ccc" -> "eee" [label="2"];"#));
     }
 }
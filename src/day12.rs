@@ -1,13 +1,240 @@
+use crate::bitset::BitSet;
+use crate::memo::Memo;
+use crate::polyomino;
 use crate::puzzle::Puzzle;
+use crate::render::{Raster, Svg, Visualization, categorical_color};
 
-use ahash::{AHashMap, AHashSet};
+use ahash::AHashMap;
 use rayon::prelude::*;
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::OnceLock;
 
 pub struct Day {
     shapes: Vec<Shape>,
     regions: Vec<Region>,
+    placements: PlacementCache,
+    options: Options,
+}
+
+/// How the packing search is carried out.
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub solver: Solver,
+    /// Whether the depth-first search flood-fills the empty cells at each step and backtracks
+    /// when the holes they form are too small or oddly sized to take the presents still to be
+    /// placed. The dancing-links solver ignores this.
+    pub prune_holes: bool,
+    /// The most states each depth-first search remembers as dead ends. Past this, the states
+    /// that have gone longest without being looked up are forgotten, so they may be explored
+    /// again. Each state takes about 20 bytes, and regions are searched in parallel.
+    pub memo_limit: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            solver: Solver::default(),
+            prune_holes: false,
+            memo_limit: 1 << 20,
+        }
+    }
+}
+
+/// The search that decides whether a region's presents fit, for regions that the area and
+/// bounding-box checks cannot settle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Solver {
+    /// Depth-first search that places the most constrained shape first and remembers the states
+    /// that failed.
+    #[default]
+    Dfs,
+    /// Knuth's Algorithm X with dancing links, treating the region as an exact cover problem in
+    /// which each shape must be covered once per copy and each cell at most once.
+    Dlx,
+}
+
+/// A present placed in a region.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlacedPresent {
+    /// The index of the present's shape.
+    pub shape: usize,
+    /// Which of the shape's distinct rotations and reflections is placed.
+    pub variant: usize,
+    /// The column of the left edge of the placed variant's bounding box.
+    pub x: usize,
+    /// The row of the top edge of the placed variant's bounding box.
+    pub y: usize,
+    cells: Vec<(usize, usize)>,
+}
+
+impl PlacedPresent {
+    fn new(shape: &Shape, index: usize, variant: usize, x: usize, y: usize) -> Self {
+        let cells = shape.variants[variant]
+            .cells()
+            .iter()
+            .map(|&(dx, dy)| (x + dx as usize, y + dy as usize))
+            .collect();
+        Self {
+            shape: index,
+            variant,
+            x,
+            y,
+            cells,
+        }
+    }
+
+    /// The `(column, row)` of each cell the present covers.
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+}
+
+/// An arrangement of all of a region's presents without overlaps.
+#[derive(Clone, Debug)]
+pub struct Packing {
+    width: usize,
+    height: usize,
+    presents: Vec<PlacedPresent>,
+}
+
+impl Packing {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn presents(&self) -> &[PlacedPresent] {
+        &self.presents
+    }
+
+    /// Renders the region with each present in its own color and outlined.
+    pub fn svg(&self) -> Visualization {
+        const CELL: f64 = 10.0;
+        let owners = self.owners();
+        let owner = |x: usize, y: usize| owners[y * self.width + x];
+        let (width, height) = (self.width as f64 * CELL, self.height as f64 * CELL);
+        let mut svg = Svg::new(width, height);
+        svg.rect(0.0, 0.0, width, height, "#ffffff", None);
+        for (k, present) in self.presents.iter().enumerate() {
+            let title = format!(
+                "present {k}: shape {}, variant {} at ({}, {})",
+                present.shape, present.variant, present.x, present.y
+            );
+            for &(x, y) in &present.cells {
+                let (left, top) = (x as f64 * CELL, y as f64 * CELL);
+                svg.rect(left, top, CELL, CELL, categorical_color(k), Some(&title));
+            }
+        }
+        // Lines between cells of different presents, so that neighbouring presents that share a
+        // color stay distinguishable.
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (left, top) = (x as f64 * CELL, y as f64 * CELL);
+                let (right, bottom) = (left + CELL, top + CELL);
+                if x + 1 < self.width && owner(x, y) != owner(x + 1, y) {
+                    svg.line((right, top), (right, bottom), "#000000", 1.0);
+                }
+                if y + 1 < self.height && owner(x, y) != owner(x, y + 1) {
+                    svg.line((left, bottom), (right, bottom), "#000000", 1.0);
+                }
+            }
+        }
+        let corners = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+        svg.outline(&corners, "#000000", 2.0, None);
+        svg.finish()
+    }
+
+    /// Renders the region one cell per cell, with each present in its own color and area.
+    pub fn raster(&self) -> Raster {
+        let mut raster = Raster::new(self.width, self.height, "#ffffff");
+        for (k, present) in self.presents.iter().enumerate() {
+            for &(x, y) in &present.cells {
+                raster.set(x, y, categorical_color(k), k);
+            }
+        }
+        raster
+    }
+
+    /// The index of the present covering each cell, in row-major order.
+    fn owners(&self) -> Vec<Option<usize>> {
+        let mut owners = vec![None; self.width * self.height];
+        for (k, present) in self.presents.iter().enumerate() {
+            for &(x, y) in &present.cells {
+                owners[y * self.width + x] = Some(k);
+            }
+        }
+        owners
+    }
+}
+
+/// Draws the region one character per cell, labelling the presents `A` to `Z`, then `a` to `z`,
+/// cyclically, and empty cells `.`.
+impl fmt::Display for Packing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+        let owners = self.owners();
+        for row in owners.chunks(self.width) {
+            let line: String = row
+                .iter()
+                .map(|owner| owner.map_or('.', |k| LABELS[k % LABELS.len()] as char))
+                .collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why the presents listed for a region cannot all fit in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Infeasibility {
+    /// The region lists counts for more shapes than the input defines.
+    ShapeCountMismatch { listed: usize, shapes: usize },
+    /// The presents cover more cells than the region has.
+    AreaDeficit { needed: usize, available: usize },
+    /// A listed shape fits inside the region in none of its orientations.
+    NoPlacements { shape: usize },
+    /// The depth-first search tried every arrangement without finding a packing.
+    SearchExhausted(SearchStats),
+}
+
+impl fmt::Display for Infeasibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Infeasibility::ShapeCountMismatch { listed, shapes } => {
+                write!(
+                    f,
+                    "lists {listed} shape counts, but there are {shapes} shapes"
+                )
+            }
+            Infeasibility::AreaDeficit { needed, available } => {
+                write!(
+                    f,
+                    "the presents cover {needed} cells, but the region has {available}"
+                )
+            }
+            Infeasibility::NoPlacements { shape } => {
+                write!(f, "shape {shape} fits in no orientation")
+            }
+            Infeasibility::SearchExhausted(stats) => write!(
+                f,
+                "no packing after exploring {} states ({} memo hits)",
+                stats.nodes, stats.memo_hits
+            ),
+        }
+    }
+}
+
+/// Counters from a depth-first packing search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// The number of states visited, including those answered from the memo.
+    pub nodes: u64,
+    /// The number of states recognized as dead ends seen before.
+    pub memo_hits: u64,
 }
 
 impl Puzzle for Day {
@@ -17,59 +244,25 @@ impl Puzzle for Day {
     /// shapes.
     /// Auxiliary space complexity: O(2^B) where B is the area of the region.
     fn solve_part_1(&self) -> String {
-        type PLKey = (usize, usize, usize);
         let shapes = &self.shapes;
         let mut trivial_yes = 0usize;
         let mut hard_regions: Vec<&Region> = Vec::new();
-        let mut used_shape = vec![false; shapes.len()];
-        let mut hard_sizes: AHashSet<(usize, usize)> = AHashSet::new();
         for r in &self.regions {
             match triage_region(r, shapes) {
                 RegionTriage::TriviallyFits => {
                     trivial_yes += 1;
                 }
                 RegionTriage::TriviallyImpossible => {}
-                RegionTriage::NeedsSearch => {
-                    hard_regions.push(r);
-                    hard_sizes.insert((r.w, r.h));
-                    for (i, &c) in r.counts.iter().enumerate() {
-                        if c != 0 {
-                            used_shape[i] = true;
-                        }
-                    }
-                }
-            }
-        }
-        if hard_regions.is_empty() {
-            return trivial_yes.to_string();
-        }
-        let mut keys: Vec<PLKey> =
-            Vec::with_capacity(hard_sizes.len() * used_shape.iter().filter(|&&u| u).count());
-        for &(w, h) in hard_sizes.iter() {
-            for (i, &u) in used_shape.iter().enumerate() {
-                if u {
-                    keys.push((w, h, i));
-                }
+                RegionTriage::NeedsSearch => hard_regions.push(r),
             }
         }
-        let generated: Vec<(PLKey, Arc<PlacementList>)> = keys
-            .into_par_iter()
-            .map(|(w, h, i)| {
-                (
-                    (w, h, i),
-                    Arc::new(PlacementList::generate(w, h, &shapes[i])),
-                )
-            })
-            .collect();
-        let mut placement_map: AHashMap<PLKey, Arc<PlacementList>> =
-            AHashMap::with_capacity(generated.len());
-        for (k, v) in generated {
-            placement_map.insert(k, v);
-        }
-        let pm = &placement_map;
+        let pc = &self.placements;
         let hard_yes = hard_regions
             .par_iter()
-            .filter(|r| region_can_fit(r, shapes, pm))
+            .filter(|r| match self.options.solver {
+                Solver::Dfs => region_can_fit(r, shapes, pc, &self.options),
+                Solver::Dlx => region_fits_dlx(r, shapes, pc),
+            })
             .count();
         (trivial_yes + hard_yes).to_string()
     }
@@ -77,20 +270,112 @@ impl Puzzle for Day {
     fn solve_part_2(&self) -> String {
         "Day 12 has no part 2".to_string()
     }
+
+    /// Renders the first region whose presents can be packed, if any.
+    fn visualize(&self) -> Option<Visualization> {
+        (0..self.regions.len())
+            .find_map(|i| self.packing(i))
+            .map(|packing| packing.svg())
+    }
+
+    /// Like [`Puzzle::visualize`], as a grid of cells.
+    fn raster(&self) -> Option<Raster> {
+        (0..self.regions.len())
+            .find_map(|i| self.packing(i))
+            .map(|packing| packing.raster())
+    }
 }
 
 impl Day {
     pub fn create(input: &str) -> Box<dyn Puzzle> {
+        Box::new(Self::with_options(input, Options::default()))
+    }
+
+    pub fn with_options(input: &str, options: Options) -> Self {
         let (shapes_raw, regions) = parse_input(input);
         let mut shapes: Vec<Shape> = Vec::with_capacity(shapes_raw.len());
         for cells in shapes_raw {
-            let variants = gen_variants(&cells);
+            let shape = polyomino::Shape::new(cells);
             shapes.push(Shape {
-                area: cells.len(),
-                variants,
+                area: shape.area(),
+                variants: shape.variants(),
+            });
+        }
+        let placements = PlacementCache::new(&regions);
+        Day {
+            shapes,
+            regions,
+            placements,
+            options,
+        }
+    }
+
+    pub fn num_regions(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns an arrangement of the presents listed for the region at `index`, or `None` if
+    /// they cannot all fit. Regions that need a search are searched depth-first, whichever
+    /// solver the options select.
+    ///
+    /// Panics if `index` is out of range.
+    pub fn packing(&self, index: usize) -> Option<Packing> {
+        let region = &self.regions[index];
+        let presents = match triage_region(region, &self.shapes) {
+            RegionTriage::TriviallyImpossible => return None,
+            RegionTriage::TriviallyFits => stacked_packing(region, &self.shapes),
+            RegionTriage::NeedsSearch => {
+                region_packing(region, &self.shapes, &self.placements, &self.options).0
+            }
+        }?;
+        Some(Packing {
+            width: region.w,
+            height: region.h,
+            presents,
+        })
+    }
+
+    /// Returns why the presents listed for the region at `index` cannot fit, or `None` if they
+    /// can. As with [`Day::packing`], regions that need a search are searched depth-first.
+    ///
+    /// Panics if `index` is out of range.
+    pub fn infeasibility(&self, index: usize) -> Option<Infeasibility> {
+        let region = &self.regions[index];
+        let shapes = &self.shapes;
+        if region.counts.len() != shapes.len() {
+            return Some(Infeasibility::ShapeCountMismatch {
+                listed: region.counts.len(),
+                shapes: shapes.len(),
             });
         }
-        Box::new(Day { shapes, regions })
+        let listed = || (0..shapes.len()).filter(|&i| region.counts[i] != 0);
+        let needed = listed()
+            .map(|i| region.counts[i] as usize * shapes[i].area)
+            .sum();
+        let available = region.w * region.h;
+        if needed > available {
+            return Some(Infeasibility::AreaDeficit { needed, available });
+        }
+        if let Some(shape) = listed().find(|&i| !shapes[i].fits_in(region.w, region.h)) {
+            return Some(Infeasibility::NoPlacements { shape });
+        }
+        match triage_region(region, shapes) {
+            RegionTriage::NeedsSearch => {
+                match region_packing(region, shapes, &self.placements, &self.options) {
+                    (Some(_), _) => None,
+                    (None, stats) => Some(Infeasibility::SearchExhausted(stats)),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the index of every region whose presents cannot fit, with the reason.
+    pub fn infeasible_regions(&self) -> Vec<(usize, Infeasibility)> {
+        (0..self.regions.len())
+            .into_par_iter()
+            .filter_map(|i| self.infeasibility(i).map(|reason| (i, reason)))
+            .collect()
     }
 }
 
@@ -110,21 +395,21 @@ struct Region {
 #[derive(Clone)]
 struct Shape {
     area: usize,
-    variants: Vec<Variant>,
+    variants: Vec<polyomino::Shape>,
 }
 
 impl Shape {
     #[inline]
     fn fits_in(&self, rw: usize, rh: usize) -> bool {
-        self.variants.iter().any(|v| v.w <= rw && v.h <= rh)
+        self.variants.iter().any(|v| v.fits_in(rw, rh))
     }
 
     #[inline]
     fn min_height_that_fits(&self, rw: usize, rh: usize) -> Option<usize> {
         self.variants
             .iter()
-            .filter(|v| v.w <= rw && v.h <= rh)
-            .map(|v| v.h)
+            .filter(|v| v.fits_in(rw, rh))
+            .map(|v| v.height())
             .min()
     }
 
@@ -132,8 +417,8 @@ impl Shape {
     fn min_width_that_fits(&self, rw: usize, rh: usize) -> Option<usize> {
         self.variants
             .iter()
-            .filter(|v| v.w <= rw && v.h <= rh)
-            .map(|v| v.w)
+            .filter(|v| v.fits_in(rw, rh))
+            .map(|v| v.width())
             .min()
     }
 }
@@ -190,39 +475,102 @@ fn triage_region(region: &Region, shapes: &[Shape]) -> RegionTriage {
     RegionTriage::NeedsSearch
 }
 
-#[derive(Clone)]
-struct Variant {
-    w: usize,
-    h: usize,
-    cells: Vec<(u8, u8)>,
+/// Packs the presents by stacking them in a single column, each in its shortest orientation, or
+/// failing that in a single row, each in its narrowest one. These are the packings that
+/// [`triage_region`] relies on when it reports that a region trivially fits.
+fn stacked_packing(region: &Region, shapes: &[Shape]) -> Option<Vec<PlacedPresent>> {
+    let (w, h) = (region.w, region.h);
+    let stack = |extent: fn(&polyomino::Shape) -> usize, limit: usize, vertical: bool| {
+        let mut presents = Vec::new();
+        let mut offset = 0;
+        for (i, &count) in region.counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let (variant, v) = shapes[i]
+                .variants
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.fits_in(w, h))
+                .min_by_key(|(_, v)| extent(v))?;
+            for _ in 0..count {
+                let (x, y) = if vertical { (0, offset) } else { (offset, 0) };
+                presents.push(PlacedPresent::new(&shapes[i], i, variant, x, y));
+                offset += extent(v);
+            }
+        }
+        (offset <= limit).then_some(presents)
+    };
+    stack(polyomino::Shape::height, h, true).or_else(|| stack(polyomino::Shape::width, w, false))
 }
 
 #[derive(Clone)]
 struct Placement {
     chunks: SmallVec<[(u16, u64); 4]>,
+    /// The index of the variant placed, into its shape's variants.
+    variant: u8,
+    /// The offset of the variant's top-left corner.
+    x: u16,
+    y: u16,
+    /// The combined [`Bitboard::fingerprint`] contribution of the cells the placement covers.
+    fingerprint: u128,
+}
+
+/// Placement lists keyed by (region width, region height, shape), each generated the first time
+/// a search asks for it. Regions that the triage settles never need theirs, and regions of the
+/// same size share them, even when searched in parallel.
+struct PlacementCache {
+    lists: AHashMap<(usize, usize, usize), OnceLock<PlacementList>>,
+}
+
+impl PlacementCache {
+    /// Creates an empty cache with room for every shape each region lists.
+    fn new(regions: &[Region]) -> Self {
+        let mut lists = AHashMap::new();
+        for region in regions {
+            for (i, &c) in region.counts.iter().enumerate() {
+                if c != 0 {
+                    lists.entry((region.w, region.h, i)).or_default();
+                }
+            }
+        }
+        Self { lists }
+    }
+
+    /// Returns the placements of `shapes[shape]` in a `w` by `h` region, generating them if this
+    /// is the first request. Panics unless one of the regions the cache was created for has that
+    /// size and lists that shape.
+    fn get(&self, shapes: &[Shape], w: usize, h: usize, shape: usize) -> &PlacementList {
+        self.lists[&(w, h, shape)].get_or_init(|| PlacementList::generate(w, h, &shapes[shape]))
+    }
 }
 
 #[derive(Clone)]
 struct PlacementList {
     placements: Vec<Placement>,
+    /// Whether each placement has the smallest index among its images under the symmetries of
+    /// the region.
+    orbit_minimal: Vec<bool>,
 }
 
 impl PlacementList {
     fn generate(region_w: usize, region_h: usize, shape: &Shape) -> Self {
         let mut placements = Vec::new();
-        for v in &shape.variants {
-            if v.w > region_w || v.h > region_h {
+        for (variant, v) in shape.variants.iter().enumerate() {
+            if !v.fits_in(region_w, region_h) {
                 continue;
             }
-            let max_x0 = region_w - v.w;
-            let max_y0 = region_h - v.h;
+            let max_x0 = region_w - v.width();
+            let max_y0 = region_h - v.height();
             for y0 in 0..=max_y0 {
                 for x0 in 0..=max_x0 {
                     let mut chunks: SmallVec<[(u16, u64); 4]> = SmallVec::new();
-                    for &(dx, dy) in &v.cells {
+                    let mut fingerprint = 0;
+                    for &(dx, dy) in v.cells() {
                         let x = x0 + dx as usize;
                         let y = y0 + dy as usize;
                         let idx = y * region_w + x;
+                        fingerprint ^= zobrist(Zobrist::Cell, idx, 0);
                         let wi = (idx >> 6) as u16;
                         let bit = 1u64 << (idx & 63);
                         if let Some((_, m)) = chunks.iter_mut().find(|(w, _)| *w == wi) {
@@ -231,11 +579,21 @@ impl PlacementList {
                             chunks.push((wi, bit));
                         }
                     }
-                    placements.push(Placement { chunks });
+                    placements.push(Placement {
+                        chunks,
+                        variant: variant as u8,
+                        x: x0 as u16,
+                        y: y0 as u16,
+                        fingerprint,
+                    });
                 }
             }
         }
-        Self { placements }
+        let orbit_minimal = orbit_minimal(region_w, region_h, &placements);
+        Self {
+            placements,
+            orbit_minimal,
+        }
     }
 
     #[inline]
@@ -244,13 +602,82 @@ impl PlacementList {
     }
 }
 
+/// Returns, for each placement, whether every symmetry of a `w` by `h` region maps it onto a
+/// placement with an index at least as large.
+fn orbit_minimal(w: usize, h: usize, placements: &[Placement]) -> Vec<bool> {
+    let key = |cells: &mut dyn Iterator<Item = usize>| {
+        let mut chunks: SmallVec<[(u16, u64); 4]> = SmallVec::new();
+        for idx in cells {
+            let (wi, bit) = ((idx >> 6) as u16, 1u64 << (idx & 63));
+            match chunks.iter_mut().find(|(w, _)| *w == wi) {
+                Some((_, m)) => *m |= bit,
+                None => chunks.push((wi, bit)),
+            }
+        }
+        chunks.sort_unstable();
+        chunks
+    };
+    let index: AHashMap<SmallVec<[(u16, u64); 4]>, usize> = placements
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (key(&mut placement_cells(p)), i))
+        .collect();
+    // The flips and half turn map any rectangle onto itself; the transposes and quarter turns
+    // only map a square onto itself.
+    let symmetries = if w == h { 1..8 } else { 1..4 };
+    placements
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            symmetries.clone().all(|g| {
+                let mut image = placement_cells(p).map(|idx| {
+                    let (x, y) = (idx % w, idx / w);
+                    let (x, y) = match g {
+                        1 => (w - 1 - x, y),
+                        2 => (x, h - 1 - y),
+                        3 => (w - 1 - x, h - 1 - y),
+                        4 => (y, x),
+                        5 => (h - 1 - y, w - 1 - x),
+                        6 => (h - 1 - y, x),
+                        _ => (y, w - 1 - x),
+                    };
+                    y * w + x
+                });
+                index.get(&key(&mut image)).is_none_or(|&j| j >= i)
+            })
+        })
+        .collect()
+}
+
+fn placement_cells(p: &Placement) -> impl Iterator<Item = usize> + '_ {
+    p.chunks.iter().flat_map(|&(wi, m)| {
+        (0..64)
+            .filter(move |bit| m >> bit & 1 == 1)
+            .map(move |bit| wi as usize * 64 + bit)
+    })
+}
+
 fn region_can_fit(
     region: &Region,
     shapes: &[Shape],
-    placement_map: &AHashMap<(usize, usize, usize), Arc<PlacementList>>,
+    placements: &PlacementCache,
+    options: &Options,
 ) -> bool {
+    region_packing(region, shapes, placements, options)
+        .0
+        .is_some()
+}
+
+/// Searches for a packing of the region's presents, returning the placement used for each
+/// present if there is one, along with how much searching it took.
+fn region_packing(
+    region: &Region,
+    shapes: &[Shape],
+    cache: &PlacementCache,
+    options: &Options,
+) -> (Option<Vec<PlacedPresent>>, SearchStats) {
     if region.counts.len() != shapes.len() {
-        return false;
+        return (None, SearchStats::default());
     }
     let board_cells = region.w * region.h;
     let needed_cells: usize = region
@@ -260,128 +687,539 @@ fn region_can_fit(
         .map(|(i, &c)| (c as usize) * shapes[i].area)
         .sum();
     if needed_cells > board_cells {
-        return false;
+        return (None, SearchStats::default());
     }
-    let mut placements: Vec<Option<Arc<PlacementList>>> = vec![None; shapes.len()];
+    let mut placements: Vec<Option<&PlacementList>> = vec![None; shapes.len()];
     let mut types: Vec<usize> = Vec::new();
     for (i, &c) in region.counts.iter().enumerate() {
         if c == 0 {
             continue;
         }
-        let plist = placement_map.get(&(region.w, region.h, i)).unwrap();
+        let plist = cache.get(shapes, region.w, region.h, i);
         if plist.placements.is_empty() {
-            return false;
+            return (None, SearchStats::default());
         }
-        placements[i] = Some(plist.clone());
+        placements[i] = Some(plist);
         types.push(i);
     }
-    let words = board_cells.div_ceil(64);
-    let mut occ = vec![0u64; words];
+    let mut board = Bitboard::new(board_cells);
     let mut remaining = region.counts.clone();
     let pieces_left: usize = remaining.iter().map(|&c| c as usize).sum();
-    let mut memo: AHashSet<StateKey> = AHashSet::new();
-    dfs_pack(
-        &mut occ,
-        &mut remaining,
-        &placements,
-        &types,
-        pieces_left,
-        &mut memo,
-    )
-}
-
-#[derive(Hash, Eq, PartialEq)]
-struct StateKey {
-    occ: SmallVec<[u64; 16]>,
-    remaining: SmallVec<[u8; 32]>,
-}
-
-fn dfs_pack(
-    occ: &mut [u64],
-    remaining: &mut [u8],
-    placements: &[Option<Arc<PlacementList>>],
-    types: &[usize],
-    pieces_left: usize,
-    memo: &mut AHashSet<StateKey>,
-) -> bool {
-    if pieces_left == 0 {
-        return true;
-    }
-    let key = StateKey {
-        occ: SmallVec::from_slice(occ),
-        remaining: SmallVec::from_slice(remaining),
+    let mut packer = Packer {
+        placements: &placements,
+        types: &types,
+        areas: shapes.iter().map(|s| s.area).collect(),
+        width: region.w,
+        height: region.h,
+        pieces: pieces_left,
+        prune_holes: options.prune_holes,
+        memo: Memo::bounded(options.memo_limit),
+        seen: BitSet::default(),
+        stack: Vec::new(),
+        holes: Vec::new(),
+        reachable: Vec::new(),
+        placed: Vec::new(),
+        stats: SearchStats::default(),
+        fingerprint: types.iter().fold(0, |fp, &t| {
+            fp ^ zobrist(Zobrist::Remaining, t, remaining[t] as usize)
+                ^ zobrist(Zobrist::Next, t, 0)
+        }),
     };
-    if memo.contains(&key) {
-        return false;
+    let mut next = vec![0; shapes.len()];
+    if !packer.pack(&mut board, &mut remaining, &mut next, pieces_left) {
+        return (None, packer.search_stats());
     }
-    let mut best_t: Option<usize> = None;
-    let mut best_fit_count: usize = usize::MAX;
-    for &t in types {
-        let cnt = remaining[t];
-        if cnt == 0 {
-            continue;
+    let packing = packer
+        .placed
+        .iter()
+        .map(|&(t, i)| {
+            let p = &placements[t].as_ref().unwrap().placements[i];
+            PlacedPresent::new(
+                &shapes[t],
+                t,
+                p.variant as usize,
+                p.x as usize,
+                p.y as usize,
+            )
+        })
+        .collect();
+    (Some(packing), packer.search_stats())
+}
+
+/// What a pseudo-random [`zobrist`] value stands for.
+#[derive(Clone, Copy)]
+enum Zobrist {
+    /// A covered cell, by index.
+    Cell = 1,
+    /// The number of copies of a shape left to place.
+    Remaining,
+    /// The smallest placement index the next copy of a shape may use.
+    Next,
+}
+
+/// Returns a fixed pseudo-random 128-bit value for `(kind, a, b)`.
+///
+/// A search state is fingerprinted by XOR-ing together the values for its covered cells and its
+/// per-shape counters, so the fingerprint can be updated in constant time as presents are placed
+/// and removed, and the memo stores 16 bytes per state however large the region. Should two
+/// distinct states ever share a fingerprint, the search could skip a state it has not explored,
+/// but with 128 bits that is vanishingly unlikely at any feasible memo size.
+fn zobrist(kind: Zobrist, a: usize, b: usize) -> u128 {
+    fn splitmix64(mut x: u64) -> u64 {
+        x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+    let seed = splitmix64(splitmix64(splitmix64(kind as u64) ^ a as u64) ^ b as u64);
+    let lo = splitmix64(seed);
+    let hi = splitmix64(seed ^ 0x6a09_e667_f3bc_c908);
+    (hi as u128) << 64 | lo as u128
+}
+
+/// The cells of a region, one bit each, in as many words as the region needs.
+struct Bitboard {
+    bits: BitSet,
+    /// The XOR of the [`zobrist`] values of the covered cells.
+    fingerprint: u128,
+}
+
+impl Bitboard {
+    fn new(cells: usize) -> Self {
+        Self {
+            bits: BitSet::new(cells),
+            fingerprint: 0,
         }
-        let plist = placements[t].as_ref().unwrap();
-        let mut fit = 0usize;
-        for p in plist.iter() {
-            if fits(occ, p) {
-                fit += 1;
-                if fit >= best_fit_count {
+    }
+
+    #[inline(always)]
+    fn fits(&self, p: &Placement) -> bool {
+        p.chunks
+            .iter()
+            .all(|&(wi, m)| self.bits.words()[wi as usize] & m == 0)
+    }
+
+    #[inline(always)]
+    fn apply(&mut self, p: &Placement) {
+        let words = self.bits.words_mut();
+        for &(wi, m) in p.chunks.iter() {
+            words[wi as usize] |= m;
+        }
+        self.fingerprint ^= p.fingerprint;
+    }
+
+    #[inline(always)]
+    fn unapply(&mut self, p: &Placement) {
+        let words = self.bits.words_mut();
+        for &(wi, m) in p.chunks.iter() {
+            words[wi as usize] ^= m;
+        }
+        self.fingerprint ^= p.fingerprint;
+    }
+}
+
+/// The state shared by every level of the depth-first packing search.
+struct Packer<'a> {
+    placements: &'a [Option<&'a PlacementList>],
+    types: &'a [usize],
+    areas: Vec<usize>,
+    width: usize,
+    height: usize,
+    /// The number of presents in the region.
+    pieces: usize,
+    prune_holes: bool,
+    /// The dead-end states seen so far, by fingerprint.
+    memo: Memo<u128, ()>,
+    /// Scratch space for the hole pruning, kept to avoid allocating at every node.
+    seen: BitSet,
+    stack: Vec<usize>,
+    holes: Vec<usize>,
+    reachable: Vec<bool>,
+    /// The shape and placement index of each present on the board, in the order placed.
+    placed: Vec<(usize, usize)>,
+    /// The XOR of the [`zobrist`] values of the per-shape counters `remaining` and `next`.
+    fingerprint: u128,
+    stats: SearchStats,
+}
+
+impl<'a> Packer<'a> {
+    fn search_stats(&self) -> SearchStats {
+        SearchStats {
+            memo_hits: self.memo.stats().hits,
+            ..self.stats
+        }
+    }
+
+    /// Returns whether the remaining presents can be added to the board.
+    ///
+    /// Copies of a shape are interchangeable, so each one is placed at a larger placement index
+    /// than the copy before it, starting from `next`. Every symmetry of the region maps packings
+    /// onto packings, so the first present placed is also restricted to placements with the
+    /// smallest index in their orbit: applying the symmetry that minimizes the smallest index
+    /// used by that shape turns any packing into one that starts there.
+    fn pack(
+        &mut self,
+        board: &mut Bitboard,
+        remaining: &mut [u8],
+        next: &mut [u32],
+        pieces_left: usize,
+    ) -> bool {
+        self.stats.nodes += 1;
+        if pieces_left == 0 {
+            return true;
+        }
+        let key = board.fingerprint ^ self.fingerprint;
+        if self.memo.get(&key).is_some() {
+            return false;
+        }
+        if self.prune_holes && !self.holes_can_hold(&board.bits, remaining) {
+            self.memo.insert(key, ());
+            return false;
+        }
+        let first = pieces_left == self.pieces;
+        let placements = self.placements;
+        let candidates = move |plist: &'a PlacementList, from: u32| {
+            (from as usize..plist.placements.len())
+                .filter(move |&i| !first || plist.orbit_minimal[i])
+                .map(move |i| (i, &plist.placements[i]))
+        };
+        let mut best_t: Option<usize> = None;
+        let mut best_fit_count: usize = usize::MAX;
+        for &t in self.types {
+            let cnt = remaining[t];
+            if cnt == 0 {
+                continue;
+            }
+            let plist = placements[t].as_ref().unwrap();
+            let mut fit = 0usize;
+            for (_, p) in candidates(plist, next[t]) {
+                if board.fits(p) {
+                    fit += 1;
+                    if fit >= best_fit_count {
+                        break;
+                    }
+                }
+            }
+            if fit == 0 {
+                self.memo.insert(key, ());
+                return false;
+            }
+            if fit < best_fit_count {
+                best_fit_count = fit;
+                best_t = Some(t);
+                if best_fit_count == 1 {
                     break;
                 }
             }
         }
-        if fit == 0 {
-            memo.insert(key);
-            return false;
-        }
-        if fit < best_fit_count {
-            best_fit_count = fit;
-            best_t = Some(t);
-            if best_fit_count == 1 {
-                break;
+        let t = best_t.unwrap();
+        let plist = placements[t].as_ref().unwrap();
+        let from = next[t];
+        let counters = zobrist(Zobrist::Remaining, t, remaining[t] as usize)
+            ^ zobrist(Zobrist::Remaining, t, remaining[t] as usize - 1)
+            ^ zobrist(Zobrist::Next, t, from as usize);
+        for (i, p) in candidates(plist, from) {
+            if !board.fits(p) {
+                continue;
             }
+            board.apply(p);
+            remaining[t] -= 1;
+            next[t] = if remaining[t] == 0 { 0 } else { i as u32 + 1 };
+            let change = counters ^ zobrist(Zobrist::Next, t, next[t] as usize);
+            self.fingerprint ^= change;
+            self.placed.push((t, i));
+            if self.pack(board, remaining, next, pieces_left - 1) {
+                return true;
+            }
+            self.placed.pop();
+            self.fingerprint ^= change;
+            next[t] = from;
+            remaining[t] += 1;
+            board.unapply(p);
         }
+        self.memo.insert(key, ());
+        false
     }
-    let t = best_t.unwrap();
-    let plist = placements[t].as_ref().unwrap();
-    for p in plist.iter() {
-        if !fits(occ, p) {
-            continue;
+
+    /// Returns whether the empty cells could still take the remaining presents, judging by area
+    /// alone.
+    ///
+    /// A present lies entirely inside one connected hole, so each hole can take at most the
+    /// largest total area of remaining presents that fits in it. If those totals add up to less
+    /// than the area still to be placed, no packing exists.
+    fn holes_can_hold(&mut self, occ: &BitSet, remaining: &[u8]) -> bool {
+        let needed: usize = self
+            .types
+            .iter()
+            .map(|&t| remaining[t] as usize * self.areas[t])
+            .sum();
+        self.find_holes(occ);
+        let largest = self.holes.iter().copied().max().unwrap_or(0);
+        // reachable[n] is whether some multiset of the remaining presents has total area n.
+        let reachable = &mut self.reachable;
+        reachable.clear();
+        reachable.resize(largest + 1, false);
+        reachable[0] = true;
+        for &t in self.types {
+            let area = self.areas[t];
+            for _ in 0..remaining[t] {
+                for n in (area..=largest).rev() {
+                    reachable[n] |= reachable[n - area];
+                }
+            }
         }
-        apply(occ, p);
-        remaining[t] -= 1;
-        if dfs_pack(occ, remaining, placements, types, pieces_left - 1, memo) {
-            return true;
+        let usable: usize = self
+            .holes
+            .iter()
+            .map(|&size| (0..=size).rev().find(|&n| reachable[n]).unwrap_or(0))
+            .sum();
+        usable >= needed
+    }
+
+    /// Fills `holes` with the sizes of the 4-connected components of empty cells.
+    fn find_holes(&mut self, occ: &BitSet) {
+        let (w, h) = (self.width, self.height);
+        let seen = &mut self.seen;
+        seen.clone_from(occ);
+        self.holes.clear();
+        for start in 0..w * h {
+            if !seen.insert(start) {
+                continue;
+            }
+            self.stack.push(start);
+            let mut size = 0;
+            while let Some(idx) = self.stack.pop() {
+                size += 1;
+                let (x, y) = (idx % w, idx / w);
+                let neighbours = [
+                    (x > 0).then(|| idx - 1),
+                    (x + 1 < w).then(|| idx + 1),
+                    (y > 0).then(|| idx - w),
+                    (y + 1 < h).then(|| idx + w),
+                ];
+                for n in neighbours.into_iter().flatten() {
+                    if seen.insert(n) {
+                        self.stack.push(n);
+                    }
+                }
+            }
+            self.holes.push(size);
         }
-        remaining[t] += 1;
-        unapply(occ, p);
     }
-    memo.insert(key);
-    false
 }
 
-#[inline(always)]
-fn fits(occ: &[u64], p: &Placement) -> bool {
-    for &(wi, m) in p.chunks.iter() {
-        if (occ[wi as usize] & m) != 0 {
-            return false;
+/// Decides whether a region's presents fit with Algorithm X over dancing links.
+fn region_fits_dlx(region: &Region, shapes: &[Shape], placements: &PlacementCache) -> bool {
+    if region.counts.len() != shapes.len() {
+        return false;
+    }
+    let types: Vec<usize> = (0..shapes.len())
+        .filter(|&i| region.counts[i] != 0)
+        .collect();
+    let mut dlx = Dlx::new(
+        &types
+            .iter()
+            .map(|&i| region.counts[i] as usize)
+            .collect::<Vec<_>>(),
+        region.w * region.h,
+    );
+    for (primary, &i) in types.iter().enumerate() {
+        for p in placements.get(shapes, region.w, region.h, i).iter() {
+            dlx.add_row(primary, placement_cells(p));
         }
     }
-    true
+    dlx.search()
 }
 
-#[inline(always)]
-fn apply(occ: &mut [u64], p: &Placement) {
-    for &(wi, m) in p.chunks.iter() {
-        occ[wi as usize] |= m;
-    }
+/// Dancing links for exact cover with multiplicities. Primary columns must each be covered by a
+/// given number of rows, and secondary columns by at most one row.
+///
+/// Nodes live in parallel arrays: node 0 is the root, the column headers follow it (primary
+/// columns first), and the rows' nodes come after. Only the primary headers are linked into the
+/// root's list, so only they are ever chosen to branch on.
+struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The header of each node's column.
+    column: Vec<usize>,
+    /// The number of rows in each column, indexed by header.
+    size: Vec<usize>,
+    /// How many more rows each primary column needs, indexed by header.
+    need: Vec<usize>,
+    num_primary: usize,
 }
 
-#[inline(always)]
-fn unapply(occ: &mut [u64], p: &Placement) {
-    for &(wi, m) in p.chunks.iter() {
-        occ[wi as usize] ^= m;
+impl Dlx {
+    fn new(needs: &[usize], num_secondary: usize) -> Self {
+        let num_primary = needs.len();
+        let headers = 1 + num_primary + num_secondary;
+        let mut dlx = Dlx {
+            left: (0..headers).collect(),
+            right: (0..headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            size: vec![0; headers],
+            need: vec![0; headers],
+            num_primary,
+        };
+        for c in 0..=num_primary {
+            dlx.right[c] = (c + 1) % (num_primary + 1);
+            dlx.left[(c + 1) % (num_primary + 1)] = c;
+        }
+        dlx.need[1..=num_primary].copy_from_slice(needs);
+        dlx
+    }
+
+    /// Adds a row covering primary column `primary` and the given secondary columns.
+    fn add_row(&mut self, primary: usize, secondary: impl Iterator<Item = usize>) {
+        let first = self.left.len();
+        let columns =
+            std::iter::once(1 + primary).chain(secondary.map(|c| 1 + self.num_primary + c));
+        for (k, c) in columns.enumerate() {
+            let node = first + k;
+            self.left.push(if k == 0 { node } else { node - 1 });
+            self.right.push(first);
+            if k > 0 {
+                self.right[node - 1] = node;
+                self.left[first] = node;
+            }
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            let above = self.up[c];
+            self.down[above] = node;
+            self.up[c] = node;
+            self.column.push(c);
+            self.size[c] += 1;
+        }
+    }
+
+    fn unlink_vertical(&mut self, node: usize) {
+        let (up, down) = (self.up[node], self.down[node]);
+        self.down[up] = down;
+        self.up[down] = up;
+        self.size[self.column[node]] -= 1;
+    }
+
+    fn relink_vertical(&mut self, node: usize) {
+        let (up, down) = (self.up[node], self.down[node]);
+        self.down[up] = node;
+        self.up[down] = node;
+        self.size[self.column[node]] += 1;
+    }
+
+    /// Removes a column from the header list and every row in it from the other columns.
+    fn cover(&mut self, c: usize) {
+        let (left, right) = (self.left[c], self.right[c]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.unlink_vertical(j);
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.relink_vertical(j);
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (left, right) = (self.left[c], self.right[c]);
+        self.right[left] = c;
+        self.left[right] = c;
+    }
+
+    /// Removes a row from all of its columns.
+    fn hide_row(&mut self, row: usize) {
+        let mut j = row;
+        loop {
+            self.unlink_vertical(j);
+            j = self.right[j];
+            if j == row {
+                break;
+            }
+        }
+    }
+
+    fn unhide_row(&mut self, row: usize) {
+        let mut j = self.left[row];
+        loop {
+            self.relink_vertical(j);
+            if j == row {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Returns whether every primary column can be covered as often as it needs.
+    ///
+    /// Branches on the primary column with the fewest rows. Each row tried is then hidden from
+    /// its siblings, so every set of rows is explored once rather than once per order in which
+    /// its rows could be chosen.
+    fn search(&mut self) -> bool {
+        let mut c = self.right[0];
+        if c == 0 {
+            return true;
+        }
+        let mut best = c;
+        while c != 0 {
+            if self.size[c] < self.size[best] {
+                best = c;
+            }
+            c = self.right[c];
+        }
+        let c = best;
+        if self.size[c] < self.need[c] {
+            return false;
+        }
+        let mut tried = Vec::new();
+        let mut found = false;
+        let mut r = self.down[c];
+        while r != c {
+            self.hide_row(r);
+            tried.push(r);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+            self.need[c] -= 1;
+            let done = self.need[c] == 0;
+            if done {
+                self.cover(c);
+            }
+            found = self.search();
+            if done {
+                self.uncover(c);
+            }
+            self.need[c] += 1;
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            if found {
+                break;
+            }
+            r = self.down[r];
+        }
+        for &r in tried.iter().rev() {
+            self.unhide_row(r);
+        }
+        found
     }
 }
 
@@ -469,103 +1307,286 @@ fn is_region_line(s: &str) -> bool {
         && h.chars().all(|c| c.is_ascii_digit())
 }
 
-fn gen_variants(base: &[(i32, i32)]) -> Vec<Variant> {
-    let mut seen: AHashSet<Vec<(u8, u8)>> = AHashSet::new();
-    let mut out: Vec<Variant> = Vec::new();
-    for flip in [false, true] {
-        for rot in 0..4 {
-            let mut coords: Vec<(i32, i32)> = Vec::with_capacity(base.len());
-            for &(x0, y0) in base {
-                let mut x = x0;
-                let y = y0;
-                if flip {
-                    x = -x;
-                }
-                let (rx, ry) = match rot {
-                    0 => (x, y),
-                    1 => (-y, x),
-                    2 => (-x, -y),
-                    3 => (y, -x),
-                    _ => unreachable!(),
-                };
-                coords.push((rx, ry));
-            }
-            let min_x = coords.iter().map(|(x, _)| *x).min().unwrap();
-            let min_y = coords.iter().map(|(_, y)| *y).min().unwrap();
-            for (x, y) in coords.iter_mut() {
-                *x -= min_x;
-                *y -= min_y;
-            }
-            let max_x = coords.iter().map(|(x, _)| *x).max().unwrap();
-            let max_y = coords.iter().map(|(_, y)| *y).max().unwrap();
-            let w = (max_x + 1) as usize;
-            let h = (max_y + 1) as usize;
-            let mut cells_u8: Vec<(u8, u8)> = coords
-                .into_iter()
-                .map(|(x, y)| (x as u8, y as u8))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::DAY12 as EXAMPLE;
+    use crate::puzzle::example_tests;
+
+    example_tests! {
+        test_part_1_example_1: solve_part_1(EXAMPLE) == "2",
+        test_part_2_example_1: solve_part_2(EXAMPLE) == "Day 12 has no part 2",
+    }
+
+    #[test]
+    fn test_part_1_example_dlx() {
+        let options = Options {
+            solver: Solver::Dlx,
+            ..Options::default()
+        };
+        let puzzle = Day::with_options(EXAMPLE, options);
+        assert_eq!(puzzle.solve_part_1(), "2");
+    }
+
+    #[test]
+    fn test_part_1_example_pruned() {
+        let options = Options {
+            prune_holes: true,
+            ..Options::default()
+        };
+        let puzzle = Day::with_options(EXAMPLE, options);
+        assert_eq!(puzzle.solve_part_1(), "2");
+    }
+
+    #[test]
+    fn test_hole_pruning_matches_dfs() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        let day = Day::with_options(EXAMPLE, Options::default());
+        let pruned = Options {
+            prune_holes: true,
+            ..Options::default()
+        };
+        for _ in 0..100 {
+            let w = rng.random_range(3..7usize);
+            let h = rng.random_range(3..7usize);
+            let counts: Vec<u8> = (0..day.shapes.len())
+                .map(|_| rng.random_range(0..3u8))
                 .collect();
-            cells_u8.sort_unstable();
-            if seen.insert(cells_u8.clone()) {
-                out.push(Variant {
-                    w,
-                    h,
-                    cells: cells_u8,
-                });
+            let region = Region { w, h, counts };
+            let placements = PlacementCache::new(std::slice::from_ref(&region));
+            assert_eq!(
+                region_can_fit(&region, &day.shapes, &placements, &pruned),
+                region_can_fit(&region, &day.shapes, &placements, &Options::default()),
+                "{w}x{h}: {:?}",
+                region.counts
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_holes() {
+        let placements = [];
+        let mut packer = Packer {
+            placements: &placements,
+            types: &[],
+            areas: Vec::new(),
+            width: 5,
+            height: 3,
+            pieces: 0,
+            prune_holes: true,
+            memo: Memo::bounded(1),
+            seen: BitSet::default(),
+            stack: Vec::new(),
+            holes: Vec::new(),
+            reachable: Vec::new(),
+            placed: Vec::new(),
+            fingerprint: 0,
+            stats: SearchStats::default(),
+        };
+        // ..#..
+        // ###..
+        // .#...
+        let mut occ = BitSet::new(15);
+        for i in [2, 5, 6, 7, 11] {
+            occ.set(i);
+        }
+        packer.find_holes(&occ);
+        packer.holes.sort_unstable();
+        assert_eq!(packer.holes, vec![1, 2, 7]);
+    }
+
+    #[test]
+    fn test_orbit_minimal() {
+        let day = Day::with_options(EXAMPLE, Options::default());
+        // Shape 4 fits a 3x3 region only by filling its bounding box, and its four rotations are
+        // the images of one another under the square's symmetries.
+        let plist = PlacementList::generate(3, 3, &day.shapes[4]);
+        assert_eq!(plist.placements.len(), 4);
+        assert_eq!(plist.orbit_minimal, vec![true, false, false, false]);
+    }
+
+    /// Checks that a packing places exactly the region's presents, inside it, without overlaps.
+    fn assert_valid_packing(day: &Day, index: usize, packing: &Packing) {
+        let region = &day.regions[index];
+        assert_eq!((packing.width(), packing.height()), (region.w, region.h));
+        let mut counts = vec![0; day.shapes.len()];
+        let mut covered = ahash::AHashSet::new();
+        for present in packing.presents() {
+            counts[present.shape] += 1;
+            assert_eq!(present.cells().len(), day.shapes[present.shape].area);
+            for &(x, y) in present.cells() {
+                assert!(
+                    x < region.w && y < region.h,
+                    "({x}, {y}) is outside the region"
+                );
+                assert!(covered.insert((x, y)), "({x}, {y}) is covered twice");
             }
         }
+        assert_eq!(counts, region.counts);
     }
-    out
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    #[test]
+    fn test_packing_example() {
+        let day = Day::with_options(EXAMPLE, Options::default());
+        assert_eq!(day.num_regions(), 3);
+        for index in 0..2 {
+            let packing = day.packing(index).unwrap();
+            assert_valid_packing(&day, index, &packing);
+        }
+        assert!(day.packing(2).is_none());
+    }
+
+    #[test]
+    fn test_stacked_packing() {
+        let input = EXAMPLE.replace("4x4: 0 0 0 0 2 0", "3x12: 0 2 0 0 2 0");
+        let day = Day::with_options(&input, Options::default());
+        let packing = day.packing(0).unwrap();
+        assert_valid_packing(&day, 0, &packing);
+        assert!(packing.presents().iter().all(|p| p.x == 0));
+    }
+
+    #[test]
+    fn test_large_region_packing() {
+        // 1400 cells, spanning 22 words of the bitboard.
+        let input = EXAMPLE.replace("4x4: 0 0 0 0 2 0", "70x20: 5 5 5 5 5 5");
+        let day = Day::with_options(&input, Options::default());
+        let packing = day.packing(0).unwrap();
+        assert_valid_packing(&day, 0, &packing);
+    }
+
+    #[test]
+    fn test_infeasible_regions_example() {
+        let day = Day::with_options(EXAMPLE, Options::default());
+        let report = day.infeasible_regions();
+        assert_eq!(report.len(), 1);
+        let (index, reason) = &report[0];
+        assert_eq!(*index, 2);
+        let Infeasibility::SearchExhausted(stats) = reason else {
+            panic!("unexpected reason: {reason}");
+        };
+        assert!(stats.nodes > stats.memo_hits);
+    }
+
+    #[test]
+    fn test_infeasibility_reasons() {
+        let regions = "3x3: 0 0 0 0 2 0\n2x9: 0 0 0 0 1 0\n4x4: 0 0 0 0 0 0 1\n3x3: 0 0 0 0 1 0";
+        let input = EXAMPLE.replace(
+            "4x4: 0 0 0 0 2 0\n12x5: 1 0 1 0 2 2\n12x5: 1 0 1 0 3 2",
+            regions,
+        );
+        let day = Day::with_options(&input, Options::default());
+        assert_eq!(
+            day.infeasibility(0),
+            Some(Infeasibility::AreaDeficit {
+                needed: 14,
+                available: 9
+            })
+        );
+        assert_eq!(
+            day.infeasibility(1),
+            Some(Infeasibility::NoPlacements { shape: 4 })
+        );
+        assert_eq!(
+            day.infeasibility(2),
+            Some(Infeasibility::ShapeCountMismatch {
+                listed: 7,
+                shapes: 6
+            })
+        );
+        assert_eq!(day.infeasibility(3), None);
+        assert_eq!(
+            day.infeasibility(0).unwrap().to_string(),
+            "the presents cover 14 cells, but the region has 9"
+        );
+    }
 
     #[test]
-    fn test_part_1_example_1() {
-        let input = "\
-            0:\n\
-            ###\n\
-            ##.\n\
-            ##.\n\
-            \n\
-            1:\n\
-            ###\n\
-            ##.\n\
-            .##\n\
-            \n\
-            2:\n\
-            .##\n\
-            ###\n\
-            ##.\n\
-            \n\
-            3:\n\
-            ##.\n\
-            ###\n\
-            ##.\n\
-            \n\
-            4:\n\
-            ###\n\
-            #..\n\
-            ###\n\
-            \n\
-            5:\n\
-            ###\n\
-            .#.\n\
-            ###\n\
-            \n\
-            4x4: 0 0 0 0 2 0\n\
-            12x5: 1 0 1 0 2 2\n\
-            12x5: 1 0 1 0 3 2";
-        let puzzle = Day::create(input);
+    fn test_infeasibility_matches_packing() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        let regions: Vec<String> = (0..50)
+            .map(|_| {
+                let (w, h) = (rng.random_range(3..7u8), rng.random_range(3..7u8));
+                let counts: Vec<String> = (0..6)
+                    .map(|_| rng.random_range(0..3u8).to_string())
+                    .collect();
+                format!("{w}x{h}: {}", counts.join(" "))
+            })
+            .collect();
+        let input = EXAMPLE.replace("4x4: 0 0 0 0 2 0", &regions.join("\n"));
+        let day = Day::with_options(&input, Options::default());
+        for index in 0..day.num_regions() {
+            match day.packing(index) {
+                Some(packing) => {
+                    assert_valid_packing(&day, index, &packing);
+                    assert_eq!(day.infeasibility(index), None);
+                }
+                None => assert!(day.infeasibility(index).is_some()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_packing_display() {
+        let day = Day::with_options(EXAMPLE, Options::default());
+        let text = day.packing(0).unwrap().to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|line| line.len() == 4));
+        assert_eq!(text.matches('.').count(), 16 - 2 * 7);
+        assert_eq!(text.matches('A').count(), 7);
+        assert_eq!(text.matches('B').count(), 7);
+    }
+
+    #[test]
+    fn test_visualize() {
+        let vis = Day::create(EXAMPLE).visualize().unwrap();
+        assert_eq!(vis.extension(), "svg");
+        assert!(vis.contents().contains("present 1: shape 4"));
+    }
+
+    #[test]
+    fn test_raster() {
+        let day = Day::create(EXAMPLE);
+        let raster = day.raster().unwrap();
+        assert_eq!((raster.width(), raster.height()), (4, 4));
+        let white = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| raster.color(x, y) == [255, 255, 255])
+            .count();
+        assert_eq!(white, 16 - 2 * 7);
+    }
+
+    #[test]
+    fn test_part_1_example_small_memo() {
+        let options = Options {
+            memo_limit: 2,
+            ..Options::default()
+        };
+        let puzzle = Day::with_options(EXAMPLE, options);
         assert_eq!(puzzle.solve_part_1(), "2");
     }
 
     #[test]
-    fn test_solve_part_1() {
-        let input = std::fs::read_to_string(PathBuf::from("resources/tests/12")).unwrap();
-        let puzzle = Day::create(&input);
-        assert_eq!(puzzle.solve_part_1(), "519");
+    fn test_dlx_matches_dfs() {
+        use rand::Rng;
+        let mut rng = crate::seed::rng();
+        let day = Day::with_options(EXAMPLE, Options::default());
+        for _ in 0..100 {
+            let w = rng.random_range(3..7usize);
+            let h = rng.random_range(3..7usize);
+            let counts: Vec<u8> = (0..day.shapes.len())
+                .map(|_| rng.random_range(0..3u8))
+                .collect();
+            let region = Region { w, h, counts };
+            let placements = PlacementCache::new(std::slice::from_ref(&region));
+            assert_eq!(
+                region_fits_dlx(&region, &day.shapes, &placements),
+                region_can_fit(&region, &day.shapes, &placements, &Options::default()),
+                "{w}x{h}: {:?}",
+                region.counts
+            );
+        }
     }
 }
@@ -0,0 +1,154 @@
+/// One of the four orthogonal directions on a grid addressed by `(row, col)`, where rows grow
+/// downwards, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    /// Returns the `(row, col)` offset of one step in this direction.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Dir4::Up => (-1, 0),
+            Dir4::Right => (0, 1),
+            Dir4::Down => (1, 0),
+            Dir4::Left => (0, -1),
+        }
+    }
+
+    /// Returns the direction a quarter turn anticlockwise.
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// Returns the direction a quarter turn clockwise.
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Returns the offsets of the four orthogonal neighbours, clockwise from up.
+    pub fn offsets() -> impl Iterator<Item = (isize, isize)> {
+        Self::ALL.into_iter().map(Self::delta)
+    }
+}
+
+/// One of the eight orthogonal and diagonal directions on a grid addressed by `(row, col)`, in
+/// clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    /// Returns the `(row, col)` offset of one step in this direction.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Dir8::Up => (-1, 0),
+            Dir8::UpRight => (-1, 1),
+            Dir8::Right => (0, 1),
+            Dir8::DownRight => (1, 1),
+            Dir8::Down => (1, 0),
+            Dir8::DownLeft => (1, -1),
+            Dir8::Left => (0, -1),
+            Dir8::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Returns the direction an eighth of a turn anticlockwise.
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Returns the direction an eighth of a turn clockwise.
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Returns the offsets of the eight surrounding cells, clockwise from up.
+    pub fn offsets() -> impl Iterator<Item = (isize, isize)> {
+        Self::ALL.into_iter().map(Self::delta)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir4() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        for dir in Dir4::ALL {
+            let (dr, dc) = dir.delta();
+            assert_eq!(dir.opposite().delta(), (-dr, -dc));
+            // A quarter turn clockwise maps (row, col) offsets to (col, -row).
+            assert_eq!(dir.turn_right().delta(), (dc, -dr));
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.opposite());
+            assert_eq!(Dir8::from(dir).delta(), dir.delta());
+        }
+        assert_eq!(
+            Dir4::offsets().collect::<Vec<_>>(),
+            vec![(-1, 0), (0, 1), (1, 0), (0, -1)]
+        );
+    }
+
+    #[test]
+    fn test_dir8() {
+        assert_eq!(Dir8::Up.turn_right(), Dir8::UpRight);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::UpLeft);
+        for dir in Dir8::ALL {
+            let (dr, dc) = dir.delta();
+            assert_eq!(dir.opposite().delta(), (-dr, -dc));
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(
+                dir.turn_right().turn_right(),
+                Dir8::ALL[(dir as usize + 2) % 8]
+            );
+        }
+        let mut offsets: Vec<(isize, isize)> = Dir8::offsets().collect();
+        offsets.sort_unstable();
+        let expected: Vec<(isize, isize)> = (-1..=1)
+            .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+            .filter(|&d| d != (0, 0))
+            .collect();
+        assert_eq!(offsets, expected);
+    }
+}
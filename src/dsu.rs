@@ -0,0 +1,168 @@
+/// A disjoint-set forest over the elements `0..n`, with union by size and path compression.
+///
+/// ```
+/// use aoc2025::dsu::Dsu;
+///
+/// let mut dsu = Dsu::new(5);
+/// assert!(dsu.union(0, 1));
+/// assert!(dsu.union(3, 1));
+/// assert!(!dsu.union(0, 3));
+/// assert!(dsu.same(0, 3) && !dsu.same(0, 2));
+/// assert_eq!(dsu.size(1), 3);
+/// assert_eq!(dsu.components(), 3);
+/// ```
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    /// Creates `n` singleton sets.
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the representative of the set containing `x`.
+    ///
+    /// Time complexity: O(α(N)) amortized
+    /// Auxiliary space complexity: O(1)
+    pub fn find(&mut self, mut x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        while self.parent[x] != x {
+            let p = self.parent[x];
+            self.parent[x] = root;
+            x = p;
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning whether they were different sets.
+    ///
+    /// Time complexity: O(α(N)) amortized
+    /// Auxiliary space complexity: O(1)
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut ra = self.find(a);
+        let mut rb = self.find(b);
+        if ra == rb {
+            return false;
+        }
+        if self.size[ra] < self.size[rb] {
+            std::mem::swap(&mut ra, &mut rb);
+        }
+        self.parent[rb] = ra;
+        self.size[ra] += self.size[rb];
+        self.components -= 1;
+        true
+    }
+
+    /// Returns whether `a` and `b` are in the same set.
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of elements in the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Returns the number of sets.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the size of every set, in no particular order.
+    ///
+    /// Time complexity: O(N)
+    /// Auxiliary space complexity: O(N)
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.parent.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_singletons() {
+        let mut dsu = Dsu::new(4);
+        assert_eq!(dsu.len(), 4);
+        assert_eq!(dsu.components(), 4);
+        assert!((0..4).all(|x| dsu.find(x) == x && dsu.size(x) == 1));
+        assert!(Dsu::new(0).is_empty());
+    }
+
+    #[test]
+    fn test_union() {
+        let mut dsu = Dsu::new(5);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(3, 4));
+        assert!(dsu.union(1, 4));
+        assert!(!dsu.union(0, 3));
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 2));
+        assert_eq!(dsu.size(4), 4);
+        assert_eq!(dsu.components(), 2);
+        let mut sizes = dsu.component_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 4]);
+    }
+
+    /// Compares against a naive labelling that relabels a whole set on every union.
+    #[test]
+    fn test_matches_naive_labelling() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let n = rng.random_range(1..60usize);
+            let mut dsu = Dsu::new(n);
+            let mut label: Vec<usize> = (0..n).collect();
+            for _ in 0..rng.random_range(0..2 * n) {
+                let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+                let (la, lb) = (label[a], label[b]);
+                assert_eq!(dsu.union(a, b), la != lb);
+                for l in label.iter_mut() {
+                    if *l == lb {
+                        *l = la;
+                    }
+                }
+                let x = rng.random_range(0..n);
+                let y = rng.random_range(0..n);
+                assert_eq!(dsu.same(x, y), label[x] == label[y]);
+                let expected_size = label.iter().filter(|&&l| l == label[x]).count();
+                assert_eq!(dsu.size(x), expected_size);
+            }
+            let mut expected: Vec<usize> = (0..n)
+                .filter(|&l| label.contains(&l))
+                .map(|l| label.iter().filter(|&&m| m == l).count())
+                .collect();
+            let mut sizes = dsu.component_sizes();
+            expected.sort_unstable();
+            sizes.sort_unstable();
+            assert_eq!(dsu.components(), expected.len());
+            assert_eq!(sizes, expected);
+            assert_eq!(sizes.iter().sum::<usize>(), n);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::bitset::BitGrid;
+use crate::day12::Packing;
+use crate::puzzle::Puzzle;
+use crate::render::{Raster, Visualization, escape};
+use crate::report::{self, Report};
+use std::fmt::Write;
+
+/// The side of a cell in the pictures of grids, in SVG user units.
+const CELL: f64 = 6.0;
+
+/// Wraps `content` of the MIME type `mime` in the markers that evcxr looks for in what a value's
+/// `evcxr_display` method prints, so that the notebook shows it as that type rather than as text.
+pub fn rich_output(mime: &str, content: &str) -> String {
+    format!(
+        "EVCXR_BEGIN_CONTENT {mime}\n{}\nEVCXR_END_CONTENT\n",
+        content.trim_end()
+    )
+}
+
+/// Returns the rich output that shows `visualization`: SVG as a picture, and other text formats
+/// as preformatted text.
+fn visualization_output(visualization: &Visualization) -> String {
+    match visualization {
+        Visualization::Svg(svg) => rich_output("image/svg+xml", svg),
+        Visualization::Dot(dot) => rich_output("text/html", &format!("<pre>{}</pre>", escape(dot))),
+        #[cfg(feature = "png")]
+        Visualization::Png(bytes) => rich_output("image/png", &base64(bytes)),
+    }
+}
+
+/// Encodes `bytes` in standard base64 with padding, as evcxr expects images to be.
+#[cfg(feature = "png")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Returns an HTML table with `rows`, the first being the header.
+fn html_table<const N: usize>(rows: &[[String; N]]) -> String {
+    let mut html = String::from("<table>\n");
+    for (i, row) in rows.iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+        html.push_str("<tr>");
+        for cell in row {
+            write!(html, "<{tag}>{}</{tag}>", escape(cell)).unwrap();
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>");
+    html
+}
+
+impl BitGrid {
+    /// Shows the grid in evcxr as a picture, with the set bits black.
+    pub fn evcxr_display(&self) {
+        print!("{}", self.evcxr_output());
+    }
+
+    fn evcxr_output(&self) -> String {
+        let mut raster = Raster::new(self.width(), self.height(), "#ffffff");
+        for (row, col) in self.iter_ones() {
+            raster.set(col, row, "#000000", 0);
+        }
+        visualization_output(&raster.svg(CELL))
+    }
+}
+
+impl Raster {
+    /// Shows the grid in evcxr as a picture.
+    pub fn evcxr_display(&self) {
+        print!("{}", visualization_output(&self.svg(CELL)));
+    }
+}
+
+impl Packing {
+    /// Shows the packing in evcxr as the picture of [`Packing::svg`].
+    pub fn evcxr_display(&self) {
+        print!("{}", visualization_output(&self.svg()));
+    }
+}
+
+impl Report {
+    /// Shows the report in evcxr as an HTML table.
+    pub fn evcxr_display(&self) {
+        print!("{}", self.evcxr_output());
+    }
+
+    fn evcxr_output(&self) -> String {
+        let mut rows = report::rows(&self.days, &self.medians);
+        let total = rows.pop().unwrap();
+        let mut html = html_table(&rows);
+        // The total row is in bold, as in the Markdown table.
+        let total: String = total
+            .iter()
+            .map(|cell| format!("<td><b>{}</b></td>", escape(cell)))
+            .collect();
+        html.insert_str(
+            html.len() - "</table>".len(),
+            &format!("<tr>{total}</tr>\n"),
+        );
+        rich_output("text/html", &html)
+    }
+}
+
+impl dyn Puzzle {
+    /// Shows the puzzle in evcxr as a table of its answers, solving both parts, followed by its
+    /// visualization if it has one.
+    pub fn evcxr_display(&self) {
+        print!("{}", self.evcxr_output());
+    }
+
+    fn evcxr_output(&self) -> String {
+        let answers = html_table(&[
+            ["Part 1", "Part 2"].map(String::from),
+            [self.solve_part_1(), self.solve_part_2()],
+        ]);
+        let mut output = rich_output("text/html", &answers);
+        if let Some(visualization) = self.visualize() {
+            output.push_str(&visualization_output(&visualization));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::DayReport;
+    use crate::{day01, day04, examples};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_rich_output() {
+        assert_eq!(
+            rich_output("text/html", "<b>hi</b>\n"),
+            "EVCXR_BEGIN_CONTENT text/html\n<b>hi</b>\nEVCXR_END_CONTENT\n"
+        );
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(2, 1);
+        grid.set(0, 1);
+        let output = grid.evcxr_output();
+        assert!(output.starts_with("EVCXR_BEGIN_CONTENT image/svg+xml\n<svg"));
+        assert!(output.contains(r##"<rect x="6" y="0" width="6" height="6" fill="#000000"/>"##));
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report {
+            days: vec![DayReport {
+                day: 1,
+                answers: [Some("3".into()), Some("<6>".into())],
+                parse_ns: Some(1_000),
+                solve_ns: [Some(2_000), Some(3_000)],
+            }],
+            medians: BTreeMap::new(),
+        };
+        let output = report.evcxr_output();
+        assert!(output.starts_with("EVCXR_BEGIN_CONTENT text/html\n<table>\n<tr><th>Puzzle</th>"));
+        assert!(
+            output.contains("<tr><td>Day 01</td><td>3</td><td>&lt;6&gt;</td><td>6.0000 µs</td>")
+        );
+        assert!(output.contains("<tr><td><b>Total</b></td><td><b></b></td>"));
+        assert!(output.ends_with("</tr>\n</table>\nEVCXR_END_CONTENT\n"));
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let output = day01::Day::create(examples::DAY01).evcxr_output();
+        assert_eq!(
+            output,
+            rich_output(
+                "text/html",
+                "<table>\n<tr><th>Part 1</th><th>Part 2</th></tr>\n<tr><td>3</td><td>6</td></tr>\n</table>"
+            )
+        );
+        let output = day04::Day::create(examples::DAY04).evcxr_output();
+        assert!(output.contains("EVCXR_BEGIN_CONTENT image/svg+xml\n<svg"));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,282 @@
+use crate::runner::InputSource;
+use std::error::Error;
+
+/// Day 1's example: a list of dial rotations.
+pub const DAY01: &str = "\
+    L68\n\
+    L30\n\
+    R48\n\
+    L5\n\
+    R60\n\
+    L55\n\
+    L1\n\
+    L99\n\
+    R14\n\
+    L82";
+
+/// Day 2's example: a list of ID ranges.
+pub const DAY02: &str = "\
+    11-22,\
+    95-115,\
+    998-1012,\
+    1188511880-1188511890,\
+    222220-222224,\
+    1698522-1698528,\
+    446443-446449,\
+    38593856-38593862,\
+    565653-565659,\
+    824824821-824824827,\
+    2121212118-2121212124";
+
+/// Day 3's example: four banks of batteries.
+pub const DAY03: &str = "\
+    987654321111111\n\
+    811111111111119\n\
+    234234234234278\n\
+    818181911112111";
+
+/// Day 4's example: a grid of paper rolls.
+pub const DAY04: &str = "\
+    ..@@.@@@@.\n\
+    @@@.@.@.@@\n\
+    @@@@@.@.@@\n\
+    @.@@@@..@.\n\
+    @@.@@@@.@@\n\
+    .@@@@@@@.@\n\
+    .@.@.@.@@@\n\
+    @.@@@.@@@@\n\
+    .@@@@@@@@.\n\
+    @.@.@@@.@.";
+
+/// Day 5's example: fresh ingredient ranges, then ingredient IDs.
+pub const DAY05: &str = "\
+    3-5\n\
+    10-14\n\
+    16-20\n\
+    12-18\n\
+    \n\
+    1\n\
+    5\n\
+    8\n\
+    11\n\
+    17\n\
+    32";
+
+/// Day 6's example: a worksheet of problems. Its lines are concatenated rather than continued
+/// with `\`, which would drop their leading spaces.
+pub const DAY06: &str = concat!(
+    "123 328  51 64 \n",
+    " 45 64  387 23 \n",
+    "  6 98  215 314\n",
+    "*   +   *   +  ",
+);
+
+/// Day 7's example: a tachyon manifold.
+pub const DAY07: &str = "\
+    .......S.......\n\
+    ...............\n\
+    .......^.......\n\
+    ...............\n\
+    ......^.^......\n\
+    ...............\n\
+    .....^.^.^.....\n\
+    ...............\n\
+    ....^.^...^....\n\
+    ...............\n\
+    ...^.^...^.^...\n\
+    ...............\n\
+    ..^...^.....^..\n\
+    ...............\n\
+    .^.^.^.^.^...^.\n\
+    ...............";
+
+/// Day 8's example: junction box positions.
+pub const DAY08: &str = "\
+    162,817,812\n\
+    57,618,57\n\
+    906,360,560\n\
+    592,479,940\n\
+    352,342,300\n\
+    466,668,158\n\
+    542,29,236\n\
+    431,825,988\n\
+    739,650,466\n\
+    52,470,668\n\
+    216,146,977\n\
+    819,987,18\n\
+    117,168,530\n\
+    805,96,715\n\
+    346,949,466\n\
+    970,615,88\n\
+    941,993,340\n\
+    862,61,35\n\
+    984,92,344\n\
+    425,690,689";
+
+/// Day 9's example: red tile positions.
+pub const DAY09: &str = "\
+    7,1\n\
+    11,1\n\
+    11,7\n\
+    9,7\n\
+    9,5\n\
+    2,5\n\
+    2,3\n\
+    7,3";
+
+/// Day 10's example: machine descriptions.
+pub const DAY10: &str = "\
+    [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n\
+    [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}\n\
+    [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+
+/// Day 11's part 1 example: a graph of devices reachable from `you`.
+pub const DAY11_PART_1: &str = "\
+    aaa: you hhh\n\
+    you: bbb ccc\n\
+    bbb: ddd eee\n\
+    ccc: ddd eee fff\n\
+    ddd: ggg\n\
+    eee: out\n\
+    fff: out\n\
+    ggg: out\n\
+    hhh: ccc fff iii\n\
+    iii: out";
+
+/// Day 11's part 2 example: a graph of devices reachable from `svr`.
+pub const DAY11_PART_2: &str = "\
+    svr: aaa bbb\n\
+    aaa: fft\n\
+    fft: ccc\n\
+    bbb: tty\n\
+    tty: ccc\n\
+    ccc: ddd eee\n\
+    ddd: hub\n\
+    hub: fff\n\
+    eee: dac\n\
+    dac: fff\n\
+    fff: ggg hhh\n\
+    ggg: out\n\
+    hhh: out";
+
+/// Day 12's example: present shapes, then regions.
+pub const DAY12: &str = "\
+    0:\n\
+    ###\n\
+    ##.\n\
+    ##.\n\
+    \n\
+    1:\n\
+    ###\n\
+    ##.\n\
+    .##\n\
+    \n\
+    2:\n\
+    .##\n\
+    ###\n\
+    ##.\n\
+    \n\
+    3:\n\
+    ##.\n\
+    ###\n\
+    ##.\n\
+    \n\
+    4:\n\
+    ###\n\
+    #..\n\
+    ###\n\
+    \n\
+    5:\n\
+    ###\n\
+    .#.\n\
+    ###\n\
+    \n\
+    4x4: 0 0 0 0 2 0\n\
+    12x5: 1 0 1 0 2 2\n\
+    12x5: 1 0 1 0 3 2";
+
+/// Day 11's two examples as one input with an answer for each part. The part 1 devices are
+/// renamed to start with `j`, so that the graphs share only `out`.
+pub const DAY11: &str = "\
+    jaa: you jhh\n\
+    you: jbb jcc\n\
+    jbb: jdd jee\n\
+    jcc: jdd jee jff\n\
+    jdd: jgg\n\
+    jee: out\n\
+    jff: out\n\
+    jgg: out\n\
+    jhh: jcc jff jii\n\
+    jii: out\n\
+    svr: aaa bbb\n\
+    aaa: fft\n\
+    fft: ccc\n\
+    bbb: tty\n\
+    tty: ccc\n\
+    ccc: ddd eee\n\
+    ddd: hub\n\
+    hub: fff\n\
+    eee: dac\n\
+    dac: fff\n\
+    fff: ggg hhh\n\
+    ggg: out\n\
+    hhh: out";
+
+/// Returns the example input for `day` with an answer for each part, or `None` if there is no
+/// such day.
+pub fn get(day: u8) -> Option<&'static str> {
+    match day {
+        1 => Some(DAY01),
+        2 => Some(DAY02),
+        3 => Some(DAY03),
+        4 => Some(DAY04),
+        5 => Some(DAY05),
+        6 => Some(DAY06),
+        7 => Some(DAY07),
+        8 => Some(DAY08),
+        9 => Some(DAY09),
+        10 => Some(DAY10),
+        11 => Some(DAY11),
+        12 => Some(DAY12),
+        _ => None,
+    }
+}
+
+/// Serves each day's example instead of its puzzle input, for trying the solutions without an
+/// Advent of Code account (`--example`).
+pub struct Examples;
+
+impl InputSource for Examples {
+    fn get_input(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        get(day)
+            .map(str::to_string)
+            .ok_or_else(|| format!("no example for day {day}").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day11, registry};
+
+    #[test]
+    fn test_every_day_has_an_example() {
+        for day in &registry::DAYS {
+            assert!(Examples.get_input(day.number).is_ok(), "day {}", day.number);
+        }
+        assert!(Examples.get_input(0).is_err());
+    }
+
+    #[test]
+    fn test_day11_combined_example() {
+        let combined = day11::Day::create(DAY11);
+        assert_eq!(
+            combined.solve_part_1(),
+            day11::Day::create(DAY11_PART_1).solve_part_1()
+        );
+        assert_eq!(
+            combined.solve_part_2(),
+            day11::Day::create(DAY11_PART_2).solve_part_2()
+        );
+    }
+}
@@ -0,0 +1,162 @@
+/// Parses a non-empty run of ASCII digits, returning `None` if it holds anything else or the value
+/// does not fit in a `u64`.
+///
+/// Time complexity: O(N)
+/// Auxiliary space complexity: O(1)
+#[inline]
+pub fn parse_u64(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() {
+        return None;
+    }
+    if bytes.len() < 20 {
+        // 19 digits cannot overflow, so accumulate without checks and test the digits only once.
+        let mut value = 0u64;
+        let mut invalid = false;
+        for &b in bytes {
+            let digit = b.wrapping_sub(b'0');
+            invalid |= digit > 9;
+            value = value.wrapping_mul(10).wrapping_add(digit as u64);
+        }
+        return (!invalid).then_some(value);
+    }
+    bytes.iter().try_fold(0u64, |value, &b| {
+        let digit = b.wrapping_sub(b'0');
+        if digit > 9 {
+            return None;
+        }
+        value.checked_mul(10)?.checked_add(digit as u64)
+    })
+}
+
+/// Parses an optional `-` or `+` followed by a non-empty run of ASCII digits, returning `None` if
+/// the bytes hold anything else or the value does not fit in an `i64`.
+///
+/// Time complexity: O(N)
+/// Auxiliary space complexity: O(1)
+#[inline]
+pub fn parse_i64(bytes: &[u8]) -> Option<i64> {
+    match bytes.split_first() {
+        Some((b'-', digits)) => 0i64.checked_sub_unsigned(parse_u64(digits)?),
+        Some((b'+', digits)) => i64::try_from(parse_u64(digits)?).ok(),
+        _ => i64::try_from(parse_u64(bytes)?).ok(),
+    }
+}
+
+/// Returns the integers in `bytes`, in order, skipping everything between them. A `-` directly
+/// before a number makes it negative unless the `-` follows a letter or digit, so `3-5` holds `3`
+/// and `5` while `x=-5` holds `-5`.
+///
+/// ```
+/// use aoc2025::fastparse::integers;
+///
+/// let values: Vec<i64> = integers(b"3-5, x=-5").collect();
+/// assert_eq!(values, [3, 5, -5]);
+/// ```
+///
+/// The iterator panics on a number that does not fit in an `i64`.
+pub fn integers(bytes: &[u8]) -> Integers<'_> {
+    Integers { bytes, pos: 0 }
+}
+
+/// The iterator returned by [`integers`].
+#[derive(Clone, Debug)]
+pub struct Integers<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Iterator for Integers<'_> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        let bytes = self.bytes;
+        let start = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        self.pos = start + len;
+        let negative = start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric());
+        let value = parse_u64(&bytes[start..self.pos]).and_then(|magnitude| {
+            if negative {
+                0i64.checked_sub_unsigned(magnitude)
+            } else {
+                i64::try_from(magnitude).ok()
+            }
+        });
+        let text = || String::from_utf8_lossy(&bytes[start - negative as usize..self.pos]);
+        Some(value.unwrap_or_else(|| panic!("`{}` does not fit in a 64-bit integer", text())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_parse_u64() {
+        assert_eq!(parse_u64(b"0"), Some(0));
+        assert_eq!(parse_u64(b"00042"), Some(42));
+        assert_eq!(
+            parse_u64(b"9999999999999999999"),
+            Some(9_999_999_999_999_999_999)
+        );
+        assert_eq!(parse_u64(b"18446744073709551615"), Some(u64::MAX));
+        assert_eq!(parse_u64(b"000000000000000000001"), Some(1));
+        assert_eq!(parse_u64(b"18446744073709551616"), None);
+        for bad in [&b""[..], b"-1", b"+1", b"1 ", b"1a", b"/", b":"] {
+            assert_eq!(parse_u64(bad), None, "{}", String::from_utf8_lossy(bad));
+        }
+    }
+
+    #[test]
+    fn test_parse_i64() {
+        assert_eq!(parse_i64(b"-17"), Some(-17));
+        assert_eq!(parse_i64(b"+17"), Some(17));
+        assert_eq!(parse_i64(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse_i64(b"9223372036854775807"), Some(i64::MAX));
+        assert_eq!(parse_i64(b"9223372036854775808"), None);
+        assert_eq!(parse_i64(b"-9223372036854775809"), None);
+        assert_eq!(parse_i64(b"-"), None);
+        assert_eq!(parse_i64(b"--1"), None);
+    }
+
+    /// Compares both parsers against the standard library on random strings of digits and signs.
+    #[test]
+    fn test_matches_std() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..10_000 {
+            let len = rng.random_range(0..22);
+            let text: String = (0..len)
+                .map(|_| match rng.random_range(0..40) {
+                    0 => '-',
+                    1 => 'x',
+                    _ => char::from(b'0' + rng.random_range(0..10u8)),
+                })
+                .collect();
+            let bytes = text.as_bytes();
+            assert_eq!(parse_u64(bytes), text.parse().ok(), "{text}");
+            assert_eq!(parse_i64(bytes), text.parse().ok(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_integers() {
+        let all = |line: &str| integers(line.as_bytes()).collect::<Vec<i64>>();
+        assert_eq!(all("162,817,812"), vec![162, 817, 812]);
+        assert_eq!(all("3-5"), vec![3, 5]);
+        assert_eq!(all("p=-3,4 v=5,-6"), vec![-3, 4, 5, -6]);
+        assert_eq!(all("-1 - 2 a-3"), vec![-1, 2, 3]);
+        assert_eq!(all("[.##.] (3) (1,3) {3,5}"), vec![3, 1, 3, 3, 5]);
+        assert_eq!(all("no numbers"), Vec::<i64>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "`99999999999999999999` does not fit in a 64-bit integer")]
+    fn test_integers_overflow() {
+        integers(b"1,99999999999999999999").for_each(drop);
+    }
+}
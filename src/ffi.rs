@@ -0,0 +1,173 @@
+use crate::registry::{self, Part, SolveError};
+use std::slice;
+
+/// The answer was written to the output buffer.
+pub const AOC2025_OK: i32 = 0;
+/// No day with the given number is solved.
+pub const AOC2025_UNKNOWN_DAY: i32 = 1;
+/// The part is neither 1 nor 2.
+pub const AOC2025_UNKNOWN_PART: i32 = 2;
+/// The input is not valid UTF-8.
+pub const AOC2025_INVALID_UTF8: i32 = 3;
+/// The day could not parse or solve the input. The output buffer holds the reason.
+pub const AOC2025_INVALID_INPUT: i32 = 4;
+/// The answer does not fit in the output buffer, whose length was set to the size it needs.
+pub const AOC2025_BUFFER_TOO_SMALL: i32 = 5;
+/// A pointer that must not be null was null.
+pub const AOC2025_NULL_POINTER: i32 = 6;
+
+/// Solves `part` (1 or 2) of `day` on the `input_len` bytes of UTF-8 at `input_ptr`, for callers
+/// outside Rust, returning one of the `AOC2025_*` status codes.
+///
+/// On entry `*out_len` is the capacity of `out_buf`. The answer is written to `out_buf` as UTF-8
+/// without a trailing NUL, and `*out_len` is set to its length. An answer that does not fit is
+/// not written; `*out_len` is set to the length it needs instead, so the caller can retry with a
+/// larger buffer (answers are short, so 64 bytes is plenty). For [`AOC2025_INVALID_INPUT`] the
+/// reason is written the same way, cut short to fit. For the other errors `*out_len` is set to 0.
+///
+/// # Safety
+///
+/// `out_len` must point to a writable `usize`, `out_buf` to `*out_len` writable bytes, and
+/// `input_ptr` to `input_len` readable bytes. `out_buf` may be null when `*out_len` is 0, and
+/// `input_ptr` when `input_len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc2025_solve(
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_len.is_null() {
+        return AOC2025_NULL_POINTER;
+    }
+    let capacity = unsafe { out_len.replace(0) };
+    if (input_ptr.is_null() && input_len > 0) || (out_buf.is_null() && capacity > 0) {
+        return AOC2025_NULL_POINTER;
+    }
+    let input: &[u8] = if input_len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(input_ptr, input_len) }
+    };
+    let out: &mut [u8] = if capacity == 0 {
+        &mut []
+    } else {
+        unsafe { slice::from_raw_parts_mut(out_buf, capacity) }
+    };
+    let (status, len) = solve_into(day, part, input, out);
+    unsafe { out_len.write(len) };
+    status
+}
+
+/// Does the work of [`aoc2025_solve`] on safe slices, returning its status and the length to
+/// report back.
+fn solve_into(day: u8, part: u8, input: &[u8], out: &mut [u8]) -> (i32, usize) {
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => return (AOC2025_UNKNOWN_PART, 0),
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return (AOC2025_INVALID_UTF8, 0);
+    };
+    match registry::solve(day, part, input) {
+        Ok(answer) if answer.len() <= out.len() => {
+            out[..answer.len()].copy_from_slice(answer.as_bytes());
+            (AOC2025_OK, answer.len())
+        }
+        Ok(answer) => (AOC2025_BUFFER_TOO_SMALL, answer.len()),
+        Err(SolveError::UnknownDay(_)) => (AOC2025_UNKNOWN_DAY, 0),
+        Err(SolveError::Parse { error, .. }) => invalid_input(&error.to_string(), out),
+        Err(SolveError::InvalidInput { message, .. }) => invalid_input(&message, out),
+    }
+}
+
+/// Writes as much of `message` as fits in `out`, returning the status for invalid input and the
+/// length written.
+fn invalid_input(message: &str, out: &mut [u8]) -> (i32, usize) {
+    let len = message.floor_char_boundary(out.len());
+    out[..len].copy_from_slice(&message.as_bytes()[..len]);
+    (AOC2025_INVALID_INPUT, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+    use std::ptr;
+
+    /// Calls [`aoc2025_solve`] with an output buffer of `capacity` bytes, returning the status and
+    /// what was written (or, if nothing fit, the reported length as a string).
+    fn call(day: u8, part: u8, input: &[u8], capacity: usize) -> (i32, String) {
+        let mut out = vec![0; capacity];
+        let mut len = capacity;
+        let status = unsafe {
+            aoc2025_solve(
+                day,
+                part,
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr(),
+                &mut len,
+            )
+        };
+        match out.get(..len) {
+            Some(written) => (status, String::from_utf8(written.to_vec()).unwrap()),
+            None => (status, len.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = examples::DAY01.as_bytes();
+        assert_eq!(call(1, 1, input, 64), (AOC2025_OK, "3".to_string()));
+        assert_eq!(call(1, 2, input, 64), (AOC2025_OK, "6".to_string()));
+        let input = examples::DAY02.as_bytes();
+        assert_eq!(
+            call(2, 1, input, 4),
+            (AOC2025_BUFFER_TOO_SMALL, "10".to_string())
+        );
+        assert_eq!(
+            call(2, 1, input, 10),
+            (AOC2025_OK, "1227775554".to_string())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(call(13, 1, b"", 64), (AOC2025_UNKNOWN_DAY, String::new()));
+        assert_eq!(call(1, 3, b"L1", 64), (AOC2025_UNKNOWN_PART, String::new()));
+        assert_eq!(
+            call(1, 1, b"L\xff", 64),
+            (AOC2025_INVALID_UTF8, String::new())
+        );
+        let message = "line 1, column 1: expected a number, found `x`";
+        assert_eq!(
+            call(2, 1, b"x", 64),
+            (AOC2025_INVALID_INPUT, message.to_string())
+        );
+        assert_eq!(
+            call(2, 1, b"x", 4),
+            (AOC2025_INVALID_INPUT, message[..4].to_string())
+        );
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let mut out = [0; 8];
+        let mut len = out.len();
+        let status = unsafe { aoc2025_solve(1, 1, ptr::null(), 0, out.as_mut_ptr(), &mut len) };
+        assert_eq!((status, len), (AOC2025_OK, 1));
+        let mut len = 0;
+        let status = unsafe { aoc2025_solve(1, 1, ptr::null(), 0, ptr::null_mut(), &mut len) };
+        assert_eq!((status, len), (AOC2025_BUFFER_TOO_SMALL, 1));
+        let mut len = out.len();
+        let status = unsafe { aoc2025_solve(1, 1, ptr::null(), 1, out.as_mut_ptr(), &mut len) };
+        assert_eq!((status, len), (AOC2025_NULL_POINTER, 0));
+        let status =
+            unsafe { aoc2025_solve(1, 1, ptr::null(), 0, out.as_mut_ptr(), ptr::null_mut()) };
+        assert_eq!(status, AOC2025_NULL_POINTER);
+    }
+}
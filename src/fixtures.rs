@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The environment variable that points the tests and benchmarks at a directory of puzzle inputs
+/// other than `resources/tests`.
+pub const INPUTS_VAR: &str = "AOC_TEST_INPUTS";
+
+/// Returns the directory of personal puzzle inputs used by the answer tests and benchmarks:
+/// `$AOC_TEST_INPUTS` if it is set, and otherwise `resources/tests` in the crate root, wherever
+/// the tests are run from.
+pub fn inputs_dir() -> PathBuf {
+    inputs_dir_from(std::env::var_os(INPUTS_VAR))
+}
+
+fn inputs_dir_from(var: Option<OsString>) -> PathBuf {
+    match var {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/tests"),
+    }
+}
+
+/// Reads the personal input for `day` from [`inputs_dir`], where it is named by the day's
+/// two-digit number.
+pub fn read_input(day: u8) -> Result<String, MissingInput> {
+    read_input_from(&inputs_dir(), day)
+}
+
+fn read_input_from(dir: &Path, day: u8) -> Result<String, MissingInput> {
+    let path = dir.join(format!("{day:02}"));
+    fs::read_to_string(&path).map_err(|source| MissingInput { path, source })
+}
+
+/// Returns whether [`inputs_dir`] holds an input for any day. Personal inputs cannot be
+/// committed, so tests that need them skip themselves when there are none at all.
+pub fn inputs_available() -> bool {
+    let dir = inputs_dir();
+    (1..=25).any(|day| dir.join(format!("{day:02}")).is_file())
+}
+
+/// A personal puzzle input that could not be read.
+#[derive(Debug)]
+pub struct MissingInput {
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+impl MissingInput {
+    /// Returns whether the input file does not exist, as opposed to existing but being unreadable.
+    /// Tests and benchmarks skip the days whose inputs are absent.
+    pub fn is_absent(&self) -> bool {
+        self.source.kind() == io::ErrorKind::NotFound
+    }
+}
+
+impl fmt::Display for MissingInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot read {}: {} (check out the resources/tests submodule, or set {INPUTS_VAR} to \
+             a directory with inputs named 01, 02, ...)",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl Error for MissingInput {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_inputs_dir() {
+        let default = inputs_dir_from(None);
+        assert!(default.is_absolute());
+        assert!(default.ends_with("resources/tests"));
+        let dir = inputs_dir_from(Some("/somewhere/else".into()));
+        assert_eq!(dir, PathBuf::from("/somewhere/else"));
+    }
+
+    #[test]
+    fn test_read_input() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("07"), "S\n^\n").unwrap();
+        assert_eq!(read_input_from(dir.path(), 7).unwrap(), "S\n^\n");
+
+        let error = read_input_from(dir.path(), 8).unwrap_err();
+        assert!(error.is_absent());
+        assert_eq!(error.path, dir.path().join("08"));
+        let message = error.to_string();
+        assert!(message.starts_with(&format!("cannot read {}", error.path.display())));
+        assert!(message.contains(INPUTS_VAR));
+
+        fs::create_dir(dir.path().join("09")).unwrap();
+        assert!(!read_input_from(dir.path(), 9).unwrap_err().is_absent());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::compress::compress;
+use crate::prefix::Prefix2D;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A point in the plane. Points order by `x`, then `y`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Point2<i64> {
+    /// Returns the Manhattan (taxicab) distance to `other`.
+    pub fn manhattan(&self, other: &Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Returns the squared Euclidean distance to `other`, which must fit in a `u64`.
+    pub fn dist2(&self, other: &Self) -> u64 {
+        let (dx, dy) = (self.x.abs_diff(other.x), self.y.abs_diff(other.y));
+        dx * dx + dy * dy
+    }
+
+    /// Returns the Euclidean distance to `other`.
+    pub fn euclidean(&self, other: &Self) -> f64 {
+        (self.dist2(other) as f64).sqrt()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A point in space. Points order by `x`, then `y`, then `z`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Copy> Point3<T> {
+    /// The coordinate along the given axis (0 = x, 1 = y, 2 = z).
+    pub fn coord(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+}
+
+impl Point3<i64> {
+    /// Returns the Manhattan (taxicab) distance to `other`.
+    pub fn manhattan(&self, other: &Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Returns the squared Euclidean distance to `other`, which must fit in a `u64`.
+    pub fn dist2(&self, other: &Self) -> u64 {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        let dz = self.z.abs_diff(other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Returns the Euclidean distance to `other`.
+    pub fn euclidean(&self, other: &Self) -> f64 {
+        (self.dist2(other) as f64).sqrt()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// The smallest axis-aligned rectangle containing a set of points, given by its corners with the
+/// least and the greatest coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: Point2<i64>,
+    pub max: Point2<i64>,
+}
+
+impl BoundingBox {
+    /// Returns the bounding box of `points`, or `None` if there are none.
+    ///
+    /// Time complexity: O(N)
+    /// Auxiliary space complexity: O(1)
+    pub fn of<'a>(points: impl IntoIterator<Item = &'a Point2<i64>>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, &p| {
+            Some(match bounds {
+                None => Self { min: p, max: p },
+                Some(Self { min, max }) => Self {
+                    min: Point2::new(min.x.min(p.x), min.y.min(p.y)),
+                    max: Point2::new(max.x.max(p.x), max.y.max(p.y)),
+                },
+            })
+        })
+    }
+
+    /// Returns whether `point` lies inside the box or on its boundary.
+    pub fn contains(&self, point: Point2<i64>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+/// Returns the area enclosed by a closed loop of vertices, using the shoelace formula.
+///
+/// The area is measured between the vertices themselves, not in tiles. It is exact for
+/// rectilinear loops; for other lattice polygons it is rounded down.
+///
+/// Time complexity: O(N)
+/// Auxiliary space complexity: O(1)
+pub fn polygon_area(points: &[Point2<i64>]) -> i128 {
+    let n = points.len();
+    let twice_area: i128 = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128
+        })
+        .sum();
+    twice_area.abs() / 2
+}
+
+/// Returns whether `point` lies inside the region enclosed by closed rectilinear loops, or on one
+/// of their sides. With several loops, a point is inside if it is enclosed by an odd number of
+/// them, so later loops can cut holes in earlier ones.
+///
+/// Time complexity: O(N)
+/// Auxiliary space complexity: O(1)
+pub fn contains_point(loops: &[Vec<Point2<i64>>], point: Point2<i64>) -> bool {
+    let Point2 { x: px, y: py } = point;
+    let mut inside = false;
+    for points in loops {
+        let n = points.len();
+        for i in 0..n {
+            let Point2 { x: x1, y: y1 } = points[i];
+            let Point2 { x: x2, y: y2 } = points[(i + 1) % n];
+            let on_side =
+                (x1.min(x2)..=x1.max(x2)).contains(&px) && (y1.min(y2)..=y1.max(y2)).contains(&py);
+            if on_side {
+                return true;
+            }
+            // Cast a ray towards +x, counting the vertical sides it crosses. Each side covers the
+            // half-open span from its lower end, so a ray through a vertex is counted once.
+            if x1 == x2 && x1 > px && (y1.min(y2)..y1.max(y2)).contains(&py) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// The interior of closed rectilinear loops on the grid compressed to the loops' coordinates,
+/// with a 2D prefix sum over the cells that are inside.
+///
+/// Cell `(row, col)` spans `xs[col]..xs[col + 1]` and `ys[row]..ys[row + 1]`. A cell is inside if
+/// it is enclosed by an odd number of loops, as in [`contains_point`].
+pub struct InsideCells {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    x_index: HashMap<i64, usize>,
+    y_index: HashMap<i64, usize>,
+    pref: Prefix2D,
+}
+
+/// Compresses the loops' coordinates and fills their interior with a scanline parity sweep.
+///
+/// Time complexity: O(N^2)
+/// Auxiliary space complexity: O(N^2)
+pub fn inside_cells_prefix_sum(loops: &[Vec<Point2<i64>>]) -> InsideCells {
+    let (xs, x_index) = compress(loops.iter().flatten().map(|p| p.x));
+    let (ys, y_index) = compress(loops.iter().flatten().map(|p| p.y));
+    let v_edges = build_vertical_edges(loops, &x_index);
+    let pref = Prefix2D::from_grid(&build_inside_cells(&xs, &ys, &v_edges));
+    InsideCells {
+        xs,
+        ys,
+        x_index,
+        y_index,
+        pref,
+    }
+}
+
+impl InsideCells {
+    /// The distinct x-coordinates of the loops, in increasing order.
+    pub fn xs(&self) -> &[i64] {
+        &self.xs
+    }
+
+    /// The distinct y-coordinates of the loops, in increasing order.
+    pub fn ys(&self) -> &[i64] {
+        &self.ys
+    }
+
+    /// The index of `x` in [`InsideCells::xs`], if it is one of the loops' coordinates.
+    pub fn x_index(&self, x: i64) -> Option<usize> {
+        self.x_index.get(&x).copied()
+    }
+
+    /// The index of `y` in [`InsideCells::ys`], if it is one of the loops' coordinates.
+    pub fn y_index(&self, y: i64) -> Option<usize> {
+        self.y_index.get(&y).copied()
+    }
+
+    /// Returns the number of inside cells with rows in `y_min..y_max` and columns in
+    /// `x_min..x_max`, in O(1).
+    pub fn count(&self, x_min: usize, x_max: usize, y_min: usize, y_max: usize) -> i128 {
+        self.pref.sum_rect(x_min..x_max, y_min..y_max)
+    }
+
+    /// Returns whether the cell in the given row and column is inside.
+    pub fn is_inside(&self, row: usize, col: usize) -> bool {
+        self.count(col, col + 1, row, row + 1) == 1
+    }
+
+    /// Returns whether every cell between the compressed coordinates `(xi1, yi1)` and
+    /// `(xi2, yi2)` is inside, i.e. whether the rectangle with those corners lies within the
+    /// loops.
+    pub fn contains_rect(&self, (xi1, yi1): (usize, usize), (xi2, yi2): (usize, usize)) -> bool {
+        let (x_min, x_max) = (xi1.min(xi2), xi1.max(xi2));
+        let (y_min, y_max) = (yi1.min(yi2), yi1.max(yi2));
+        let target_cells = ((x_max - x_min) * (y_max - y_min)) as i128;
+        self.count(x_min, x_max, y_min, y_max) == target_cells
+    }
+}
+
+/// Extract vertical edges from closed polygonal chains.
+fn build_vertical_edges(
+    loops: &[Vec<Point2<i64>>],
+    x_index: &HashMap<i64, usize>,
+) -> Vec<Vec<(i64, i64)>> {
+    let mut v_edges: Vec<Vec<(i64, i64)>> = vec![vec![]; x_index.len()];
+    for points in loops {
+        let n = points.len();
+        for i in 0..n {
+            let p1 = points[i];
+            let p2 = points[(i + 1) % n];
+            if p1.x == p2.x
+                && let Some(&xi) = x_index.get(&p1.x)
+            {
+                let y_min = p1.y.min(p2.y);
+                let y_max = p1.y.max(p2.y);
+                v_edges[xi].push((y_min, y_max));
+            }
+        }
+    }
+    v_edges
+}
+
+/// Marks the compressed cells inside the polygon, row by row.
+fn build_inside_cells(xs: &[i64], ys: &[i64], v_edges: &[Vec<(i64, i64)>]) -> Vec<Vec<bool>> {
+    let w = xs.len().saturating_sub(1);
+    ys.windows(2)
+        .map(|span| {
+            let (y_start, y_end) = (span[0], span[1]);
+            let mut inside = false;
+            (0..w)
+                .map(|c| {
+                    for &(ey_min, ey_max) in &v_edges[c] {
+                        if ey_min <= y_start && ey_max >= y_end {
+                            inside = !inside;
+                        }
+                    }
+                    inside
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point2<i64> {
+        Point2::new(x, y)
+    }
+
+    fn example() -> Vec<Point2<i64>> {
+        vec![
+            p(7, 1),
+            p(11, 1),
+            p(11, 7),
+            p(9, 7),
+            p(9, 5),
+            p(2, 5),
+            p(2, 3),
+            p(7, 3),
+        ]
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (p(1, -2), p(4, 2));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.dist2(&b), 25);
+        assert_eq!(a.euclidean(&b), 5.0);
+        let (a, b) = (Point3::new(0, 0, 0), Point3::new(-2, 3, 6));
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.dist2(&b), 49);
+        assert_eq!(b.euclidean(&a), 7.0);
+        assert_eq!((b.coord(0), b.coord(1), b.coord(2)), (-2, 3, 6));
+        assert_eq!(b.to_string(), "(-2, 3, 6)");
+        assert_eq!(a.manhattan(&a), 0);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(BoundingBox::of(&[]), None);
+        let bounds = BoundingBox::of(&example()).unwrap();
+        assert_eq!((bounds.min, bounds.max), (p(2, 1), p(11, 7)));
+        assert!(example().into_iter().all(|q| bounds.contains(q)));
+        assert!(!bounds.contains(p(1, 4)) && !bounds.contains(p(5, 8)));
+    }
+
+    #[test]
+    fn test_polygon_area() {
+        assert_eq!(polygon_area(&example()), 30);
+        assert_eq!(polygon_area(&[p(0, 0), p(4, 0), p(4, 3), p(0, 3)]), 12);
+        // Orientation does not matter.
+        assert_eq!(polygon_area(&[p(0, 0), p(0, 3), p(4, 3), p(4, 0)]), 12);
+        assert_eq!(polygon_area(&[p(0, 0), p(1, 0), p(0, 1)]), 0);
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let loops = vec![example()];
+        assert!(contains_point(&loops, p(8, 2)));
+        assert!(contains_point(&loops, p(3, 4)));
+        assert!(contains_point(&loops, p(2, 4)));
+        assert!(contains_point(&loops, p(7, 1)));
+        assert!(contains_point(&loops, p(10, 7)));
+        assert!(!contains_point(&loops, p(3, 2)));
+        assert!(!contains_point(&loops, p(3, 6)));
+        assert!(!contains_point(&loops, p(12, 4)));
+        // A ray through a vertex or along a side.
+        assert!(!contains_point(&loops, p(1, 5)));
+        assert!(!contains_point(&loops, p(1, 3)));
+    }
+
+    #[test]
+    fn test_contains_point_with_hole() {
+        let loops = vec![
+            vec![p(0, 0), p(10, 0), p(10, 10), p(0, 10)],
+            vec![p(4, 4), p(6, 4), p(6, 6), p(4, 6)],
+        ];
+        assert!(contains_point(&loops, p(2, 2)));
+        assert!(contains_point(&loops, p(4, 5)));
+        assert!(!contains_point(&loops, p(5, 5)));
+    }
+
+    #[test]
+    fn test_inside_cells_prefix_sum() {
+        let cells = inside_cells_prefix_sum(&[example()]);
+        assert_eq!(cells.xs(), &[2, 7, 9, 11]);
+        assert_eq!(cells.ys(), &[1, 3, 5, 7]);
+        assert_eq!((cells.x_index(9), cells.y_index(4)), (Some(2), None));
+        let inside: Vec<Vec<bool>> = (0..3)
+            .map(|row| (0..3).map(|col| cells.is_inside(row, col)).collect())
+            .collect();
+        assert_eq!(
+            inside,
+            vec![
+                vec![false, true, true],
+                vec![true, true, true],
+                vec![false, false, true],
+            ]
+        );
+        assert_eq!(cells.count(0, 3, 0, 3), 6);
+        assert!(cells.contains_rect((0, 1), (2, 2)));
+        assert!(!cells.contains_rect((0, 0), (2, 2)));
+        // A degenerate rectangle along a side covers no cells.
+        assert!(cells.contains_rect((0, 1), (0, 2)));
+    }
+}
@@ -0,0 +1,367 @@
+use crate::render::{Dot, Visualization};
+use std::collections::HashMap;
+
+/// A directed graph whose nodes are named, with the names interned as dense `u32` ids in order of
+/// first appearance.
+#[derive(Default)]
+pub struct Graph {
+    /// The name of each node, by id.
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    /// The successors of each node, by id, sorted and without duplicates.
+    edges: Vec<Vec<u32>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a graph with an edge for each `(from, to)` pair of names.
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in pairs {
+            let (from, to) = (graph.intern(from), graph.intern(to));
+            graph.edges[from as usize].push(to);
+        }
+        for successors in &mut graph.edges {
+            successors.sort_unstable();
+            successors.dedup();
+        }
+        graph
+    }
+
+    /// Returns the id of the named node, adding it if it is new.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    /// Replaces the successors of `node`.
+    pub fn set_successors(&mut self, node: u32, successors: impl IntoIterator<Item = u32>) {
+        let mut successors: Vec<u32> = successors.into_iter().collect();
+        successors.sort_unstable();
+        successors.dedup();
+        self.edges[node as usize] = successors;
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// Returns the successors of `node`, in increasing order of id.
+    pub fn successors(&self, node: u32) -> &[u32] {
+        &self.edges[node as usize]
+    }
+
+    /// Returns every edge as `(from, to)`, in increasing order.
+    pub fn edges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (0..self.len() as u32)
+            .flat_map(move |from| self.successors(from).iter().map(move |&to| (from, to)))
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the nodes in a topological order (Kahn's algorithm), or `None` if the graph has a
+    /// cycle.
+    ///
+    /// Time complexity: O(V + E)
+    /// Auxiliary space complexity: O(V)
+    pub fn topological_order(&self) -> Option<Vec<u32>> {
+        let mut in_degree = vec![0usize; self.len()];
+        for &next in self.edges.iter().flatten() {
+            in_degree[next as usize] += 1;
+        }
+        let mut ready: Vec<u32> = (0..self.len() as u32)
+            .filter(|&node| in_degree[node as usize] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = ready.pop() {
+            order.push(node);
+            for &next in self.successors(node) {
+                in_degree[next as usize] -= 1;
+                if in_degree[next as usize] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Returns, for each node, whether it can be reached from `start`.
+    ///
+    /// Time complexity: O(V + E)
+    /// Auxiliary space complexity: O(V)
+    pub fn reachable_from(&self, start: u32) -> Vec<bool> {
+        search(start, self.len(), |node| self.successors(node))
+    }
+
+    /// Returns, for each node, whether `end` can be reached from it.
+    ///
+    /// Time complexity: O(V + E)
+    /// Auxiliary space complexity: O(V + E)
+    pub fn reaching(&self, end: u32) -> Vec<bool> {
+        let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); self.len()];
+        for (from, to) in self.edges() {
+            predecessors[to as usize].push(from);
+        }
+        search(end, self.len(), |node| &predecessors[node as usize])
+    }
+
+    /// Counts the paths from `start` to `end` in a directed acyclic graph, or returns `None` if
+    /// the count overflows a `usize`.
+    ///
+    /// The count is a forward pass over the nodes reachable from `start` in topological order, so
+    /// deep graphs need no recursion. Nodes that lie on a cycle are never ready to be passed, so
+    /// paths through them are not counted.
+    ///
+    /// Time complexity: O(V + E)
+    /// Auxiliary space complexity: O(V)
+    pub fn count_paths(&self, start: u32, end: u32) -> Option<usize> {
+        // The number of edges entering each node from nodes reachable from `start`.
+        let reachable = self.reachable_from(start);
+        let mut in_degree = vec![0usize; self.len()];
+        for (from, to) in self.edges() {
+            if reachable[from as usize] {
+                in_degree[to as usize] += 1;
+            }
+        }
+        let mut counts: Vec<Option<usize>> = vec![Some(0); self.len()];
+        counts[start as usize] = Some(1);
+        let mut ready = vec![start];
+        while let Some(node) = ready.pop() {
+            // Paths end at `end`, so nothing is counted beyond it.
+            if node == end {
+                continue;
+            }
+            let count = counts[node as usize];
+            for &next in self.successors(node) {
+                let total = &mut counts[next as usize];
+                *total = total.zip(count).and_then(|(a, b)| a.checked_add(b));
+                in_degree[next as usize] -= 1;
+                if in_degree[next as usize] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        counts[end as usize]
+    }
+
+    /// Renders the graph as a directed GraphViz graph with the given graph attributes.
+    ///
+    /// Edges are listed in increasing order of their ids, with the attributes `edge_attrs`
+    /// returns for them. A node is declared, with the attributes `node_attrs` returns for it,
+    /// if it has any attributes or no edges at all.
+    pub fn to_dot(
+        &self,
+        attrs: &[(&str, &str)],
+        node_attrs: impl Fn(u32) -> Vec<(&'static str, String)>,
+        edge_attrs: impl Fn(u32, u32) -> Vec<(&'static str, String)>,
+    ) -> Visualization {
+        let mut has_edges = vec![false; self.len()];
+        for (from, to) in self.edges() {
+            has_edges[from as usize] = true;
+            has_edges[to as usize] = true;
+        }
+        let mut dot = Dot::directed(attrs);
+        for node in 0..self.len() as u32 {
+            let attrs = node_attrs(node);
+            if !attrs.is_empty() || !has_edges[node as usize] {
+                dot.node(self.name(node), &borrow_attrs(&attrs));
+            }
+        }
+        for (from, to) in self.edges() {
+            let attrs = edge_attrs(from, to);
+            dot.edge(self.name(from), self.name(to), &borrow_attrs(&attrs));
+        }
+        dot.finish()
+    }
+}
+
+fn borrow_attrs<'a>(attrs: &'a [(&'static str, String)]) -> Vec<(&'static str, &'a str)> {
+    attrs.iter().map(|(k, v)| (*k, v.as_str())).collect()
+}
+
+/// Returns which of `n` nodes can be reached from `start` by following `next`.
+fn search<'a>(start: u32, n: usize, next: impl Fn(u32) -> &'a [u32]) -> Vec<bool> {
+    let mut seen = vec![false; n];
+    seen[start as usize] = true;
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for &other in next(node) {
+            if !seen[other as usize] {
+                seen[other as usize] = true;
+                stack.push(other);
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn diamond() -> Graph {
+        Graph::from_pairs([("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("a", "b")])
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        let graph = diamond();
+        assert_eq!(graph.len(), 4);
+        let (a, d) = (graph.id("a").unwrap(), graph.id("d").unwrap());
+        assert_eq!(graph.name(a), "a");
+        assert_eq!(graph.successors(a).len(), 2);
+        assert!(graph.successors(d).is_empty());
+        assert_eq!(graph.edges().count(), 4);
+        assert_eq!(graph.id("e"), None);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = diamond();
+        let order = graph.topological_order().unwrap();
+        let mut position = vec![0; graph.len()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node as usize] = i;
+        }
+        assert!(
+            graph
+                .edges()
+                .all(|(from, to)| position[from as usize] < position[to as usize])
+        );
+        let cyclic = Graph::from_pairs([("a", "b"), ("b", "c"), ("c", "b")]);
+        assert_eq!(cyclic.topological_order(), None);
+    }
+
+    #[test]
+    fn test_reachability() {
+        let graph = Graph::from_pairs([("a", "b"), ("b", "c"), ("d", "c")]);
+        let id = |name| graph.id(name).unwrap();
+        assert_eq!(
+            graph.reachable_from(id("b")),
+            vec![false, true, true, false]
+        );
+        assert_eq!(graph.reaching(id("b")), vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = diamond();
+        let id = |name| graph.id(name).unwrap();
+        assert_eq!(graph.count_paths(id("a"), id("d")), Some(2));
+        assert_eq!(graph.count_paths(id("b"), id("d")), Some(1));
+        assert_eq!(graph.count_paths(id("d"), id("a")), Some(0));
+        assert_eq!(graph.count_paths(id("a"), id("a")), Some(1));
+    }
+
+    #[test]
+    fn test_count_paths_overflow() {
+        // A ladder of 70 diamonds has 2^70 paths.
+        let names: Vec<String> = (0..=70).map(|i| format!("n{i}")).collect();
+        let sides: Vec<[String; 2]> = (0..70)
+            .map(|i| [format!("l{i}"), format!("r{i}")])
+            .collect();
+        let mut pairs = Vec::new();
+        for i in 0..70 {
+            for side in &sides[i] {
+                pairs.push((names[i].as_str(), side.as_str()));
+                pairs.push((side.as_str(), names[i + 1].as_str()));
+            }
+        }
+        let graph = Graph::from_pairs(pairs);
+        let id = |name: &str| graph.id(name).unwrap();
+        assert_eq!(graph.count_paths(id("n0"), id("n60")), Some(1 << 60));
+        assert_eq!(graph.count_paths(id("n0"), id("n70")), None);
+    }
+
+    /// Compares path counts in random DAGs against a depth-first enumeration.
+    #[test]
+    fn test_count_paths_random() {
+        fn enumerate(graph: &Graph, node: u32, end: u32) -> usize {
+            if node == end {
+                return 1;
+            }
+            graph
+                .successors(node)
+                .iter()
+                .map(|&next| enumerate(graph, next, end))
+                .sum()
+        }
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let n = rng.random_range(1..12u32);
+            let names: Vec<String> = (0..n).map(|i| i.to_string()).collect();
+            let mut pairs = Vec::new();
+            for a in 0..n as usize {
+                for b in a + 1..n as usize {
+                    if rng.random_range(0..3) == 0 {
+                        pairs.push((names[a].as_str(), names[b].as_str()));
+                    }
+                }
+            }
+            let mut graph = Graph::from_pairs(pairs);
+            for name in &names {
+                graph.intern(name);
+            }
+            for start in 0..n {
+                for end in 0..n {
+                    let (s, e) = (graph.id(&start.to_string()), graph.id(&end.to_string()));
+                    let (s, e) = (s.unwrap(), e.unwrap());
+                    assert_eq!(graph.count_paths(s, e), Some(enumerate(&graph, s, e)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut graph = diamond();
+        graph.intern("lonely");
+        let a = graph.id("a").unwrap();
+        let vis = graph.to_dot(
+            &[("rankdir", "LR")],
+            |node| {
+                if node == a {
+                    vec![("shape", "box".to_string())]
+                } else {
+                    Vec::new()
+                }
+            },
+            |from, _| {
+                if from == a {
+                    vec![("color", "red".to_string())]
+                } else {
+                    Vec::new()
+                }
+            },
+        );
+        let dot = vis.contents();
+        assert!(dot.starts_with("digraph {\n  rankdir=\"LR\";\n"));
+        assert!(dot.contains("  \"a\" [shape=\"box\"];\n"));
+        assert!(dot.contains("  \"lonely\";\n"));
+        assert!(!dot.contains("  \"b\";\n"));
+        assert!(dot.contains("  \"a\" -> \"b\" [color=\"red\"];\n"));
+        assert!(dot.contains("  \"b\" -> \"d\";\n"));
+        assert_eq!(dot.matches(" -> ").count(), 4);
+    }
+}
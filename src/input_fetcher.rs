@@ -1,3 +1,4 @@
+use crate::runner::InputSource;
 use reqwest::StatusCode;
 use reqwest::blocking::Client;
 use std::error::Error;
@@ -48,8 +49,10 @@ impl InputFetcher {
         })
     }
 
+    /// Reads the session token, without the line break that editors tend to leave after it.
     fn get_session_token(&self) -> Result<String, Box<dyn Error>> {
-        fs::read_to_string(&self.session_token_path).map_err(|e| e.into())
+        let token = fs::read_to_string(&self.session_token_path)?;
+        Ok(token.trim().to_string())
     }
 
     fn fetch_input(&self, day: u8, session_token: &str) -> Result<String, Box<dyn Error>> {
@@ -61,11 +64,25 @@ impl InputFetcher {
             .send()?;
         match response.status() {
             StatusCode::OK => Ok(response.text()?),
+            // Advent of Code answers a session token it does not recognize with a 400 and a
+            // request to log in.
+            StatusCode::BAD_REQUEST => Err(format!(
+                "Failed to fetch input: the session token in {} was rejected; log in to Advent of \
+                 Code and copy the new session cookie into it",
+                self.session_token_path.display()
+            )
+            .into()),
             status => Err(format!("Failed to fetch input: {status}").into()),
         }
     }
 }
 
+impl InputSource for InputFetcher {
+    fn get_input(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        InputFetcher::get_input(self, day)
+    }
+}
+
 fn url_path(day: u8) -> String {
     format!("/2025/day/{day}/input")
 }
@@ -75,6 +92,8 @@ mod tests {
     use crate::input_fetcher::{InputFetcher, url_path};
     use httpmock::Mock;
     use httpmock::prelude::*;
+    use rand::Rng;
+    use rand::rngs::StdRng;
     use std::path::Path;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -101,13 +120,62 @@ mod tests {
             assert_eq!(input, context.get_input(day));
             mock.assert();
 
-            // Verify that local files were created.
+            // Verify that local files were created, and are used from then on.
             let input_file_path = context.input_dir.path().join(format!("{:02}", day));
             let input = std::fs::read_to_string(input_file_path).unwrap();
             assert_eq!(input, context.get_input(day));
+            assert_eq!(fetcher.get_input(day).unwrap(), context.get_input(day));
+            mock.assert_calls(1);
         }
     }
 
+    #[test]
+    fn failed_fetch_is_not_cached() {
+        let context = TestContext::create();
+        let fetcher = context.get_fetcher();
+        context.delete_puzzle_input_file(1);
+        let mut mock = context.server_down_mock(1);
+        assert!(fetcher.get_input(1).is_err());
+        assert!(!context.input_dir.path().join("01").exists());
+        mock.delete();
+
+        let mock = context.server_up_mock(1);
+        assert_eq!(fetcher.get_input(1).unwrap(), context.get_input(1));
+        mock.assert();
+    }
+
+    #[test]
+    fn remote_fetch_creates_input_directory() {
+        let context = TestContext::create();
+        let input_dir = context.input_dir.path().join("nested").join("puzzle");
+        let fetcher = InputFetcher::create_custom(
+            context.server.base_url().as_str(),
+            &input_dir,
+            context.session_token_file.path(),
+        );
+        let mock = context.server_up_mock(3);
+        assert_eq!(fetcher.get_input(3).unwrap(), context.get_input(3));
+        mock.assert();
+        let input = std::fs::read_to_string(input_dir.join("03")).unwrap();
+        assert_eq!(input, context.get_input(3));
+    }
+
+    #[test]
+    fn session_token_is_trimmed() {
+        let context = TestContext::create();
+        let token_file = NamedTempFile::new().unwrap();
+        std::fs::write(token_file.path(), format!("{}\n", context.session_token)).unwrap();
+        let fetcher = InputFetcher::create_custom(
+            context.server.base_url().as_str(),
+            context.input_dir.path(),
+            token_file.path(),
+        );
+        context.delete_puzzle_input_file(1);
+        let mock = context.server_up_mock(1);
+        assert_eq!(fetcher.get_input(1).unwrap(), context.get_input(1));
+        mock.assert();
+    }
+
     #[test]
     fn fetch_fails_when_all_sources_unavailable() {
         let context = TestContext::create();
@@ -142,8 +210,8 @@ mod tests {
 
     #[test]
     fn fetch_fails_if_bad_session_token() {
-        let context = TestContext::create();
-        let bad_cookie = random_session_token();
+        let mut context = TestContext::create();
+        let bad_cookie = random_session_token(&mut context.rng);
         assert_ne!(context.session_token, bad_cookie);
         let bad_cookie_file = NamedTempFile::new().unwrap();
         std::fs::write(bad_cookie_file.path(), bad_cookie.as_bytes()).unwrap();
@@ -162,8 +230,9 @@ mod tests {
                 then.status(400)
                     .body("Puzzle inputs differ by user.  Please log in to get your puzzle input.");
             });
-            let result = fetcher.get_input(day);
-            assert!(result.is_err());
+            let message = fetcher.get_input(day).unwrap_err().to_string();
+            assert!(message.contains("session token"), "{message}");
+            assert!(message.contains(&bad_cookie_file.path().display().to_string()));
             mock_with_correct_token.assert_calls(0);
             mock_with_invalid_token.assert();
         }
@@ -184,8 +253,8 @@ mod tests {
                            The calendar countdown is synchronized with the server time; \
                            the link will be enabled on the calendar the instant this puzzle becomes available.");
             });
-            let result = fetcher.get_input(day);
-            assert!(result.is_err());
+            let message = fetcher.get_input(day).unwrap_err().to_string();
+            assert!(message.contains("404"), "{message}");
             mock.assert();
         }
     }
@@ -196,17 +265,20 @@ mod tests {
         session_token: String,
         session_token_file: NamedTempFile,
         server: MockServer,
+        /// Draws the context's random inputs and tokens, and any more a test needs.
+        rng: StdRng,
     }
 
     impl TestContext {
         pub fn create() -> Self {
-            let inputs: Vec<String> = (1..=25).map(|_| random_puzzle()).collect();
+            let mut rng = crate::seed::rng();
+            let inputs: Vec<String> = (1..=25).map(|_| random_puzzle(&mut rng)).collect();
             let input_dir = TempDir::new().unwrap();
             for day in 1..=25 {
                 let input_file_path = input_dir.path().join(format!("{:02}", day));
                 std::fs::write(&input_file_path, &inputs[day - 1]).unwrap();
             }
-            let session_token = random_session_token();
+            let session_token = random_session_token(&mut rng);
             let session_token_file = NamedTempFile::new().unwrap();
             std::fs::write(session_token_file.path(), session_token.as_bytes()).unwrap();
             let server = MockServer::start();
@@ -216,6 +288,7 @@ mod tests {
                 session_token,
                 session_token_file,
                 server,
+                rng,
             }
         }
 
@@ -255,7 +328,7 @@ mod tests {
         }
     }
 
-    fn random_puzzle() -> String {
+    fn random_puzzle(rng: &mut impl Rng) -> String {
         // Puzzle inputs tend to contain a wide variety of ASCII characters including line feed.
         // They can also be fairly large.
         let charset = format!(
@@ -269,17 +342,15 @@ mod tests {
             "abcdefghijklmnopqrstuvwxyz", // ASCII codes 97-122 (lowercase letters)
             "{|}~"                        // ASCII codes 123-126 (symbols)
         );
-        random_string(charset.as_str(), 65535)
+        random_string(rng, charset.as_str(), 65535)
     }
 
-    fn random_session_token() -> String {
+    fn random_session_token(rng: &mut impl Rng) -> String {
         // Session tokens appear to be 128 characters of ASCII hex digits
-        random_string("0123456789abcdef", 128)
+        random_string(rng, "0123456789abcdef", 128)
     }
 
-    fn random_string(charset: &str, length: usize) -> String {
-        use rand::Rng;
-        let mut rng = rand::rng();
+    fn random_string(rng: &mut impl Rng, charset: &str, length: usize) -> String {
         (0..length)
             .map(|_| {
                 let index = rng.random_range(0..charset.len());
@@ -0,0 +1,223 @@
+use std::ops::RangeInclusive;
+
+/// A set of `u64`s stored as the sorted, disjoint ranges covering it. Ranges that overlap or touch
+/// are merged, so each range is separated from the next by at least one missing value.
+///
+/// ```
+/// use aoc2025::intervals::IntervalSet;
+///
+/// let set = IntervalSet::merge([10..=14, 3..=5, 12..=18, 6..=6]);
+/// assert_eq!(set.ranges(), &[3..=6, 10..=18]);
+/// assert_eq!(set.len(), 13);
+/// assert!(set.contains(17) && !set.contains(8));
+/// assert_eq!(set.nth(4), Some(10));
+/// assert_eq!(set.complement(0..=20).ranges(), &[0..=2, 7..=9, 19..=20]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<u64>>,
+    /// The number of values in the ranges before each range.
+    before: Vec<u128>,
+}
+
+impl IntervalSet {
+    /// Builds the union of `ranges`. Empty ranges (with `start > end`) are ignored.
+    ///
+    /// Time complexity: O(N log N)
+    /// Auxiliary space complexity: O(N)
+    pub fn merge(ranges: impl IntoIterator<Item = RangeInclusive<u64>>) -> Self {
+        let mut sorted: Vec<RangeInclusive<u64>> =
+            ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_unstable_by_key(|r| *r.start());
+        let mut merged: Vec<RangeInclusive<u64>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => merged.push(range),
+            }
+        }
+        Self::from_disjoint(merged)
+    }
+
+    /// Wraps ranges that are already sorted, disjoint and separated by gaps.
+    fn from_disjoint(ranges: Vec<RangeInclusive<u64>>) -> Self {
+        let before = ranges
+            .iter()
+            .scan(0u128, |total, r| {
+                let before = *total;
+                *total += range_len(r);
+                Some(before)
+            })
+            .collect();
+        Self { ranges, before }
+    }
+
+    /// The disjoint ranges, in increasing order.
+    pub fn ranges(&self) -> &[RangeInclusive<u64>] {
+        &self.ranges
+    }
+
+    /// Returns the number of values in the set, which is up to 2^64.
+    ///
+    /// Time complexity: O(1)
+    /// Auxiliary space complexity: O(1)
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .last()
+            .map_or(0, |r| self.before[self.ranges.len() - 1] + range_len(r))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns whether `value` is in the set.
+    ///
+    /// Time complexity: O(log N)
+    /// Auxiliary space complexity: O(1)
+    pub fn contains(&self, value: u64) -> bool {
+        let i = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges.get(i).is_some_and(|r| r.contains(&value))
+    }
+
+    /// Returns the values of `within` that are not in the set.
+    ///
+    /// Time complexity: O(N)
+    /// Auxiliary space complexity: O(N)
+    pub fn complement(&self, within: RangeInclusive<u64>) -> Self {
+        let (lo, hi) = (*within.start(), *within.end());
+        let mut gaps = Vec::new();
+        // The least value from `lo` on that is neither in a range seen so far nor in a gap, or
+        // `None` past `u64::MAX`.
+        let mut next = Some(lo);
+        for r in &self.ranges {
+            let Some(from) = next.filter(|&from| from <= hi) else {
+                break;
+            };
+            if *r.start() > from {
+                gaps.push(from..=(*r.start() - 1).min(hi));
+            }
+            if *r.end() >= from {
+                next = r.end().checked_add(1);
+            }
+        }
+        if let Some(from) = next.filter(|&from| from <= hi) {
+            gaps.push(from..=hi);
+        }
+        Self::from_disjoint(gaps)
+    }
+
+    /// Returns the `k`-th smallest value in the set, counting from 0.
+    ///
+    /// Time complexity: O(log N)
+    /// Auxiliary space complexity: O(1)
+    pub fn nth(&self, k: u128) -> Option<u64> {
+        let i = self
+            .before
+            .partition_point(|&before| before <= k)
+            .checked_sub(1)?;
+        let offset = k - self.before[i];
+        let range = &self.ranges[i];
+        (offset < range_len(range)).then(|| *range.start() + offset as u64)
+    }
+}
+
+/// The number of values in a non-empty range.
+fn range_len(range: &RangeInclusive<u64>) -> u128 {
+    (*range.end() - *range.start()) as u128 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_merge() {
+        let set = IntervalSet::merge([
+            16..=20,
+            3..=5,
+            12..=18,
+            10..=14,
+            6..=6,
+            RangeInclusive::new(9, 1),
+        ]);
+        assert_eq!(set.ranges(), &[3..=6, 10..=20]);
+        assert_eq!(set.len(), 15);
+        assert!(IntervalSet::merge([]).is_empty());
+        let full = IntervalSet::merge([0..=u64::MAX, 5..=7]);
+        assert_eq!(full.len(), 1 << 64);
+    }
+
+    #[test]
+    fn test_contains_and_nth() {
+        let set = IntervalSet::merge([3..=5, 10..=14]);
+        let members: Vec<u64> = (0..20).filter(|&v| set.contains(v)).collect();
+        assert_eq!(members, vec![3, 4, 5, 10, 11, 12, 13, 14]);
+        let nth: Vec<Option<u64>> = (0..9).map(|k| set.nth(k)).collect();
+        assert_eq!(
+            nth,
+            vec![
+                Some(3),
+                Some(4),
+                Some(5),
+                Some(10),
+                Some(11),
+                Some(12),
+                Some(13),
+                Some(14),
+                None
+            ]
+        );
+        assert_eq!(IntervalSet::default().nth(0), None);
+    }
+
+    #[test]
+    fn test_complement() {
+        let set = IntervalSet::merge([3..=5, 10..=14]);
+        assert_eq!(set.complement(0..=20).ranges(), &[0..=2, 6..=9, 15..=20]);
+        assert_eq!(set.complement(4..=12).ranges(), &[6..=9]);
+        assert_eq!(set.complement(11..=13).ranges(), &[]);
+        let full = IntervalSet::merge([0..=u64::MAX]);
+        assert!(full.complement(0..=u64::MAX).is_empty());
+        let edge = IntervalSet::merge([0..=0, u64::MAX..=u64::MAX]);
+        assert_eq!(edge.complement(0..=u64::MAX).ranges(), &[1..=u64::MAX - 1]);
+    }
+
+    /// Compares every operation against a set of booleans over a small universe.
+    #[test]
+    fn test_matches_bools() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..500 {
+            let ranges: Vec<RangeInclusive<u64>> = (0..rng.random_range(0..8))
+                .map(|_| rng.random_range(0..40u64)..=rng.random_range(0..40u64))
+                .collect();
+            let set = IntervalSet::merge(ranges.clone());
+            let member = |v: u64| ranges.iter().any(|r| r.contains(&v));
+            let members: Vec<u64> = (0..40).filter(|&v| member(v)).collect();
+            assert_eq!(set.len(), members.len() as u128, "{ranges:?}");
+            for v in 0..40 {
+                assert_eq!(set.contains(v), member(v), "{ranges:?} {v}");
+            }
+            for (k, &v) in members.iter().enumerate() {
+                assert_eq!(set.nth(k as u128), Some(v));
+            }
+            assert_eq!(set.nth(members.len() as u128), None);
+            for pair in set.ranges().windows(2) {
+                assert!(*pair[0].end() + 1 < *pair[1].start(), "{ranges:?}");
+            }
+            let (lo, hi) = (rng.random_range(0..40u64), rng.random_range(0..40u64));
+            let complement = set.complement(lo..=hi);
+            for v in 0..40 {
+                let expected = (lo..=hi).contains(&v) && !member(v);
+                assert_eq!(
+                    complement.contains(v),
+                    expected,
+                    "{ranges:?} {lo}..={hi} {v}"
+                );
+            }
+        }
+    }
+}
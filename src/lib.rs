@@ -1,3 +1,10 @@
+pub mod answers;
+pub mod bench_check;
+pub mod bitset;
+#[cfg(feature = "cargo-aoc")]
+pub mod cargo_aoc;
+pub mod combinatorics;
+pub mod compress;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -10,5 +17,43 @@ pub mod day09;
 pub mod day10;
 pub mod day11;
 pub mod day12;
+pub mod dir;
+pub mod dsu;
+#[cfg(feature = "evcxr")]
+pub mod evcxr;
+pub mod examples;
+pub mod fastparse;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixtures;
+pub mod geom;
+pub mod graph;
+#[cfg(feature = "fetch")]
 pub mod input_fetcher;
+pub mod intervals;
+pub mod math;
+pub mod memo;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod parse;
+pub mod polyomino;
+pub mod pq;
+pub mod prefix;
 pub mod puzzle;
+pub mod registry;
+pub mod render;
+pub mod report;
+pub mod run_log;
+pub mod runner;
+pub mod search;
+pub mod seed;
+pub mod table;
+pub mod testgen;
+pub mod threads;
+#[cfg(feature = "viz-gui")]
+pub mod viz_gui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use registry::{Part, SolveError, solve};
+pub use threads::configure_threads;
@@ -1,25 +1,219 @@
+use aoc2025::answers::{self, AnswerFile};
+use aoc2025::bench_check;
+use aoc2025::examples::Examples;
 use aoc2025::input_fetcher::InputFetcher;
-use aoc2025::puzzle::Puzzle;
-use aoc2025::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12};
+#[cfg(feature = "otel")]
+use aoc2025::otel;
+use aoc2025::registry::{self, DayEntry};
+use aoc2025::render::VizFormat;
+use aoc2025::report::{self, Report};
+use aoc2025::run_log::{self, RunLog};
+use aoc2025::runner::{self, Format, InputSource};
+use aoc2025::threads;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-fn main() {
-    let fetcher = InputFetcher::create();
-    let puzzles: Vec<Box<dyn Puzzle>> = vec![
-        day01::Day::create(fetcher.get_input(1).unwrap().as_str()),
-        day02::Day::create(fetcher.get_input(2).unwrap().as_str()),
-        day03::Day::create(fetcher.get_input(3).unwrap().as_str()),
-        day04::Day::create(fetcher.get_input(4).unwrap().as_str()),
-        day05::Day::create(fetcher.get_input(5).unwrap().as_str()),
-        day06::Day::create(fetcher.get_input(6).unwrap().as_str()),
-        day07::Day::create(fetcher.get_input(7).unwrap().as_str()),
-        day08::Day::create(fetcher.get_input(8).unwrap().as_str()),
-        day09::Day::create(fetcher.get_input(9).unwrap().as_str()),
-        day10::Day::create(fetcher.get_input(10).unwrap().as_str()),
-        day11::Day::create(fetcher.get_input(11).unwrap().as_str()),
-        day12::Day::create(fetcher.get_input(12).unwrap().as_str()),
-    ];
-    for (i, puzzle) in puzzles.iter().enumerate() {
-        println!("Day {:02} Part 1: {}", i + 1, puzzle.solve_part_1());
-        println!("Day {:02} Part 2: {}", i + 1, puzzle.solve_part_2());
+/// What the command line asks for, apart from where the inputs come from.
+struct Options {
+    visualize_dir: Option<PathBuf>,
+    viz_format: VizFormat,
+    format: Format,
+    log: RunLog,
+    /// Check the answers against the answers file.
+    verify: bool,
+    /// Only run the days that the answers file has no complete answers for.
+    only_unsolved: bool,
+    /// Save the answers to the answers file.
+    record: bool,
+    /// Where to export the steps of the run as OpenTelemetry spans.
+    #[cfg(feature = "otel")]
+    otlp_endpoint: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let threads = args
+        .iter()
+        .position(|arg| arg == "--threads")
+        .map(|i| {
+            let threads = args.get(i + 1).expect("--threads requires a number");
+            threads
+                .parse::<usize>()
+                .unwrap_or_else(|_| panic!("--threads expects a number, found `{threads}`"))
+        })
+        .or_else(threads::from_env);
+    if let Some(threads) = threads
+        && let Err(e) = aoc2025::configure_threads(threads)
+    {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    if args.get(1).is_some_and(|arg| arg == "readme") {
+        return readme(&args[2..]);
+    }
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let visualize_dir = args
+        .iter()
+        .position(|arg| arg == "--visualize")
+        .map(|i| PathBuf::from(args.get(i + 1).expect("--visualize requires a directory")));
+    let viz_format = args
+        .iter()
+        .position(|arg| arg == "--viz-format")
+        .map(|i| {
+            let format = args
+                .get(i + 1)
+                .expect("--viz-format requires a format such as native or png");
+            format
+                .parse::<VizFormat>()
+                .unwrap_or_else(|e| panic!("{e}"))
+        })
+        .unwrap_or_default();
+    let format = args
+        .iter()
+        .position(|arg| arg == "--format")
+        .map(|i| {
+            let format = args
+                .get(i + 1)
+                .expect("--format requires a format such as text or json");
+            format.parse::<Format>().unwrap_or_else(|e| panic!("{e}"))
+        })
+        .unwrap_or(Format::Text);
+    let log = match args.iter().position(|arg| arg == "--log-file") {
+        Some(i) => {
+            let path = PathBuf::from(args.get(i + 1).expect("--log-file requires a file"));
+            RunLog::append(&path).unwrap_or_else(|e| panic!("cannot open {}: {e}", path.display()))
+        }
+        None => RunLog::in_memory(),
+    };
+    let options = Options {
+        visualize_dir,
+        viz_format,
+        format,
+        log,
+        verify: flag("--verify"),
+        only_unsolved: flag("--only-unsolved"),
+        record: flag("--record"),
+        #[cfg(feature = "otel")]
+        otlp_endpoint: args
+            .iter()
+            .position(|arg| arg == "--otlp-endpoint")
+            .map(|i| {
+                args.get(i + 1)
+                    .expect("--otlp-endpoint requires a URL")
+                    .clone()
+            })
+            .or_else(otel::endpoint_from_env),
+    };
+    if flag("--example") {
+        solve(&Examples, options)
+    } else {
+        solve(&InputFetcher::create(), options)
+    }
+}
+
+/// Solves the days `options` asks for on their inputs from `source` and prints the answers,
+/// failing if any of them is checked and found wrong.
+fn solve(source: &impl InputSource, mut options: Options) -> ExitCode {
+    let answers_path = answers::default_path();
+    let mut answers = if options.verify || options.only_unsolved || options.record {
+        AnswerFile::load(&answers_path).unwrap_or_else(|e| panic!("{e}"))
+    } else {
+        AnswerFile::default()
+    };
+    let days: Vec<DayEntry> = registry::DAYS
+        .iter()
+        .filter(|day| !options.only_unsolved || !answers.is_solved(day.number))
+        .copied()
+        .collect();
+    let visualize_dir = options.visualize_dir.as_deref();
+    let results = runner::run_logged(
+        &days,
+        source,
+        visualize_dir,
+        options.viz_format,
+        &mut options.log,
+    )
+    .unwrap();
+    io::stdout()
+        .write_all(&runner::encode(&results, options.format))
+        .unwrap();
+    let failed = options.verify && {
+        let failures = runner::verify(&results, &answers, source, &mut options.log).unwrap();
+        for failure in &failures {
+            eprintln!("{failure}");
+        }
+        !failures.is_empty()
+    };
+    #[cfg(feature = "otel")]
+    if let Some(endpoint) = &options.otlp_endpoint
+        && let Err(e) = otel::export(options.log.events(), endpoint)
+    {
+        eprintln!("cannot export the spans to {endpoint}: {e}");
+    }
+    if failed {
+        // Wrong answers are never recorded over the known ones.
+        return ExitCode::FAILURE;
+    }
+    if options.record {
+        for result in &results {
+            let input = source.get_input(result.day).unwrap();
+            answers.record(result, &run_log::input_hash(&input));
+        }
+        answers
+            .save(&answers_path)
+            .unwrap_or_else(|e| panic!("cannot save {}: {e}", answers_path.display()));
+    }
+    ExitCode::SUCCESS
+}
+
+/// Rewrites the results section of the README with a table of each day's answers and run time,
+/// from a fresh run on the real inputs or, with `--from-log FILE`, from the latest run in a log
+/// written with `--log-file`, alongside the medians of the latest `cargo bench` run.
+fn readme(args: &[String]) -> ExitCode {
+    let value = |name: &str| {
+        args.iter().position(|arg| arg == name).map(|i| {
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("{name} requires a file"))
+        })
+    };
+    let events = match value("--from-log") {
+        Some(path) => run_log::read(Path::new(path)).unwrap_or_else(|e| panic!("{e}")),
+        None => {
+            let mut log = RunLog::in_memory();
+            runner::run_logged(
+                &registry::DAYS,
+                &InputFetcher::create(),
+                None,
+                VizFormat::Native,
+                &mut log,
+            )
+            .unwrap();
+            log.events().to_vec()
+        }
+    };
+    let target = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let medians = bench_check::latest_medians(&Path::new(&target).join("criterion"))
+        .unwrap_or_else(|e| panic!("cannot read the benchmark results: {e}"));
+    let table = Report {
+        days: report::from_events(&events),
+        medians,
+    }
+    .to_string();
+    let path = PathBuf::from(value("--readme").map_or("README.md", String::as_str));
+    let document =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+    match report::splice(&document, &table) {
+        Ok(updated) => {
+            fs::write(&path, updated)
+                .unwrap_or_else(|e| panic!("cannot write {}: {e}", path.display()));
+            println!("Updated {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", path.display());
+            ExitCode::FAILURE
+        }
     }
 }
@@ -0,0 +1,253 @@
+use divisors_fixed::Divisors;
+use num::Integer;
+
+pub mod matrix;
+
+/// Returns the number of decimal digits of `n`, counting 0 as one digit.
+pub fn num_digits(n: u64) -> u32 {
+    if n == 0 { 1 } else { n.ilog10() + 1 }
+}
+
+/// Returns 10^`exp`, which must fit in a `u128` (so `exp` is at most 38).
+pub fn pow10(exp: u32) -> u128 {
+    10u128.pow(exp)
+}
+
+/// Returns ⌈`a` / `b`⌉.
+pub fn ceil_div<T: Integer>(a: T, b: T) -> T {
+    Integer::div_ceil(&a, &b)
+}
+
+/// Returns ⌊`a` / `b`⌋.
+pub fn floor_div<T: Integer>(a: T, b: T) -> T {
+    Integer::div_floor(&a, &b)
+}
+
+/// Returns the divisors of `n` in increasing order.
+pub fn divisors(n: u32) -> Vec<u32> {
+    n.divisors()
+}
+
+/// Möbius function μ(n) for n ≥ 0.
+///
+/// μ(0) = 0 (by convention here)
+/// μ(1) = 1
+/// μ(n) = 0 if n has a squared prime factor
+/// μ(n) = (-1)^k if n is a product of k distinct primes
+pub fn mobius(mut n: u32) -> i32 {
+    if n == 0 {
+        return 0;
+    }
+    if n == 1 {
+        return 1;
+    }
+    let mut mu: i32 = 1;
+    let mut p: u32 = 2;
+    // Trial division up to sqrt(n)
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            let mut count = 0;
+            while n.is_multiple_of(p) {
+                n /= p;
+                count += 1;
+                if count > 1 {
+                    // Squared prime factor ⇒ μ(n) = 0
+                    return 0;
+                }
+            }
+            // Flip sign for each distinct prime factor.
+            mu = -mu;
+        }
+        p += if p == 2 { 1 } else { 2 }; // Check 2, then odd numbers only.
+    }
+    // If there is a prime factor > sqrt(original n), flip sign once more.
+    if n > 1 { -mu } else { mu }
+}
+
+/// Returns the greatest common divisor of `a` and `b`, where gcd(0, 0) = 0.
+///
+/// Time complexity: O(log(min(a, b)))
+/// Auxiliary space complexity: O(1)
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the least common multiple of `a` and `b`, where lcm(0, n) = 0, or `None` if it
+/// overflows a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Returns `base`^`exp` mod `modulus`, by repeated squaring.
+///
+/// Time complexity: O(log(exp))
+/// Auxiliary space complexity: O(1)
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "modulus must be positive");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Returns `(g, x, y)` with g = gcd(a, b) = ax + by.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+/// Solves the system of congruences x ≡ r (mod m) for each `(r, m)` in `congruences` with the
+/// Chinese remainder theorem, returning `(x, lcm)` where `x` is the least non-negative solution
+/// and every solution is congruent to it modulo `lcm`, the least common multiple of the moduli.
+///
+/// The moduli need not be pairwise coprime. Returns `None` if the congruences are inconsistent or
+/// the least common multiple overflows a `u64`. An empty system is solved by `(0, 1)`.
+///
+/// Time complexity: O(N log(M)) where N is the number of congruences and M is the largest modulus
+/// Auxiliary space complexity: O(1)
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(r, n) in congruences {
+        assert!(n > 0, "moduli must be positive");
+        let (r, n) = (r as i128 % n as i128, n as i128);
+        // Find k with x + m k ≡ r (mod n), which needs g = gcd(m, n) to divide r - x.
+        let (g, p, _) = extended_gcd(m, n);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let lcm = m.checked_mul(step).filter(|&lcm| lcm <= u64::MAX as i128)?;
+        // Both factors are below `step`, which fits in a `u64`, so their product fits in a `u128`.
+        let (a, b) = (((r - x) / g).rem_euclid(step), p.rem_euclid(step));
+        let k = (a as u128 * b as u128 % step as u128) as i128;
+        x += m * k;
+        m = lcm;
+    }
+    Some((x as u64, m as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_num_digits_and_pow10() {
+        assert_eq!(num_digits(0), 1);
+        assert_eq!(num_digits(9), 1);
+        assert_eq!(num_digits(10), 2);
+        assert_eq!(num_digits(u64::MAX), 20);
+        for exp in 0..20 {
+            assert_eq!(num_digits(pow10(exp) as u64), exp + 1);
+            if exp > 0 {
+                assert_eq!(num_digits(pow10(exp) as u64 - 1), exp);
+            }
+        }
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+        for n in 1..200 {
+            let expected: Vec<u32> = (1..=n).filter(|d| n % d == 0).collect();
+            assert_eq!(divisors(n), expected);
+        }
+    }
+
+    /// Checks μ against its defining property: the sum of μ(d) over the divisors d of n is 1 for
+    /// n = 1 and 0 otherwise.
+    #[test]
+    fn test_mobius() {
+        assert_eq!(
+            (0..11).map(mobius).collect::<Vec<_>>(),
+            vec![0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1]
+        );
+        for n in 1..500 {
+            let sum: i32 = divisors(n).into_iter().map(mobius).sum();
+            assert_eq!(sum, (n == 1) as i32, "{n}");
+        }
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        let mut rng = crate::seed::rng();
+        for _ in 0..1000 {
+            let (a, b) = (
+                rng.random_range(1..10_000u64),
+                rng.random_range(1..10_000u64),
+            );
+            let g = gcd(a, b);
+            assert!(a % g == 0 && b % g == 0);
+            assert_eq!(gcd(a / g, b / g), 1);
+            assert_eq!(lcm(a, b), Some(a * b / g));
+        }
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+        let mut rng = crate::seed::rng();
+        for _ in 0..1000 {
+            let base = rng.random_range(0..1000u64);
+            let exp = rng.random_range(0..50u64);
+            let modulus = rng.random_range(1..1000u64);
+            let expected = (0..exp).fold(1 % modulus, |acc, _| acc * base % modulus);
+            assert_eq!(mod_pow(base, exp, modulus), expected);
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(0, u64::MAX), (1, 2)]), None);
+        let big = (1u64 << 63) - 25; // The largest prime below 2^63.
+        assert_eq!(crt(&[(big - 1, big), (1, 2)]), Some((2 * big - 1, 2 * big)));
+    }
+
+    /// Compares against searching every residue below the least common multiple.
+    #[test]
+    fn test_crt_matches_search() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..500 {
+            let congruences: Vec<(u64, u64)> = (0..rng.random_range(1..4))
+                .map(|_| (rng.random_range(0..30u64), rng.random_range(1..12u64)))
+                .collect();
+            let modulus = congruences
+                .iter()
+                .fold(1, |acc, &(_, m)| lcm(acc, m).unwrap());
+            let expected = (0..modulus)
+                .find(|x| congruences.iter().all(|&(r, m)| x % m == r % m))
+                .map(|x| (x, modulus));
+            assert_eq!(crt(&congruences), expected, "{congruences:?}");
+        }
+    }
+}
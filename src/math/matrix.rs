@@ -0,0 +1,281 @@
+use std::num::{Saturating, Wrapping};
+use std::ops::{Index, IndexMut, Mul};
+
+/// A set of values with an addition and a multiplication that distributes over it, such as
+/// integers under wrapping, modular or saturating arithmetic.
+pub trait Semiring: Copy {
+    /// The identity of [`Semiring::plus`].
+    fn zero() -> Self;
+    /// The identity of [`Semiring::times`].
+    fn one() -> Self;
+    fn plus(self, other: Self) -> Self;
+    fn times(self, other: Self) -> Self;
+}
+
+/// Arithmetic modulo 2^64.
+impl Semiring for Wrapping<u64> {
+    fn zero() -> Self {
+        Wrapping(0)
+    }
+
+    fn one() -> Self {
+        Wrapping(1)
+    }
+
+    fn plus(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn times(self, other: Self) -> Self {
+        self * other
+    }
+}
+
+/// Arithmetic that sticks at `u64::MAX` instead of overflowing, for counts that only need to be
+/// compared against a bound.
+impl Semiring for Saturating<u64> {
+    fn zero() -> Self {
+        Saturating(0)
+    }
+
+    fn one() -> Self {
+        Saturating(1)
+    }
+
+    fn plus(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn times(self, other: Self) -> Self {
+        self * other
+    }
+}
+
+/// An integer modulo `M`, which must be at least 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(value: u64) -> Self {
+        Self(value % M)
+    }
+
+    /// The representative in `0..M`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl<const M: u64> Semiring for ModInt<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn one() -> Self {
+        Self::new(1)
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Self(((self.0 as u128 + other.0 as u128) % M as u128) as u64)
+    }
+
+    fn times(self, other: Self) -> Self {
+        Self((self.0 as u128 * other.0 as u128 % M as u128) as u64)
+    }
+}
+
+/// A square matrix over a [`Semiring`], stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    size: usize,
+    entries: Vec<T>,
+}
+
+impl<T: Semiring> Matrix<T> {
+    /// Creates the `size` × `size` matrix of zeros.
+    pub fn zeros(size: usize) -> Self {
+        Self {
+            size,
+            entries: vec![T::zero(); size * size],
+        }
+    }
+
+    /// Creates the `size` × `size` identity matrix.
+    pub fn identity(size: usize) -> Self {
+        let mut identity = Self::zeros(size);
+        for i in 0..size {
+            identity[(i, i)] = T::one();
+        }
+        identity
+    }
+
+    /// Creates a matrix from its rows. Panics unless there are as many rows as entries in each.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let size = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == size),
+            "matrix rows must all have {size} entries"
+        );
+        Self {
+            size,
+            entries: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// The number of rows, which is also the number of columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `self^exp` by repeated squaring. `self^0` is the identity.
+    ///
+    /// Time complexity: O(N^3 log exp)
+    /// Auxiliary space complexity: O(N^2)
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut result = Self::identity(self.size);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Returns the product of the matrix with the column vector `v`.
+    ///
+    /// Time complexity: O(N^2)
+    /// Auxiliary space complexity: O(N)
+    pub fn apply(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(v.len(), self.size, "vector length must match the matrix");
+        self.entries
+            .chunks(self.size.max(1))
+            .take(self.size)
+            .map(|row| dot(row.iter().copied().zip(v.iter().copied())))
+            .collect()
+    }
+}
+
+fn dot<T: Semiring>(pairs: impl Iterator<Item = (T, T)>) -> T {
+    pairs.fold(T::zero(), |sum, (a, b)| sum.plus(a.times(b)))
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(col < self.size, "column {col} is out of range");
+        &self.entries[row * self.size + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(col < self.size, "column {col} is out of range");
+        &mut self.entries[row * self.size + col]
+    }
+}
+
+/// Multiplies two matrices of the same size.
+///
+/// Time complexity: O(N^3)
+/// Auxiliary space complexity: O(N^2)
+impl<T: Semiring> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.size, other.size, "matrices must have the same size");
+        let n = self.size;
+        let mut product = Matrix::<T>::zeros(n);
+        for i in 0..n {
+            for k in 0..n {
+                let a = self[(i, k)];
+                for j in 0..n {
+                    product[(i, j)] = product[(i, j)].plus(a.times(other[(k, j)]));
+                }
+            }
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn wrapping(rows: &[&[u64]]) -> Matrix<Wrapping<u64>> {
+        Matrix::from_rows(
+            rows.iter()
+                .map(|row| row.iter().map(|&x| Wrapping(x)).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let step = wrapping(&[&[1, 1], &[1, 0]]);
+        assert_eq!(step.pow(0), Matrix::identity(2));
+        assert_eq!(step.pow(10)[(0, 1)], Wrapping(55));
+        assert_eq!(step.pow(90)[(0, 1)], Wrapping(2_880_067_194_370_816_120));
+        // F(100) mod 2^64.
+        let f100 = 354_224_848_179_261_915_075u128;
+        assert_eq!(step.pow(100)[(0, 1)], Wrapping(f100 as u64));
+        let step: Matrix<ModInt<1_000_000_007>> = Matrix::from_rows(vec![
+            vec![ModInt::new(1); 2],
+            vec![ModInt::new(1), ModInt::zero()],
+        ]);
+        assert_eq!(step.pow(100)[(0, 1)].value(), (f100 % 1_000_000_007) as u64);
+    }
+
+    #[test]
+    fn test_linear_recurrence() {
+        // a(n) = 2 a(n-1) + 3 a(n-2) + a(n-3), stepping the vector (a(n), a(n-1), a(n-2)).
+        let step = wrapping(&[&[2, 3, 1], &[1, 0, 0], &[0, 1, 0]]);
+        let mut terms = vec![0u64, 0, 1];
+        for n in 3..32 {
+            terms.push(2 * terms[n - 1] + 3 * terms[n - 2] + terms[n - 3]);
+        }
+        for n in 0..30 {
+            let state = step
+                .pow(n as u64)
+                .apply(&[Wrapping(1), Wrapping(0), Wrapping(0)]);
+            assert_eq!(state[0], Wrapping(terms[n + 2]), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_saturating() {
+        let doubling: Matrix<Saturating<u64>> = Matrix::from_rows(vec![vec![Saturating(2)]]);
+        assert_eq!(doubling.pow(63)[(0, 0)], Saturating(1 << 63));
+        assert_eq!(doubling.pow(64)[(0, 0)], Saturating(u64::MAX));
+        assert_eq!(doubling.pow(1000)[(0, 0)], Saturating(u64::MAX));
+    }
+
+    /// Checks exponentiation by squaring against repeated multiplication.
+    #[test]
+    fn test_pow_matches_repeated_multiplication() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..50 {
+            let n = rng.random_range(0..5);
+            let m: Matrix<ModInt<998_244_353>> = Matrix::from_rows(
+                (0..n)
+                    .map(|_| {
+                        (0..n)
+                            .map(|_| ModInt::new(rng.random_range(0..u64::MAX)))
+                            .collect()
+                    })
+                    .collect(),
+            );
+            let mut power = Matrix::identity(n);
+            for exp in 0..20 {
+                assert_eq!(m.pow(exp), power, "{m:?}^{exp}");
+                power = &power * &m;
+            }
+        }
+    }
+}
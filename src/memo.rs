@@ -0,0 +1,185 @@
+use ahash::AHashMap;
+use std::hash::Hash;
+
+/// Counters from a [`Memo`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// The number of lookups that found an entry.
+    pub hits: u64,
+    /// The number of lookups that found nothing.
+    pub misses: u64,
+    /// The number of entries dropped to stay within the limit.
+    pub evictions: u64,
+}
+
+/// A memo table from `K` to `V` that optionally holds at most `limit` entries.
+///
+/// Entries are kept in two generations. Once the current generation of a bounded memo fills half
+/// the limit it becomes the previous one, and the old previous generation is dropped. An entry
+/// found in the previous generation moves back to the current one, so the entries evicted are the
+/// ones that went longest without being looked up. A limit of one leaves no room for a previous
+/// generation, so such a memo keeps only the entry it last stored.
+pub struct Memo<K, V> {
+    limit: Option<usize>,
+    current: AHashMap<K, V>,
+    previous: AHashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    /// Creates a memo that never evicts.
+    pub fn new() -> Self {
+        Self {
+            limit: None,
+            current: AHashMap::new(),
+            previous: AHashMap::new(),
+            stats: MemoStats::default(),
+        }
+    }
+
+    /// Creates a memo that holds at most `limit` entries, or at most one if `limit` is 0.
+    pub fn bounded(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.current.len() + self.previous.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty() && self.previous.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Looks up `key`, counting a hit or a miss.
+    ///
+    /// Time complexity: O(1) expected, and amortized for bounded memos
+    /// Auxiliary space complexity: O(1)
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        if !self.current.contains_key(key) {
+            let Some((key, value)) = self.previous.remove_entry(key) else {
+                self.stats.misses += 1;
+                return None;
+            };
+            self.insert_current(key, value);
+        }
+        self.stats.hits += 1;
+        self.current.get(key)
+    }
+
+    /// Stores `value` for `key`, replacing any earlier value.
+    ///
+    /// Time complexity: O(1) expected, and amortized for bounded memos
+    /// Auxiliary space complexity: O(1)
+    pub fn insert(&mut self, key: K, value: V) {
+        self.previous.remove(&key);
+        self.insert_current(key, value);
+    }
+
+    /// Returns the value for `key`, computing and storing it with `f` if there is none.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> &V
+    where
+        K: Clone,
+    {
+        if self.get(&key).is_none() {
+            self.insert_current(key.clone(), f());
+        }
+        &self.current[&key]
+    }
+
+    fn insert_current(&mut self, key: K, value: V) {
+        if let Some(limit) = self.limit
+            && self.current.len() >= (limit / 2).max(1)
+            && !self.current.contains_key(&key)
+        {
+            self.stats.evictions += self.previous.len() as u64;
+            self.previous = std::mem::take(&mut self.current);
+            if limit < 2 {
+                self.stats.evictions += self.previous.len() as u64;
+                self.previous.clear();
+            }
+        }
+        self.current.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_insert() {
+        let mut memo: Memo<&str, u32> = Memo::new();
+        assert_eq!(memo.get(&"a"), None);
+        memo.insert("a", 1);
+        memo.insert("a", 2);
+        assert_eq!(memo.get(&"a"), Some(&2));
+        assert_eq!(*memo.get_or_insert_with("b", || 3), 3);
+        assert_eq!(*memo.get_or_insert_with("b", || unreachable!()), 3);
+        assert_eq!(memo.len(), 2);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 2,
+                misses: 2,
+                evictions: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_unbounded_memo_keeps_everything() {
+        let mut memo = Memo::new();
+        for n in 0..10_000u64 {
+            memo.insert(n, n * n);
+        }
+        assert!((0..10_000).all(|n| memo.get(&n) == Some(&(n * n))));
+        assert_eq!(memo.stats().evictions, 0);
+    }
+
+    #[test]
+    fn test_memo_is_bounded() {
+        let mut memo = Memo::bounded(10);
+        for n in 0..100u128 {
+            memo.insert(n, ());
+            // Keep looking up the first entry, so that it is never the least recently used.
+            assert!(memo.get(&0).is_some());
+            assert!(memo.len() <= 10);
+        }
+        assert!(memo.get(&50).is_none());
+        assert!(memo.get(&99).is_some());
+        let stats = memo.stats();
+        assert_eq!(stats.evictions as usize + memo.len(), 100);
+        assert_eq!((stats.hits, stats.misses), (101, 1));
+    }
+
+    #[test]
+    fn test_small_limits() {
+        for limit in 0..=3 {
+            let mut memo = Memo::bounded(limit);
+            for n in 0..10u8 {
+                memo.insert(n, ());
+                assert!(memo.len() <= limit.max(1), "limit {limit}");
+                assert!(memo.get(&n).is_some(), "limit {limit}");
+            }
+            assert_eq!(
+                memo.stats().evictions as usize + memo.len(),
+                10,
+                "limit {limit}"
+            );
+        }
+    }
+}
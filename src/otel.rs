@@ -0,0 +1,343 @@
+use crate::run_log::{Event, Step};
+use reqwest::blocking::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::hash::BuildHasher;
+
+/// The variable the OpenTelemetry SDKs read the base URL of the OTLP endpoint from.
+pub const ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Returns the OTLP endpoint set in [`ENDPOINT_VAR`], if any.
+pub fn endpoint_from_env() -> Option<String> {
+    std::env::var(ENDPOINT_VAR)
+        .ok()
+        .filter(|endpoint| !endpoint.is_empty())
+}
+
+/// A span in the JSON encoding of OTLP.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    /// Always 1, an internal span.
+    pub kind: u8,
+    /// Nanoseconds since the Unix epoch, as a string as OTLP encodes 64-bit integers in JSON.
+    pub start_time_unix_nano: String,
+    pub end_time_unix_nano: String,
+    pub attributes: Vec<KeyValue>,
+}
+
+/// An attribute of a [`Span`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+/// The value of an attribute, of one of the types that OTLP tells apart.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnyValue {
+    StringValue(String),
+    /// A 64-bit integer, as a string.
+    IntValue(String),
+    BoolValue(bool),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRequest {
+    resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Resource,
+    scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Serialize)]
+struct ScopeSpans {
+    scope: Scope,
+    spans: Vec<Span>,
+}
+
+#[derive(Serialize)]
+struct Scope {
+    name: String,
+    version: String,
+}
+
+/// An event with the start and end of its span, in nanoseconds since the Unix epoch.
+type Timed<'a> = (&'a Event, (u64, u64));
+
+/// Turns the events of one run into the spans of one trace with id `trace_id`: a root `run` span
+/// with a span for each day under it, which in turn has a span for each step of the day. The
+/// verification steps, which come after every day is solved, are under the root.
+///
+/// Events only record when they finished to the millisecond, so a day's steps are laid end to end
+/// with their exact durations, ending when its last step did.
+pub fn spans(events: &[Event], trace_id: u128) -> Vec<Span> {
+    let trace_id = format!("{trace_id:032x}");
+    let mut spans = Vec::new();
+    let mut span = |parent: Option<&str>, name: String, (start, end): (u64, u64), attributes| {
+        let span_id = format!("{:016x}", spans.len() + 1);
+        spans.push(Span {
+            trace_id: trace_id.clone(),
+            span_id: span_id.clone(),
+            parent_span_id: parent.map(String::from),
+            name,
+            kind: 1,
+            start_time_unix_nano: start.to_string(),
+            end_time_unix_nano: end.to_string(),
+            attributes,
+        });
+        span_id
+    };
+    let mut days: BTreeMap<u8, Vec<Timed>> = BTreeMap::new();
+    let mut verifications = Vec::new();
+    for event in events {
+        let end = event.timestamp_ms * 1_000_000;
+        match event.event {
+            Step::Verify => verifications.push((event, (end - event.duration_ns, end))),
+            _ => days.entry(event.day).or_default().push((event, (0, end))),
+        }
+    }
+    for steps in days.values_mut() {
+        let mut end = steps.last().map_or(0, |(_, (_, end))| *end);
+        for (event, times) in steps.iter_mut().rev() {
+            *times = (end - event.duration_ns, end);
+            end -= event.duration_ns;
+        }
+    }
+    let all_times = days
+        .values()
+        .flatten()
+        .chain(&verifications)
+        .map(|(_, times)| *times);
+    let run_times = (
+        all_times.clone().map(|(start, _)| start).min().unwrap_or(0),
+        all_times.map(|(_, end)| end).max().unwrap_or(0),
+    );
+    let root = span(None, "run".to_string(), run_times, Vec::new());
+    for (day, steps) in &days {
+        let day_times = (steps[0].1.0, steps[steps.len() - 1].1.1);
+        let day_span = span(
+            Some(&root),
+            format!("day {day:02}"),
+            day_times,
+            vec![int_attribute("aoc.day", u64::from(*day))],
+        );
+        for (event, times) in steps {
+            span(Some(&day_span), step_name(event), *times, attributes(event));
+        }
+    }
+    for (event, times) in verifications {
+        span(Some(&root), step_name(event), times, attributes(event));
+    }
+    spans
+}
+
+/// Names the span of `event` after its step, as the run log does.
+fn step_name(event: &Event) -> String {
+    let name = match event.event {
+        Step::Fetch => "fetch",
+        Step::Parse => "parse",
+        Step::Solve => "solve",
+        Step::Verify => "verify",
+    };
+    name.to_string()
+}
+
+fn attributes(event: &Event) -> Vec<KeyValue> {
+    let mut attributes = vec![int_attribute("aoc.day", u64::from(event.day))];
+    if let Some(part) = event.part {
+        attributes.push(int_attribute("aoc.part", u64::from(part)));
+    }
+    attributes.push(string_attribute("aoc.input_hash", &event.input_hash));
+    if let Some(answer) = &event.answer {
+        attributes.push(string_attribute("aoc.answer", answer));
+    }
+    if let Some(correct) = event.correct {
+        attributes.push(KeyValue {
+            key: "aoc.correct".to_string(),
+            value: AnyValue::BoolValue(correct),
+        });
+    }
+    attributes
+}
+
+fn int_attribute(key: &str, value: u64) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: AnyValue::IntValue(value.to_string()),
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: AnyValue::StringValue(value.to_string()),
+    }
+}
+
+/// Sends the events of one run as a new trace to the OTLP/HTTP endpoint at `endpoint`, such as
+/// `http://localhost:4318` for a local Jaeger or Grafana Alloy. Each call is its own trace.
+pub fn export(events: &[Event], endpoint: &str) -> Result<(), Box<dyn Error>> {
+    let state = RandomState::new();
+    let trace_id = (u128::from(state.hash_one(1)) << 64) | u128::from(state.hash_one(2));
+    let request = ExportRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Resource {
+                attributes: vec![string_attribute("service.name", "aoc2025")],
+            },
+            scope_spans: vec![ScopeSpans {
+                scope: Scope {
+                    name: "aoc2025".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                spans: spans(events, trace_id),
+            }],
+        }],
+    };
+    let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+    let response = Client::new()
+        .post(&url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(&request)?)
+        .send()?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{url} returned {status}: {}", response.text()?).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use std::time::Duration;
+
+    /// The events of a run of one day, with its steps finishing at the same millisecond.
+    fn events() -> Vec<Event> {
+        let step = |step: Step, part: Option<u8>, micros: u64| {
+            let mut event = Event::new(step, 1, "", Duration::from_micros(micros));
+            event.timestamp_ms = 1_764_547_200_000;
+            event.part = part;
+            event
+        };
+        let mut solve = step(Step::Solve, Some(2), 30);
+        solve.answer = Some("6".to_string());
+        let mut verify = step(Step::Verify, Some(2), 1);
+        verify.correct = Some(true);
+        vec![
+            step(Step::Fetch, None, 10),
+            step(Step::Parse, None, 20),
+            solve,
+            verify,
+        ]
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans = spans(&events(), 0xabc);
+        let names: Vec<&str> = spans.iter().map(|span| span.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["run", "day 01", "fetch", "parse", "solve", "verify"]
+        );
+        assert!(
+            spans
+                .iter()
+                .all(|span| span.trace_id == "00000000000000000000000000000abc")
+        );
+        let parents: Vec<Option<&str>> = spans
+            .iter()
+            .map(|span| span.parent_span_id.as_deref())
+            .collect();
+        let (root, day) = (Some("0000000000000001"), Some("0000000000000002"));
+        assert_eq!(parents, [None, root, day, day, day, root]);
+        // The steps are laid end to end, ending at the timestamp.
+        let end = 1_764_547_200_000_000_000_u64;
+        let times: Vec<(u64, u64)> = spans
+            .iter()
+            .map(|span| {
+                let start = span.start_time_unix_nano.parse().unwrap();
+                (start, span.end_time_unix_nano.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(times[1], (end - 60_000, end));
+        assert_eq!(times[2], (end - 60_000, end - 50_000));
+        assert_eq!(times[4], (end - 30_000, end));
+        assert_eq!(times[0], times[1]);
+        assert_eq!(
+            spans[4].attributes,
+            [
+                int_attribute("aoc.day", 1),
+                int_attribute("aoc.part", 2),
+                string_attribute("aoc.input_hash", "cbf29ce484222325"),
+                string_attribute("aoc.answer", "6"),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&spans[5].attributes[3]).unwrap(),
+            r#"{"key":"aoc.correct","value":{"boolValue":true}}"#
+        );
+    }
+
+    #[test]
+    fn test_span_json() {
+        let json = serde_json::to_string(&spans(&events(), 1)[1]).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"traceId":"00000000000000000000000000000001","spanId":"0000000000000002","#,
+                r#""parentSpanId":"0000000000000001","name":"day 01","kind":1,"#,
+                r#""startTimeUnixNano":"1764547199999940000","endTimeUnixNano":"1764547200000000000","#,
+                r#""attributes":[{"key":"aoc.day","value":{"intValue":"1"}}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/v1/traces")
+                .header("content-type", "application/json");
+            then.status(200);
+        });
+        export(&events(), &format!("{}/", server.base_url())).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_export_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/v1/traces");
+            then.status(400).body("bad spans");
+        });
+        let error = export(&events(), &server.base_url()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .ends_with("returned 400 Bad Request: bad spans")
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::fastparse;
+use std::error::Error;
+use std::fmt;
+
+/// Describes why a puzzle input could not be parsed, pointing at the offending line and, for
+/// problems found at a particular character, its column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based number of the line the problem was found on.
+    pub line: usize,
+    /// The 1-based number of the character within the line the problem was found at, or `None`
+    /// if the problem is with the line as a whole.
+    pub col: Option<usize>,
+    /// What is wrong with the input there.
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error about line `line` as a whole.
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            col: None,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error about the character at column `col` of line `line`.
+    pub fn at(line: usize, col: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            col: Some(col),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.col {
+            Some(col) => write!(f, "line {}, column {col}: {}", self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A position within a puzzle input, which parses it from left to right and reports problems as
+/// [`ParseError`]s pointing at where they were found.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    col: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor at the start of `input`.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            line: 1,
+            col: 1,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    /// Returns the input that has not been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Consumes and returns the next character.
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
+
+    /// Returns an error pointing at the cursor.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::at(self.line, self.col, message)
+    }
+
+    /// Returns an error pointing at the cursor, saying that `what` was expected there instead of
+    /// the next character.
+    pub fn expected(&self, what: &str) -> ParseError {
+        self.error(format!("expected {what}, found {}", self.found()))
+    }
+
+    /// Describes the next character for an error.
+    fn found(&self) -> String {
+        match self.peek() {
+            Some('\n') => "the end of the line".to_string(),
+            Some(c) => format!("`{c}`"),
+            None => "the end of the input".to_string(),
+        }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Consumes `expected` if it is the next character, returning whether it was.
+    pub fn eat_char(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    /// Consumes `expected`, which must be the next character.
+    pub fn expect_char(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.eat_char(expected) {
+            Ok(())
+        } else {
+            Err(self.expected(&format!("`{expected}`")))
+        }
+    }
+
+    /// Consumes a decimal number, which must fit in a `u64`.
+    pub fn parse_u64(&mut self) -> Result<u64, ParseError> {
+        let digits = self.rest().bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return Err(self.expected("a number"));
+        }
+        let text = &self.rest()[..digits];
+        let value = fastparse::parse_u64(text.as_bytes())
+            .ok_or_else(|| self.error(format!("`{text}` is too large for a 64-bit number")))?;
+        self.pos += digits;
+        self.col += digits;
+        Ok(value)
+    }
+
+    /// Consumes a decimal number with an optional leading `-`, which must fit in an `i64`.
+    pub fn parse_i64(&mut self) -> Result<i64, ParseError> {
+        let start = *self;
+        let negative = self.eat_char('-');
+        let magnitude = self.parse_u64()?;
+        let value = if negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        };
+        value.ok_or_else(|| {
+            let text = &start.rest()[..self.pos - start.pos];
+            start.error(format!("`{text}` is too large for a 64-bit number"))
+        })
+    }
+
+    /// Checks that nothing but whitespace remains.
+    pub fn expect_end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected {}", self.found())))
+        }
+    }
+
+    /// Consumes the rest of the input, returning a cursor over each of its lines that is not
+    /// blank. The cursors exclude the line breaks.
+    pub fn lines(&mut self) -> Vec<Cursor<'a>> {
+        let mut lines = Vec::new();
+        while !self.is_at_end() {
+            let end = self
+                .rest()
+                .find('\n')
+                .map_or(self.input.len(), |i| self.pos + i);
+            if !self.input[self.pos..end].trim().is_empty() {
+                lines.push(Cursor {
+                    input: &self.input[..end],
+                    ..*self
+                });
+            }
+            // The line holds no line breaks, so only the one ending it moves to the next line.
+            self.pos = end;
+            self.bump();
+        }
+        lines
+    }
+
+    /// Consumes the rest of the input, returning a cursor over each of its sections: runs of lines
+    /// separated by blank lines.
+    pub fn split_sections(&mut self) -> Vec<Cursor<'a>> {
+        let mut sections: Vec<Cursor<'a>> = Vec::new();
+        let mut last_line = 0;
+        for line in self.lines() {
+            match sections.last_mut() {
+                // A section's cursor covers the input up to the end of its last line.
+                Some(section) if line.line == last_line + 1 => section.input = line.input,
+                _ => sections.push(line),
+            }
+            last_line = line.line;
+        }
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::new(3, "expected `x,y`");
+        assert_eq!(error.to_string(), "line 3: expected `x,y`");
+    }
+
+    #[test]
+    fn test_display_with_column() {
+        let error = Cursor::new("12\n 3x").error("oops");
+        assert_eq!(error, ParseError::at(1, 1, "oops"));
+        assert_eq!(error.to_string(), "line 1, column 1: oops");
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = Cursor::new("12-34\n  é5,x");
+        assert_eq!(cursor.parse_u64(), Ok(12));
+        assert_eq!(cursor.expect_char('-'), Ok(()));
+        assert_eq!(cursor.parse_u64(), Ok(34));
+        assert_eq!(
+            cursor.expect_char(','),
+            Err(ParseError::at(
+                1,
+                6,
+                "expected `,`, found the end of the line"
+            ))
+        );
+        cursor.skip_whitespace();
+        assert!(!cursor.eat_char('5'));
+        assert_eq!(cursor.bump(), Some('é'));
+        assert_eq!((cursor.line(), cursor.col()), (2, 4));
+        assert_eq!(cursor.parse_u64(), Ok(5));
+        assert!(cursor.eat_char(','));
+        assert_eq!(
+            cursor.parse_u64().unwrap_err().to_string(),
+            "line 2, column 6: expected a number, found `x`"
+        );
+        assert_eq!(
+            cursor.expect_end().unwrap_err().to_string(),
+            "line 2, column 6: unexpected `x`"
+        );
+        cursor.bump();
+        assert_eq!(cursor.expect_end(), Ok(()));
+        assert_eq!(
+            cursor.parse_u64().unwrap_err().message,
+            "expected a number, found the end of the input"
+        );
+        let mut cursor = Cursor::new("99999999999999999999");
+        assert_eq!(
+            cursor.parse_u64().unwrap_err().to_string(),
+            "line 1, column 1: `99999999999999999999` is too large for a 64-bit number"
+        );
+    }
+
+    #[test]
+    fn test_parse_i64() {
+        let mut cursor = Cursor::new("-12,34,-9223372036854775808,9223372036854775808,-x");
+        assert_eq!(cursor.parse_i64(), Ok(-12));
+        cursor.expect_char(',').unwrap();
+        assert_eq!(cursor.parse_i64(), Ok(34));
+        cursor.expect_char(',').unwrap();
+        assert_eq!(cursor.parse_i64(), Ok(i64::MIN));
+        cursor.expect_char(',').unwrap();
+        assert_eq!(
+            cursor.parse_i64(),
+            Err(ParseError::at(
+                1,
+                29,
+                "`9223372036854775808` is too large for a 64-bit number"
+            ))
+        );
+        cursor.expect_char(',').unwrap();
+        assert_eq!(
+            cursor.parse_i64(),
+            Err(ParseError::at(1, 50, "expected a number, found `x`"))
+        );
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let mut cursor = Cursor::new("\n1\n2\n\n \n3 \r\n\n4\n");
+        let mut sections = cursor.split_sections();
+        assert!(cursor.is_at_end());
+        let starts: Vec<_> = sections.iter().map(|s| (s.line(), s.col())).collect();
+        assert_eq!(starts, vec![(2, 1), (6, 1), (8, 1)]);
+        assert_eq!(sections[0].rest(), "1\n2");
+        assert_eq!(sections[1].rest(), "3 \r");
+        let mut lines = sections[0].lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].parse_u64(), Ok(2));
+        assert_eq!(lines[1].expect_end(), Ok(()));
+        assert_eq!((lines[1].line(), lines[1].col()), (3, 2));
+    }
+}
@@ -0,0 +1,262 @@
+/// A polyomino: a set of cells `(x, y)` on the grid, translated so that its bounding box starts at
+/// `(0, 0)`. Cells are kept sorted, so two shapes are equal exactly when they cover the same cells
+/// after translation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shape {
+    cells: Vec<(u8, u8)>,
+    width: usize,
+    height: usize,
+}
+
+impl Shape {
+    /// Creates the shape covering `cells`, moved so that its least x and y are 0. Duplicate cells
+    /// are merged. Panics if the shape is more than 256 cells across.
+    pub fn new(cells: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        let cells: Vec<(i32, i32)> = cells.into_iter().collect();
+        let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let mut normalized: Vec<(u8, u8)> = cells
+            .into_iter()
+            .map(|(x, y)| {
+                let to_u8 = |v: i32| u8::try_from(v).expect("shape is more than 256 cells across");
+                (to_u8(x - min_x), to_u8(y - min_y))
+            })
+            .collect();
+        normalized.sort_unstable();
+        normalized.dedup();
+        let width = normalized.iter().map(|&(x, _)| x as usize + 1).max();
+        let height = normalized.iter().map(|&(_, y)| y as usize + 1).max();
+        Self {
+            cells: normalized,
+            width: width.unwrap_or(0),
+            height: height.unwrap_or(0),
+        }
+    }
+
+    /// Creates the shape covering the `#` characters of a picture, one line per row.
+    pub fn from_picture(picture: &str) -> Self {
+        Self::new(picture.lines().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x as i32, y as i32))
+        }))
+    }
+
+    /// The cells of the shape, sorted by x and then y.
+    pub fn cells(&self) -> &[(u8, u8)] {
+        &self.cells
+    }
+
+    /// Returns the number of cells.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The width of the bounding box.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the bounding box.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the bounding box fits in a `width` by `height` rectangle without turning.
+    #[inline]
+    pub fn fits_in(&self, width: usize, height: usize) -> bool {
+        self.width <= width && self.height <= height
+    }
+
+    fn map(&self, f: impl Fn(i32, i32) -> (i32, i32)) -> Self {
+        Self::new(self.cells.iter().map(|&(x, y)| f(x as i32, y as i32)))
+    }
+
+    /// Returns the shape turned a quarter turn.
+    pub fn rotate(&self) -> Self {
+        self.map(|x, y| (-y, x))
+    }
+
+    /// Returns the mirror image of the shape, flipped left to right.
+    pub fn flip(&self) -> Self {
+        self.map(|x, y| (-x, y))
+    }
+
+    /// Returns the distinct shapes reachable by rotating and flipping, starting with the shape
+    /// itself, then its rotations, then the rotations of its mirror image. There are at most 8.
+    ///
+    /// Time complexity: O(N log N)
+    /// Auxiliary space complexity: O(N)
+    pub fn variants(&self) -> Vec<Shape> {
+        let mut variants: Vec<Shape> = Vec::with_capacity(8);
+        for mut shape in [self.clone(), self.flip()] {
+            for _ in 0..4 {
+                let next = shape.rotate();
+                if !variants.contains(&shape) {
+                    variants.push(shape);
+                }
+                shape = next;
+            }
+        }
+        variants
+    }
+
+    /// Returns the least of the shape's variants, which is the same for every shape that can be
+    /// rotated or flipped onto this one.
+    pub fn canonical(&self) -> Shape {
+        self.variants().into_iter().min().unwrap()
+    }
+
+    /// Returns whether `other` can be rotated or flipped onto this shape.
+    pub fn is_congruent(&self, other: &Shape) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_new_normalizes() {
+        let shape = Shape::new([(5, -2), (4, -2), (4, -1), (4, -2)]);
+        assert_eq!(shape.cells(), &[(0, 0), (0, 1), (1, 0)]);
+        assert_eq!((shape.area(), shape.width(), shape.height()), (3, 2, 2));
+        assert_eq!(shape, Shape::from_picture("##\n#."));
+        assert!(shape.fits_in(2, 2) && !shape.fits_in(1, 2));
+        assert_eq!(Shape::new([]).area(), 0);
+    }
+
+    #[test]
+    fn test_variants() {
+        let count = |picture: &str| Shape::from_picture(picture).variants().len();
+        assert_eq!(count("#"), 1);
+        assert_eq!(count("##\n##"), 1);
+        assert_eq!(count("####"), 2);
+        assert_eq!(count(".#.\n###"), 4);
+        assert_eq!(count("##.\n.##"), 4);
+        assert_eq!(count("#..\n###"), 8);
+        let l = Shape::from_picture("#.\n#.\n##");
+        let variants = l.variants();
+        assert_eq!(variants[0], l);
+        assert_eq!(variants[1], Shape::from_picture("###\n#.."));
+        assert_eq!(variants[4], Shape::from_picture(".#\n.#\n##"));
+        assert!(l.is_congruent(&Shape::from_picture("..#\n###")));
+        assert!(!l.is_congruent(&Shape::from_picture("####")));
+    }
+
+    fn random_shape(rng: &mut impl Rng) -> Shape {
+        Shape::new(
+            (0..rng.random_range(1..8))
+                .map(|_| (rng.random_range(-3..3), rng.random_range(-3..3)))
+                .collect::<Vec<(i32, i32)>>(),
+        )
+    }
+
+    /// Checks that the variants form a group of at most 8 distinct shapes that every rotation and
+    /// flip maps onto itself.
+    #[test]
+    fn test_variants_are_closed() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..300 {
+            let shape = random_shape(&mut rng);
+            let variants = shape.variants();
+            assert!((1..=8).contains(&variants.len()), "{shape:?}");
+            assert_eq!(8 % variants.len(), 0, "{shape:?}");
+            for (i, v) in variants.iter().enumerate() {
+                assert!(!variants[..i].contains(v), "{shape:?}");
+                assert_eq!((v.area(), v.canonical()), (shape.area(), shape.canonical()));
+                assert!(variants.contains(&v.rotate()), "{shape:?}");
+                assert!(variants.contains(&v.flip()), "{shape:?}");
+                let mut others = v.variants();
+                others.sort();
+                let mut sorted = variants.clone();
+                sorted.sort();
+                assert_eq!(others, sorted, "{shape:?}");
+            }
+            assert_eq!(shape.rotate().rotate().rotate().rotate(), shape);
+            assert_eq!(shape.flip().flip(), shape);
+        }
+    }
+
+    /// Grows a random polyomino of `size` cells from one cell, adding a random neighbour of a
+    /// random cell each time.
+    fn random_polyomino(rng: &mut impl Rng, size: usize) -> Vec<(i32, i32)> {
+        let mut cells = vec![(0, 0)];
+        while cells.len() < size {
+            let (x, y) = cells[rng.random_range(0..cells.len())];
+            let next =
+                [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)][rng.random_range(0..4usize)];
+            if !cells.contains(&next) {
+                cells.push(next);
+            }
+        }
+        cells
+    }
+
+    fn is_connected(shape: &Shape) -> bool {
+        let cells = shape.cells();
+        let mut seen = vec![cells[0]];
+        let mut i = 0;
+        while i < seen.len() {
+            let (x, y) = seen[i];
+            for &(cx, cy) in cells {
+                if x.abs_diff(cx) + y.abs_diff(cy) == 1 && !seen.contains(&(cx, cy)) {
+                    seen.push((cx, cy));
+                }
+            }
+            i += 1;
+        }
+        seen.len() == cells.len()
+    }
+
+    /// Checks the variants of random polyominoes against the eight symmetries of the square
+    /// applied to the raw cells, and that each is a normalized polyomino with the original's
+    /// bounding box, possibly turned.
+    #[test]
+    fn test_variants_of_random_polyominoes() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..300 {
+            let size = rng.random_range(1..10usize);
+            let cells = random_polyomino(&mut rng, size);
+            let shape = Shape::new(cells.iter().copied());
+            let variants = shape.variants();
+
+            let mut expected: Vec<Shape> = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+                .into_iter()
+                .flat_map(|(sx, sy)| {
+                    let cells = &cells;
+                    [false, true].into_iter().map(move |swap| {
+                        Shape::new(cells.iter().map(|&(x, y)| {
+                            let (x, y) = if swap { (y, x) } else { (x, y) };
+                            (sx * x, sy * y)
+                        }))
+                    })
+                })
+                .collect();
+            expected.sort();
+            expected.dedup();
+            let mut sorted = variants.clone();
+            sorted.sort();
+            assert_eq!(sorted, expected, "{cells:?}");
+            assert_eq!(variants[0], shape);
+
+            let dims = (shape.width(), shape.height());
+            for v in &variants {
+                assert_eq!(v.area(), cells.len(), "{cells:?}");
+                assert!(is_connected(v), "{v:?}");
+                assert!(v.cells().iter().any(|&(x, _)| x == 0), "{v:?}");
+                assert!(v.cells().iter().any(|&(_, y)| y == 0), "{v:?}");
+                let width = v.cells().iter().map(|&(x, _)| x as usize + 1).max();
+                let height = v.cells().iter().map(|&(_, y)| y as usize + 1).max();
+                assert_eq!((Some(v.width()), Some(v.height())), (width, height));
+                assert!(
+                    (v.width(), v.height()) == dims || (v.height(), v.width()) == dims,
+                    "{v:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+/// A monotone priority queue for small integer keys: popping returns an item with the least key,
+/// and every key pushed must be at least the key last popped, as in Dijkstra's algorithm.
+///
+/// Items are kept in one bucket per key, so pushing is O(1) and a sequence of pops costs O(1) each
+/// plus the largest key overall, and memory grows with the largest key.
+///
+/// ```
+/// use aoc2025::pq::BucketQueue;
+///
+/// let mut queue = BucketQueue::new();
+/// queue.push(3, 'c');
+/// queue.push(1, 'a');
+/// assert_eq!(queue.pop(), Some((1, 'a')));
+/// queue.push(2, 'b');
+/// assert_eq!(queue.pop(), Some((2, 'b')));
+/// assert_eq!(queue.pop(), Some((3, 'c')));
+/// assert_eq!(queue.pop(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    /// The least key that may have a non-empty bucket.
+    current: usize,
+    len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: Vec::new(),
+            current: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of items queued.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Queues `item` with priority `key`. Panics if `key` is less than the key last popped.
+    ///
+    /// Time complexity: O(1) amortized, plus O(key) the first time the key is this large
+    /// Auxiliary space complexity: O(1) amortized
+    pub fn push(&mut self, key: usize, item: T) {
+        assert!(
+            key >= self.current,
+            "key {key} is less than the last key popped, {}",
+            self.current
+        );
+        if key >= self.buckets.len() {
+            self.buckets.resize_with(key + 1, Vec::new);
+        }
+        self.buckets[key].push(item);
+        self.len += 1;
+    }
+
+    /// Removes an item with the least key, returning it with its key. Items with equal keys come
+    /// out last in, first out.
+    ///
+    /// Time complexity: O(1) amortized over a run of pops
+    /// Auxiliary space complexity: O(1)
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
+        }
+        self.len -= 1;
+        let item = self.buckets[self.current].pop().unwrap();
+        Some((self.current, item))
+    }
+
+    /// Returns the least key queued.
+    pub fn min_key(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
+        }
+        Some(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut queue = BucketQueue::new();
+        assert_eq!(queue.pop(), None);
+        queue.push(3, 'c');
+        queue.push(1, 'a');
+        queue.push(3, 'd');
+        assert_eq!((queue.len(), queue.min_key()), (3, Some(1)));
+        assert_eq!(queue.pop(), Some((1, 'a')));
+        queue.push(2, 'b');
+        assert_eq!(queue.pop(), Some((2, 'b')));
+        assert_eq!(queue.pop(), Some((3, 'd')));
+        assert_eq!(queue.pop(), Some((3, 'c')));
+        assert!(queue.is_empty());
+        assert_eq!(queue.min_key(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "key 1 is less than the last key popped, 2")]
+    fn test_push_below_popped_key() {
+        let mut queue = BucketQueue::new();
+        queue.push(2, ());
+        queue.pop();
+        queue.push(1, ());
+    }
+
+    /// Compares the items popped against a list of everything queued, on random monotone
+    /// workloads.
+    #[test]
+    fn test_pops_least_key() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..200 {
+            let mut queue = BucketQueue::new();
+            let mut queued: Vec<(usize, u32)> = Vec::new();
+            let mut last = 0;
+            for _ in 0..rng.random_range(0..300) {
+                if rng.random_bool(0.6) {
+                    let key = last + rng.random_range(0..10usize);
+                    let item = rng.random_range(0..1000u32);
+                    queue.push(key, item);
+                    queued.push((key, item));
+                } else if let Some((key, item)) = queue.pop() {
+                    assert_eq!(Some(key), queued.iter().map(|&(k, _)| k).min());
+                    let i = queued.iter().position(|&e| e == (key, item)).unwrap();
+                    queued.swap_remove(i);
+                    last = key;
+                } else {
+                    assert!(queued.is_empty());
+                }
+                assert_eq!(queue.len(), queued.len());
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+/// 2D prefix sums over a grid of integers, for O(1) sums over any rectangle.
+///
+/// Sums are accumulated as `i128`, so they cannot overflow for grids of `i64`s (or anything
+/// narrower) that fit in memory.
+///
+/// ```
+/// use aoc2025::prefix::Prefix2D;
+///
+/// let prefix = Prefix2D::from_grid(&[vec![1, 2, 3], vec![4, 5, 6]]);
+/// assert_eq!(prefix.sum_rect(0..3, 0..2), 21);
+/// assert_eq!(prefix.sum_rect(1..3, 1..2), 5 + 6);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Prefix2D {
+    /// `sums[y * (width + 1) + x]` is the sum over rows `0..y` and columns `0..x`.
+    sums: Vec<i128>,
+    width: usize,
+    height: usize,
+}
+
+impl Prefix2D {
+    /// Builds the prefix sums of a grid given as rows, which must all have the same length.
+    ///
+    /// Time complexity: O(W * H)
+    /// Auxiliary space complexity: O(W * H)
+    pub fn from_grid<T: Copy + Into<i128>>(grid: &[Vec<T>]) -> Self {
+        let height = grid.len();
+        let width = grid.first().map_or(0, Vec::len);
+        let stride = width + 1;
+        let mut sums = vec![0; stride * (height + 1)];
+        for (y, row) in grid.iter().enumerate() {
+            assert_eq!(row.len(), width, "row {y} has a different length");
+            let mut row_sum = 0;
+            for (x, &value) in row.iter().enumerate() {
+                row_sum += value.into();
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+            }
+        }
+        Self {
+            sums,
+            width,
+            height,
+        }
+    }
+
+    /// The number of columns in the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows in the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn at(&self, x: usize, y: usize) -> i128 {
+        self.sums[y * (self.width + 1) + x]
+    }
+
+    /// Returns the sum over columns `xs` and rows `ys`, which is 0 if either range is empty.
+    ///
+    /// Time complexity: O(1)
+    /// Auxiliary space complexity: O(1)
+    pub fn sum_rect(&self, xs: Range<usize>, ys: Range<usize>) -> i128 {
+        if xs.is_empty() || ys.is_empty() {
+            return 0;
+        }
+        assert!(
+            xs.end <= self.width && ys.end <= self.height,
+            "rectangle {xs:?} x {ys:?} is outside the {}x{} grid",
+            self.width,
+            self.height
+        );
+        self.at(xs.end, ys.end) - self.at(xs.start, ys.end) - self.at(xs.end, ys.start)
+            + self.at(xs.start, ys.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_sum_rect() {
+        let grid = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let prefix = Prefix2D::from_grid(&grid);
+        assert_eq!((prefix.width(), prefix.height()), (3, 2));
+        assert_eq!(prefix.sum_rect(0..3, 0..2), 21);
+        assert_eq!(prefix.sum_rect(1..3, 1..2), 11);
+        assert_eq!(prefix.sum_rect(2..2, 0..2), 0);
+        assert_eq!(Prefix2D::from_grid::<u8>(&[]).sum_rect(0..0, 0..0), 0);
+    }
+
+    #[test]
+    fn test_sums_do_not_overflow() {
+        let grid = vec![vec![i64::MAX; 4]; 4];
+        let prefix = Prefix2D::from_grid(&grid);
+        assert_eq!(prefix.sum_rect(0..4, 0..4), 16 * i64::MAX as i128);
+        let prefix = Prefix2D::from_grid(&[vec![true, false], vec![true, true]]);
+        assert_eq!(prefix.sum_rect(0..2, 0..2), 3);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..100 {
+            let (w, h) = (rng.random_range(0..8usize), rng.random_range(0..8usize));
+            let grid: Vec<Vec<i32>> = (0..h)
+                .map(|_| (0..w).map(|_| rng.random_range(-100..100)).collect())
+                .collect();
+            let prefix = Prefix2D::from_grid(&grid);
+            for _ in 0..20 {
+                let (x0, x1) = (rng.random_range(0..=w), rng.random_range(0..=w));
+                let (y0, y1) = (rng.random_range(0..=h), rng.random_range(0..=h));
+                let expected: i128 = grid[y0.min(y1)..y1]
+                    .iter()
+                    .flat_map(|row| &row[x0.min(x1)..x1])
+                    .map(|&v| v as i128)
+                    .sum();
+                assert_eq!(prefix.sum_rect(x0..x1, y0..y1), expected, "{grid:?}");
+            }
+        }
+    }
+}
@@ -1,4 +1,40 @@
+use crate::render::{Raster, Visualization};
+
 pub trait Puzzle {
     fn solve_part_1(&self) -> String;
     fn solve_part_2(&self) -> String;
+
+    /// Renders a picture of the puzzle and its solution, for days that support it.
+    fn visualize(&self) -> Option<Visualization> {
+        None
+    }
+
+    /// Renders a picture of the puzzle as a grid of cells, for days whose pictures are grids.
+    fn raster(&self) -> Option<Raster> {
+        None
+    }
 }
+
+/// Generates a test for each example listed, checking that the `Day` in scope where it is used
+/// solves that part of the example to the expected answer:
+///
+/// ```text
+/// example_tests! {
+///     test_part_1_example_1: solve_part_1(EXAMPLE) == "3",
+///     test_part_2_example_1: solve_part_2(EXAMPLE) == "6",
+/// }
+/// ```
+#[cfg(test)]
+macro_rules! example_tests {
+    ($($name:ident: $part:ident($input:expr) == $answer:literal),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_eq!(Day::create($input).$part(), $answer);
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+pub(crate) use example_tests;
@@ -0,0 +1,358 @@
+use crate::parse::ParseError;
+use crate::puzzle::Puzzle;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic;
+use std::str::FromStr;
+
+/// A solved day: its number and how to build its puzzle from an input.
+#[derive(Clone, Copy)]
+pub struct DayEntry {
+    pub number: u8,
+    pub create: fn(&str) -> Box<dyn Puzzle>,
+    /// Builds the puzzle, reporting malformed input as a [`ParseError`] rather than panicking, for
+    /// the days whose parsers do.
+    pub try_create: Option<TryCreate>,
+}
+
+/// Builds a day's puzzle from an input, reporting malformed input as a [`ParseError`].
+pub type TryCreate = fn(&str) -> Result<Box<dyn Puzzle>, ParseError>;
+
+/// Every solved day, in order. Adding a day here is enough for the binary to run it and for the
+/// answer tests to check it against `resources/answers.toml`.
+pub const DAYS: [DayEntry; 12] = [
+    DayEntry {
+        number: 1,
+        create: day01::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 2,
+        create: day02::Day::create,
+        try_create: Some(day02::Day::try_create),
+    },
+    DayEntry {
+        number: 3,
+        create: day03::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 4,
+        create: day04::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 5,
+        create: day05::Day::create,
+        try_create: Some(day05::Day::try_create),
+    },
+    DayEntry {
+        number: 6,
+        create: day06::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 7,
+        create: day07::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 8,
+        create: day08::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 9,
+        create: day09::Day::create,
+        try_create: Some(day09::Day::try_create),
+    },
+    DayEntry {
+        number: 10,
+        create: day10::Day::create,
+        try_create: Some(day10::Day::try_create),
+    },
+    DayEntry {
+        number: 11,
+        create: day11::Day::create,
+        try_create: None,
+    },
+    DayEntry {
+        number: 12,
+        create: day12::Day::create,
+        try_create: None,
+    },
+];
+
+/// A second way of solving a day, such as a brute-force search or a different algorithm, kept to
+/// check the default one against.
+#[derive(Clone, Copy)]
+pub struct Variant {
+    pub number: u8,
+    pub name: &'static str,
+    pub create: fn(&str) -> Box<dyn Puzzle>,
+}
+
+/// Every alternative solver. Each must give the same answers as its day's entry in [`DAYS`].
+pub const VARIANTS: [Variant; 3] = [
+    Variant {
+        number: 8,
+        name: "exhaustive pairs",
+        create: |input| {
+            Box::new(day08::Day::with_search(
+                input,
+                day08::PairSearch::Exhaustive,
+            ))
+        },
+    },
+    Variant {
+        number: 12,
+        name: "dancing links",
+        create: |input| {
+            let options = day12::Options {
+                solver: day12::Solver::Dlx,
+                ..day12::Options::default()
+            };
+            Box::new(day12::Day::with_options(input, options))
+        },
+    },
+    Variant {
+        number: 12,
+        name: "hole pruning",
+        create: |input| {
+            let options = day12::Options {
+                prune_holes: true,
+                ..day12::Options::default()
+            };
+            Box::new(day12::Day::with_options(input, options))
+        },
+    },
+];
+
+/// Returns the entry for day `number`, if that day is solved.
+pub fn get(number: u8) -> Option<&'static DayEntry> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// One of the two parts of a puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("unknown part '{s}' (expected '1' or '2')")),
+        }
+    }
+}
+
+/// Why [`solve`] gave no answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// No day with this number is solved.
+    UnknownDay(u8),
+    /// The day could not parse the input.
+    Parse { day: u8, error: ParseError },
+    /// The day panicked on the input. Only days without a [`DayEntry::try_create`] report bad
+    /// input this way.
+    InvalidInput { day: u8, message: String },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::UnknownDay(day) => write!(f, "day {day} is not solved"),
+            SolveError::Parse { day, error } => write!(f, "invalid input for day {day}: {error}"),
+            SolveError::InvalidInput { day, message } => {
+                write!(f, "invalid input for day {day}: {message}")
+            }
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Solves `part` of `day` on `input`, for programs that want answers without handling puzzles
+/// themselves. Input that the day cannot parse is reported as [`SolveError::Parse`]. Days with no
+/// [`DayEntry::try_create`] panic on such input instead, which is caught and reported as
+/// [`SolveError::InvalidInput`], though the panic hook still prints it and it cannot be caught
+/// where panics abort.
+///
+/// ```
+/// use aoc2025::{Part, SolveError, examples, solve};
+///
+/// assert_eq!(solve(1, Part::Two, examples::DAY01), Ok("6".to_string()));
+/// assert_eq!(solve(13, Part::One, ""), Err(SolveError::UnknownDay(13)));
+/// ```
+pub fn solve(day: u8, part: Part, input: &str) -> Result<String, SolveError> {
+    let entry = get(day).ok_or(SolveError::UnknownDay(day))?;
+    let solve_part = |puzzle: Box<dyn Puzzle>| match part {
+        Part::One => puzzle.solve_part_1(),
+        Part::Two => puzzle.solve_part_2(),
+    };
+    match entry.try_create {
+        Some(try_create) => {
+            let puzzle = try_create(input).map_err(|error| SolveError::Parse { day, error })?;
+            Ok(solve_part(puzzle))
+        }
+        None => panic::catch_unwind(|| solve_part((entry.create)(input))).map_err(|payload| {
+            SolveError::InvalidInput {
+                day,
+                message: panic_message(&payload),
+            }
+        }),
+    }
+}
+
+/// Returns the message of a solver's panic.
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "the solver panicked".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::{self, AnswerFile};
+    use crate::examples;
+    use crate::fixtures;
+    use crate::run_log;
+    use crate::runner::RunResult;
+    use rayon::prelude::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_solve() {
+        for day in &DAYS {
+            let input = examples::get(day.number).unwrap();
+            let puzzle = (day.create)(input);
+            assert_eq!(
+                solve(day.number, Part::One, input),
+                Ok(puzzle.solve_part_1())
+            );
+            assert_eq!(
+                solve(day.number, Part::Two, input),
+                Ok(puzzle.solve_part_2())
+            );
+        }
+        assert_eq!(solve(0, Part::One, ""), Err(SolveError::UnknownDay(0)));
+        assert_eq!(
+            "2".parse::<Part>()
+                .map(|part| solve(2, part, "11-22,95_115")),
+            Ok(Err(SolveError::Parse {
+                day: 2,
+                error: ParseError::at(1, 9, "expected `-`, found `_`")
+            }))
+        );
+        assert_eq!(
+            solve(2, Part::One, "x").unwrap_err().to_string(),
+            "invalid input for day 2: line 1, column 1: expected a number, found `x`"
+        );
+        assert!(matches!(
+            solve(1, Part::One, "Lx"),
+            Err(SolveError::InvalidInput { day: 1, .. })
+        ));
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn test_days_are_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number as usize, i + 1);
+        }
+        assert_eq!(get(7).map(|day| day.number), Some(7));
+        assert!(get(0).is_none() && get(13).is_none());
+        assert!(VARIANTS.iter().all(|variant| get(variant.number).is_some()));
+    }
+
+    /// Returns the numbers of the `dayNN.rs` modules in `src`, in order.
+    fn day_modules() -> Vec<u8> {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut numbers: Vec<u8> = std::fs::read_dir(src)
+            .unwrap()
+            .filter_map(|entry| {
+                let name = entry.unwrap().file_name().into_string().ok()?;
+                name.strip_prefix("day")?.strip_suffix(".rs")?.parse().ok()
+            })
+            .collect();
+        numbers.sort_unstable();
+        numbers
+    }
+
+    #[test]
+    fn test_every_day_module_is_registered() {
+        let modules = day_modules();
+        assert!(!modules.is_empty());
+        let registered: Vec<u8> = DAYS.iter().map(|day| day.number).collect();
+        assert_eq!(
+            registered, modules,
+            "registry::DAYS must list exactly the days in src, so that the binary, the answer \
+             tests and the benchmarks all run them"
+        );
+    }
+
+    /// Solves each part of `day` on its personal input, returning a message for every answer that
+    /// differs from the one in `answers`. Parts with no answer listed are not checked, and neither
+    /// is a day with no input file.
+    fn check_answers(day: &DayEntry, answers: &AnswerFile) -> Vec<String> {
+        let key = format!("day{:02}", day.number);
+        let Some(expected) = answers.get(day.number) else {
+            return vec![format!("{key} has no answers in resources/answers.toml")];
+        };
+        let input = match fixtures::read_input(day.number) {
+            Ok(input) => input,
+            Err(e) if e.is_absent() => {
+                eprintln!("skipping {key}: no input at {}", e.path.display());
+                return Vec::new();
+            }
+            Err(e) => return vec![format!("{key}: {e}")],
+        };
+        if !expected.is_for_input(&run_log::input_hash(&input)) {
+            return vec![format!("{key}: the answers are for a different input")];
+        }
+        let puzzle = (day.create)(&input);
+        let result = RunResult {
+            day: day.number,
+            part1: puzzle.solve_part_1(),
+            part2: puzzle.solve_part_2(),
+            visualization: None,
+        };
+        expected
+            .mismatches(&result)
+            .into_iter()
+            .map(|mismatch| format!("{key} {mismatch}"))
+            .collect()
+    }
+
+    #[test]
+    fn test_solve_answers() {
+        if !fixtures::inputs_available() {
+            eprintln!(
+                "skipping test_solve_answers: no puzzle inputs in {}",
+                fixtures::inputs_dir().display()
+            );
+            return;
+        }
+        let answers = AnswerFile::load(&answers::default_path()).unwrap();
+        let failures: Vec<Vec<String>> = DAYS
+            .par_iter()
+            .map(|day| check_answers(day, &answers))
+            .collect();
+        let failures = failures.concat();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,609 @@
+use crate::puzzle::Puzzle;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A rendered visualization of a puzzle, ready to be written to disk.
+pub enum Visualization {
+    /// A Scalable Vector Graphics document.
+    Svg(String),
+    /// A GraphViz graph in the DOT language.
+    Dot(String),
+    /// A PNG image.
+    #[cfg(feature = "png")]
+    Png(Vec<u8>),
+}
+
+impl Visualization {
+    /// The file extension conventionally used for this kind of visualization.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Visualization::Svg(_) => "svg",
+            Visualization::Dot(_) => "dot",
+            #[cfg(feature = "png")]
+            Visualization::Png(_) => "png",
+        }
+    }
+
+    /// The rendered document.
+    ///
+    /// # Panics
+    ///
+    /// If the visualization is an image rather than a text document.
+    pub fn contents(&self) -> &str {
+        match self {
+            Visualization::Svg(contents) | Visualization::Dot(contents) => contents,
+            #[cfg(feature = "png")]
+            Visualization::Png(_) => panic!("a PNG image is not a text document"),
+        }
+    }
+
+    /// The bytes of the file the visualization is written to.
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Visualization::Svg(contents) | Visualization::Dot(contents) => contents.as_bytes(),
+            #[cfg(feature = "png")]
+            Visualization::Png(bytes) => bytes,
+        }
+    }
+
+    /// Writes the visualization to `dir/name.<extension>`, creating `dir` if needed, and returns
+    /// the path written.
+    pub fn write(&self, dir: &Path, name: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{name}.{}", self.extension()));
+        fs::write(&path, self.bytes())?;
+        Ok(path)
+    }
+}
+
+/// A minimal builder for SVG documents.
+pub struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl Svg {
+    /// Creates an empty document with the given size in user units.
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            body: String::new(),
+        }
+    }
+
+    /// Adds a filled rectangle, with an optional tooltip.
+    pub fn rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        fill: &str,
+        title: Option<&str>,
+    ) {
+        let _ = write!(
+            self.body,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{fill}""#
+        );
+        self.close("rect", title);
+    }
+
+    /// Adds a straight line.
+    pub fn line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), stroke: &str, width: f64) {
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{stroke}" stroke-width="{width}"/>"#
+        );
+    }
+
+    /// Adds a filled polygon through the given points, with an optional tooltip.
+    pub fn polygon(&mut self, points: &[(f64, f64)], fill: &str, title: Option<&str>) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        let _ = write!(
+            self.body,
+            r#"<polygon points="{}" fill="{fill}""#,
+            points.join(" ")
+        );
+        self.close("polygon", title);
+    }
+
+    /// Adds the unfilled outline of the closed polygon through the given points, with an optional
+    /// tooltip.
+    pub fn outline(
+        &mut self,
+        points: &[(f64, f64)],
+        stroke: &str,
+        width: f64,
+        title: Option<&str>,
+    ) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        let _ = write!(
+            self.body,
+            r#"<polygon points="{}" fill="none" stroke="{stroke}" stroke-width="{width}""#,
+            points.join(" ")
+        );
+        self.close("polygon", title);
+    }
+
+    /// Finishes the document.
+    pub fn finish(self) -> Visualization {
+        Visualization::Svg(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\">\n{body}</svg>\n",
+            w = self.width,
+            h = self.height,
+            body = self.body
+        ))
+    }
+
+    /// Closes an element opened with `<tag ...`, attaching the tooltip if there is one.
+    fn close(&mut self, tag: &str, title: Option<&str>) {
+        match title {
+            Some(title) => {
+                let _ = writeln!(self.body, "><title>{}</title></{tag}>", escape(title));
+            }
+            None => self.body.push_str("/>\n"),
+        }
+    }
+}
+
+/// How the runner renders visualizations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VizFormat {
+    /// Each day's own format, from [`Puzzle::visualize`].
+    #[default]
+    Native,
+    /// A PNG image of each day's [`Puzzle::raster`]. Days whose visualizations are not grids keep
+    /// their own format.
+    #[cfg(feature = "png")]
+    Png,
+}
+
+#[cfg(not(feature = "png"))]
+const VIZ_FORMAT_NAMES: &str = "'native'";
+#[cfg(feature = "png")]
+const VIZ_FORMAT_NAMES: &str = "'native' or 'png'";
+
+impl VizFormat {
+    /// Renders `puzzle` in this format, if the day has a visualization.
+    pub fn render(self, puzzle: &dyn Puzzle) -> Option<Visualization> {
+        match self {
+            VizFormat::Native => puzzle.visualize(),
+            #[cfg(feature = "png")]
+            VizFormat::Png => puzzle
+                .raster()
+                .map(|raster| raster.png(PNG_CELL))
+                .or_else(|| puzzle.visualize()),
+        }
+    }
+}
+
+impl FromStr for VizFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(VizFormat::Native),
+            #[cfg(feature = "png")]
+            "png" => Ok(VizFormat::Png),
+            _ => Err(format!(
+                "unknown visualization format '{s}' (expected {VIZ_FORMAT_NAMES})"
+            )),
+        }
+    }
+}
+
+/// The side of a cell in the PNG images that [`VizFormat::Png`] renders, in pixels.
+#[cfg(feature = "png")]
+const PNG_CELL: usize = 8;
+
+/// A grid of colored cells, such as a puzzle's map, for rendering as SVG or as a PNG image. Each
+/// cell belongs to an area, and a line is drawn between neighbouring cells of different areas so
+/// that areas of the same color stay distinguishable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Raster {
+    width: usize,
+    height: usize,
+    /// The color and area of each cell, in row-major order.
+    cells: Vec<([u8; 3], Option<usize>)>,
+}
+
+impl Raster {
+    /// Creates a grid of `width` by `height` cells of the `background` color, in no area.
+    ///
+    /// # Panics
+    ///
+    /// If `background` is not a `#rrggbb` color.
+    pub fn new(width: usize, height: usize, background: &str) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![(parse_color(background), None); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Colors the cell in column `x` of row `y` with `color` and puts it in `area`.
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside the grid, or `color` is not a `#rrggbb` color.
+    pub fn set(&mut self, x: usize, y: usize, color: &str, area: usize) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside the grid"
+        );
+        self.cells[y * self.width + x] = (parse_color(color), Some(area));
+    }
+
+    /// Returns the color of the cell in column `x` of row `y`.
+    pub fn color(&self, x: usize, y: usize) -> [u8; 3] {
+        self.cells[y * self.width + x].0
+    }
+
+    /// Returns whether the cell in column `x` of row `y` has a line along its right edge, and
+    /// along its bottom edge.
+    fn edges(&self, x: usize, y: usize) -> (bool, bool) {
+        let area = |x: usize, y: usize| self.cells[y * self.width + x].1;
+        (
+            x + 1 < self.width && area(x, y) != area(x + 1, y),
+            y + 1 < self.height && area(x, y) != area(x, y + 1),
+        )
+    }
+
+    /// Renders the grid as an SVG document with cells `cell` units wide.
+    pub fn svg(&self, cell: f64) -> Visualization {
+        let mut svg = Svg::new(self.width as f64 * cell, self.height as f64 * cell);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let [r, g, b] = self.color(x, y);
+                let (left, top) = (x as f64 * cell, y as f64 * cell);
+                svg.rect(
+                    left,
+                    top,
+                    cell,
+                    cell,
+                    &format!("#{r:02x}{g:02x}{b:02x}"),
+                    None,
+                );
+            }
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (left, top) = (x as f64 * cell, y as f64 * cell);
+                let (right, bottom) = (left + cell, top + cell);
+                let (right_edge, bottom_edge) = self.edges(x, y);
+                if right_edge {
+                    svg.line((right, top), (right, bottom), "#000000", 1.0);
+                }
+                if bottom_edge {
+                    svg.line((left, bottom), (right, bottom), "#000000", 1.0);
+                }
+            }
+        }
+        svg.finish()
+    }
+
+    /// Returns the colors of the pixels of the grid drawn with cells `cell` pixels wide, in
+    /// row-major order. The lines between areas are the last pixels of the cells before them.
+    pub fn pixels(&self, cell: usize) -> Vec<[u8; 3]> {
+        let width = self.width * cell;
+        (0..self.height * cell)
+            .flat_map(|py| (0..width).map(move |px| (px, py)))
+            .map(|(px, py)| {
+                let (x, y) = (px / cell, py / cell);
+                let (right_edge, bottom_edge) = self.edges(x, y);
+                if (right_edge && px % cell == cell - 1) || (bottom_edge && py % cell == cell - 1) {
+                    [0, 0, 0]
+                } else {
+                    self.color(x, y)
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the grid as a PNG image with cells `cell` pixels wide.
+    #[cfg(feature = "png")]
+    pub fn png(&self, cell: usize) -> Visualization {
+        let width = self.width * cell;
+        let pixels = self.pixels(cell);
+        let image = image::RgbImage::from_fn(width as u32, (self.height * cell) as u32, |x, y| {
+            image::Rgb(pixels[y as usize * width + x as usize])
+        });
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .expect("encoding a PNG image in memory cannot fail");
+        Visualization::Png(bytes)
+    }
+}
+
+/// Parses a `#rrggbb` color, such as one from [`categorical_color`] or [`log_scale_color`].
+fn parse_color(color: &str) -> [u8; 3] {
+    let channel = |i: usize| {
+        color
+            .get(1 + 2 * i..3 + 2 * i)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+    };
+    match (
+        color.len(),
+        color.starts_with('#'),
+        channel(0),
+        channel(1),
+        channel(2),
+    ) {
+        (7, true, Some(r), Some(g), Some(b)) => [r, g, b],
+        _ => panic!("`{color}` is not a #rrggbb color"),
+    }
+}
+
+/// A minimal builder for GraphViz graphs.
+pub struct Dot {
+    body: String,
+    directed: bool,
+}
+
+impl Dot {
+    /// Creates an empty undirected graph with the given graph-wide attributes.
+    pub fn new(attrs: &[(&str, &str)]) -> Self {
+        Self::with_direction(attrs, false)
+    }
+
+    /// Creates an empty directed graph with the given graph-wide attributes.
+    pub fn directed(attrs: &[(&str, &str)]) -> Self {
+        Self::with_direction(attrs, true)
+    }
+
+    fn with_direction(attrs: &[(&str, &str)], directed: bool) -> Self {
+        let mut body = String::new();
+        for (key, value) in attrs {
+            let _ = writeln!(body, "  {key}={};", quote(value));
+        }
+        Self { body, directed }
+    }
+
+    /// Adds a node with the given attributes.
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) {
+        let _ = writeln!(self.body, "  {}{};", quote(id), attr_list(attrs));
+    }
+
+    /// Adds an edge between two nodes, from `a` to `b` in a directed graph, with the given
+    /// attributes.
+    pub fn edge(&mut self, a: &str, b: &str, attrs: &[(&str, &str)]) {
+        let _ = writeln!(
+            self.body,
+            "  {} {} {}{};",
+            quote(a),
+            if self.directed { "->" } else { "--" },
+            quote(b),
+            attr_list(attrs)
+        );
+    }
+
+    /// Finishes the graph.
+    pub fn finish(self) -> Visualization {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        Visualization::Dot(format!("{kind} {{\n{}}}\n", self.body))
+    }
+}
+
+/// Quotes a DOT identifier or attribute value.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Formats a DOT attribute list, or nothing if there are no attributes.
+fn attr_list(attrs: &[(&str, &str)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let attrs: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quote(value)))
+        .collect();
+    format!(" [{}]", attrs.join(", "))
+}
+
+/// Returns a color from a fixed palette of distinguishable colors, cycling through it by `index`.
+pub fn categorical_color(index: usize) -> &'static str {
+    const PALETTE: [&str; 10] = [
+        "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+        "#bcbd22", "#17becf",
+    ];
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Escapes text for inclusion in XML content or attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Maps `value` in `1..=max` onto a color ramp on a logarithmic scale, from pale yellow for 1 to
+/// deep purple for `max`. Returns a `#rrggbb` string.
+pub fn log_scale_color(value: u128, max: u128) -> String {
+    const LOW: (f64, f64, f64) = (253.0, 231.0, 37.0);
+    const HIGH: (f64, f64, f64) = (68.0, 1.0, 84.0);
+    let t = if max <= 1 {
+        0.0
+    } else {
+        ((value.max(1) as f64).ln() / (max as f64).ln()).clamp(0.0, 1.0)
+    };
+    let mix = |low: f64, high: f64| (low + (high - low) * t).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        mix(LOW.0, HIGH.0),
+        mix(LOW.1, HIGH.1),
+        mix(LOW.2, HIGH.2)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_svg_document() {
+        let mut svg = Svg::new(20.0, 10.0);
+        svg.rect(0.0, 0.0, 10.0, 10.0, "#000000", None);
+        svg.rect(10.0, 0.0, 10.0, 10.0, "#ffffff", Some("a < b"));
+        svg.line((0.0, 0.0), (20.0, 10.0), "red", 1.5);
+        svg.polygon(&[(0.0, 0.0), (5.0, 5.0), (0.0, 5.0)], "blue", None);
+        svg.outline(
+            &[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0)],
+            "green",
+            0.5,
+            Some("x"),
+        );
+        let doc = svg.finish().contents().to_string();
+        assert!(doc.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\""));
+        assert!(doc.contains(r##"<rect x="0" y="0" width="10" height="10" fill="#000000"/>"##));
+        assert!(doc.contains(r##"fill="#ffffff"><title>a &lt; b</title></rect>"##));
+        assert!(
+            doc.contains(
+                r#"<line x1="0" y1="0" x2="20" y2="10" stroke="red" stroke-width="1.5"/>"#
+            )
+        );
+        assert!(doc.contains(r#"<polygon points="0,0 5,5 0,5" fill="blue"/>"#));
+        assert!(doc.contains(
+            r#"<polygon points="1,1 2,1 2,2" fill="none" stroke="green" stroke-width="0.5"><title>x</title></polygon>"#
+        ));
+        assert!(doc.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_dot_graph() {
+        let mut dot = Dot::new(&[("layout", "neato")]);
+        dot.node("a", &[("label", "say \"hi\"")]);
+        dot.node("b", &[]);
+        dot.edge("a", "b", &[("color", "red"), ("style", "dashed")]);
+        let vis = dot.finish();
+        assert_eq!(vis.extension(), "dot");
+        let expected = [
+            r#"graph {"#,
+            r#"  layout="neato";"#,
+            r#"  "a" [label="say \"hi\""];"#,
+            r#"  "b";"#,
+            r#"  "a" -- "b" [color="red", style="dashed"];"#,
+            "}\n",
+        ]
+        .join("\n");
+        assert_eq!(vis.contents(), expected);
+    }
+
+    #[test]
+    fn test_directed_dot_graph() {
+        let mut dot = Dot::directed(&[]);
+        dot.edge("a", "b", &[("label", "3")]);
+        let expected = "digraph {\n  \"a\" -> \"b\" [label=\"3\"];\n}\n";
+        assert_eq!(dot.finish().contents(), expected);
+    }
+
+    #[test]
+    fn test_categorical_color() {
+        assert_eq!(categorical_color(0), categorical_color(10));
+        assert_ne!(categorical_color(0), categorical_color(1));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">&'"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;"
+        );
+    }
+
+    #[test]
+    fn test_log_scale_color() {
+        assert_eq!(log_scale_color(1, 1000), "#fde725");
+        assert_eq!(log_scale_color(1000, 1000), "#440154");
+        assert_eq!(log_scale_color(5, 1), "#fde725");
+        assert_ne!(log_scale_color(10, 1000), log_scale_color(100, 1000));
+    }
+
+    #[test]
+    fn test_raster() {
+        let mut raster = Raster::new(3, 2, "#ffffff");
+        raster.set(0, 0, "#ff0000", 0);
+        raster.set(1, 0, "#ff0000", 0);
+        raster.set(0, 1, "#ff0000", 1);
+        assert_eq!(raster.color(1, 0), [255, 0, 0]);
+        assert_eq!(raster.color(2, 1), [255, 255, 255]);
+        assert_eq!(raster.edges(0, 0), (false, true));
+        assert_eq!(raster.edges(1, 0), (true, true));
+        assert_eq!(raster.edges(2, 1), (false, false));
+        let doc = raster.svg(10.0).contents().to_string();
+        assert_eq!(doc.matches("<rect").count(), 6);
+        assert_eq!(doc.matches(r##"fill="#ff0000""##).count(), 3);
+        assert_eq!(doc.matches("<line").count(), 4);
+        assert!(doc.contains(r#"<line x1="20" y1="0" x2="20" y2="10""#));
+        let pixels = raster.pixels(2);
+        assert_eq!(pixels.len(), 6 * 4);
+        // The top left cell, with the line to the cell below it along its bottom.
+        assert_eq!(pixels[0..2], [[255, 0, 0], [255, 0, 0]]);
+        assert_eq!(pixels[6..8], [[0, 0, 0], [0, 0, 0]]);
+        // The line between the second and third cells of the first row.
+        assert_eq!(
+            pixels[2..6],
+            [[255, 0, 0], [0, 0, 0], [255, 255, 255], [255, 255, 255]]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`red` is not a #rrggbb color")]
+    fn test_raster_named_color() {
+        Raster::new(1, 1, "#ffffff").set(0, 0, "red", 0);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_raster_png() {
+        let mut raster = Raster::new(2, 1, "#ffffff");
+        raster.set(0, 0, "#ff0000", 0);
+        let vis = raster.png(4);
+        assert_eq!(vis.extension(), "png");
+        assert!(vis.bytes().starts_with(b"\x89PNG\r\n\x1a\n"));
+        // The IHDR chunk, which comes first, gives the size in pixels.
+        assert_eq!(vis.bytes()[16..24], [0, 0, 0, 8, 0, 0, 0, 4]);
+    }
+
+    #[test]
+    fn test_viz_format() {
+        assert_eq!("native".parse(), Ok(VizFormat::Native));
+        assert!("gif".parse::<VizFormat>().unwrap_err().contains("'native'"));
+        #[cfg(feature = "png")]
+        assert_eq!("png".parse(), Ok(VizFormat::Png));
+    }
+
+    #[test]
+    fn test_write() {
+        let dir = TempDir::new().unwrap();
+        let vis = Svg::new(1.0, 1.0).finish();
+        let path = vis.write(&dir.path().join("nested"), "day07").unwrap();
+        assert_eq!(path, dir.path().join("nested").join("day07.svg"));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), vis.contents());
+    }
+}
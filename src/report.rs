@@ -0,0 +1,333 @@
+use crate::answers::FINAL_DAY;
+use crate::run_log::{Event, Step};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// The comment that starts the generated section of the README.
+pub const START_MARKER: &str = "<!-- results:start -->";
+/// The comment that ends the generated section of the README.
+pub const END_MARKER: &str = "<!-- results:end -->";
+
+/// What a run found for one day: its answers, and how long it took to parse the input and solve
+/// each part, in nanoseconds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DayReport {
+    pub day: u8,
+    pub answers: [Option<String>; 2],
+    pub parse_ns: Option<u64>,
+    pub solve_ns: [Option<u64>; 2],
+}
+
+impl DayReport {
+    /// Returns how long the day took to parse and solve, if the run got that far.
+    pub fn run_time_ns(&self) -> Option<u64> {
+        Some(self.parse_ns? + self.solve_ns[0]? + self.solve_ns[1]?)
+    }
+}
+
+/// Collects the events of a [`RunLog`](crate::run_log::RunLog) into one report per day, in order
+/// of day. When the log holds several runs, each day is reported from its latest.
+pub fn from_events(events: &[Event]) -> Vec<DayReport> {
+    let mut reports: BTreeMap<u8, DayReport> = BTreeMap::new();
+    for event in events {
+        let report = reports.entry(event.day).or_default();
+        report.day = event.day;
+        match (event.event, event.part) {
+            // Every run parses a day before solving it, so a parse starts the day afresh.
+            (Step::Parse, _) => {
+                *report = DayReport {
+                    day: event.day,
+                    parse_ns: Some(event.duration_ns),
+                    ..DayReport::default()
+                };
+            }
+            (Step::Solve, Some(part @ 1..=2)) => {
+                let i = usize::from(part - 1);
+                report.answers[i] = event.answer.clone();
+                report.solve_ns[i] = Some(event.duration_ns);
+            }
+            _ => {}
+        }
+    }
+    reports.into_values().collect()
+}
+
+/// The reports of a run's days together with the benchmark medians to show beside them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    pub days: Vec<DayReport>,
+    /// Median times in nanoseconds, keyed by benchmark id as from
+    /// [`bench_check::latest_medians`](crate::bench_check::latest_medians).
+    pub medians: BTreeMap<String, f64>,
+}
+
+/// Draws the report as the Markdown table of [`render_table`].
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_table(&self.days, &self.medians))
+    }
+}
+
+/// Renders `reports` as a Markdown table of each day's answers and run time, with the medians of
+/// its part benchmarks from `medians` (keyed by benchmark id, as from
+/// [`bench_check::latest_medians`](crate::bench_check::latest_medians)) where there are any.
+pub fn render_table(reports: &[DayReport], medians: &BTreeMap<String, f64>) -> String {
+    let mut rows = rows(reports, medians);
+    let total = rows.len() - 1;
+    rows[total][0] = format!("**{}**", rows[total][0]);
+    table(&rows)
+}
+
+/// The cells of the table of [`render_table`], starting with the header row and ending with the
+/// total row.
+pub(crate) fn rows(reports: &[DayReport], medians: &BTreeMap<String, f64>) -> Vec<[String; 7]> {
+    let mut rows = vec![
+        [
+            "Puzzle",
+            "Part 1 answer",
+            "Part 2 answer",
+            "Run time",
+            "Part 1 median",
+            "Part 2 median",
+            "Total median",
+        ]
+        .map(String::from),
+    ];
+    let mut total_run_ns = Some(0);
+    let mut total_median_ns = Some(0.0);
+    for report in reports {
+        let parts = if report.day == FINAL_DAY { 1 } else { 2 };
+        let part_medians: Vec<Option<f64>> = (1..=parts)
+            .map(|part| {
+                let id = format!("Day {:02}/Part {part}", report.day);
+                medians.get(&id).copied()
+            })
+            .collect();
+        let median_ns: Option<f64> = part_medians.iter().copied().sum();
+        total_run_ns = total_run_ns.zip(report.run_time_ns()).map(|(a, b)| a + b);
+        total_median_ns = total_median_ns.zip(median_ns).map(|(a, b)| a + b);
+        let answer = |i: usize| {
+            (i < parts)
+                .then(|| report.answers[i].clone())
+                .flatten()
+                .unwrap_or_default()
+        };
+        let time = |ns: Option<f64>| ns.map(format_time).unwrap_or_default();
+        rows.push([
+            format!("Day {:02}", report.day),
+            answer(0),
+            answer(1),
+            time(report.run_time_ns().map(|ns| ns as f64)),
+            time(part_medians[0]),
+            time(part_medians.get(1).copied().flatten()),
+            time(median_ns),
+        ]);
+    }
+    let time = |ns: Option<f64>| ns.map(format_time).unwrap_or_default();
+    rows.push([
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        time(total_run_ns.map(|ns| ns as f64)),
+        String::new(),
+        String::new(),
+        time(total_median_ns),
+    ]);
+    rows
+}
+
+/// Lays out `rows` as a Markdown table with padded columns, the first row being the header.
+fn table<const N: usize>(rows: &[[String; N]]) -> String {
+    let mut widths = [0; N];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        for (cell, width) in row.iter().zip(widths) {
+            write!(out, "| {cell:<width$} ").unwrap();
+        }
+        out.push_str("|\n");
+        if i == 0 {
+            for width in widths {
+                write!(out, "|{}", "-".repeat(width + 2)).unwrap();
+            }
+            out.push_str("|\n");
+        }
+    }
+    out
+}
+
+/// Formats a time in nanoseconds to five significant figures in the largest unit that keeps it
+/// at least 1, the way criterion reports them.
+pub fn format_time(ns: f64) -> String {
+    let (value, unit) = match ns {
+        ns if ns < 1e3 => (ns, "ns"),
+        ns if ns < 1e6 => (ns / 1e3, "µs"),
+        ns if ns < 1e9 => (ns / 1e6, "ms"),
+        ns => (ns / 1e9, "s"),
+    };
+    let decimals = 4 - value.log10().floor().clamp(0.0, 4.0) as usize;
+    format!("{value:.decimals$} {unit}")
+}
+
+/// Replaces the text between [`START_MARKER`] and [`END_MARKER`] in `document` with `section`,
+/// keeping the markers so that the section can be replaced again.
+pub fn splice(document: &str, section: &str) -> Result<String, String> {
+    let find_one = |marker: &str| match document.match_indices(marker).count() {
+        0 => Err(format!("no `{marker}` line")),
+        1 => Ok(document.find(marker).unwrap()),
+        _ => Err(format!("more than one `{marker}` line")),
+    };
+    let start = find_one(START_MARKER)? + START_MARKER.len();
+    let end = find_one(END_MARKER)?;
+    if end < start {
+        return Err(format!("`{END_MARKER}` comes before `{START_MARKER}`"));
+    }
+    let section = section.trim_end_matches('\n');
+    Ok(format!(
+        "{}\n{section}\n{}",
+        &document[..start],
+        &document[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn event(step: Step, day: u8, part: Option<u8>, ns: u64, answer: Option<&str>) -> Event {
+        let mut event = Event::new(step, day, "", Duration::from_nanos(ns));
+        event.part = part;
+        event.answer = answer.map(String::from);
+        event
+    }
+
+    #[test]
+    fn test_from_events() {
+        let events = [
+            event(Step::Fetch, 1, None, 5, None),
+            event(Step::Parse, 1, None, 100, None),
+            event(Step::Solve, 1, Some(1), 200, Some("3")),
+            event(Step::Solve, 1, Some(2), 300, Some("6")),
+            event(Step::Parse, 2, None, 10, None),
+            event(Step::Solve, 2, Some(1), 20, Some("old")),
+            // A later run of day 2 that did not finish replaces the earlier one.
+            event(Step::Parse, 2, None, 40, None),
+            event(Step::Solve, 2, Some(1), 50, Some("new")),
+        ];
+        let reports = from_events(&events);
+        assert_eq!(
+            reports,
+            [
+                DayReport {
+                    day: 1,
+                    answers: [Some("3".into()), Some("6".into())],
+                    parse_ns: Some(100),
+                    solve_ns: [Some(200), Some(300)],
+                },
+                DayReport {
+                    day: 2,
+                    answers: [Some("new".into()), None],
+                    parse_ns: Some(40),
+                    solve_ns: [Some(50), None],
+                },
+            ]
+        );
+        assert_eq!(reports[0].run_time_ns(), Some(600));
+        assert_eq!(reports[1].run_time_ns(), None);
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(664.45), "664.45 ns");
+        assert_eq!(format_time(11_617.0), "11.617 µs");
+        assert_eq!(format_time(1_463.5), "1.4635 µs");
+        assert_eq!(format_time(577_510_000.0), "577.51 ms");
+        assert_eq!(format_time(2_500_000_000.0), "2.5000 s");
+        assert_eq!(format_time(0.0), "0.0000 ns");
+    }
+
+    #[test]
+    fn test_render_table() {
+        let reports = [
+            DayReport {
+                day: 1,
+                answers: [Some("3".into()), Some("6".into())],
+                parse_ns: Some(1_000),
+                solve_ns: [Some(2_000), Some(3_000)],
+            },
+            DayReport {
+                day: 12,
+                answers: [Some("2".into()), Some("Day 12 has no part 2".into())],
+                parse_ns: Some(1_000),
+                solve_ns: [Some(1_000_000), Some(10)],
+            },
+        ];
+        let medians = BTreeMap::from([
+            ("Day 01/Part 1".to_string(), 1_500.0),
+            ("Day 01/Part 2".to_string(), 2_500.0),
+            ("Day 01/Parse".to_string(), 900.0),
+            ("Day 12/Part 1".to_string(), 900_000.0),
+        ]);
+        assert_eq!(
+            render_table(&reports, &medians),
+            "\
+| Puzzle    | Part 1 answer | Part 2 answer | Run time  | Part 1 median | Part 2 median | Total median |
+|-----------|---------------|---------------|-----------|---------------|---------------|--------------|
+| Day 01    | 3             | 6             | 6.0000 µs | 1.5000 µs     | 2.5000 µs     | 4.0000 µs    |
+| Day 12    | 2             |               | 1.0010 ms | 900.00 µs     |               | 900.00 µs    |
+| **Total** |               |               | 1.0070 ms |               |               | 904.00 µs    |
+"
+        );
+        let report = Report {
+            days: reports.to_vec(),
+            medians,
+        };
+        assert_eq!(
+            report.to_string(),
+            render_table(&report.days, &report.medians)
+        );
+        // Without benchmark results, the median columns are left empty.
+        let table = render_table(&reports[..1], &BTreeMap::new());
+        assert!(
+            table.contains(
+                "| Day 01    | 3             | 6             | 6.0000 µs |               |"
+            )
+        );
+    }
+
+    #[test]
+    fn test_splice() {
+        let document = format!("# Title\n\n{START_MARKER}\nold\ntable\n{END_MARKER}\n\nAfter.\n");
+        let spliced = splice(&document, "new\n").unwrap();
+        assert_eq!(
+            spliced,
+            format!("# Title\n\n{START_MARKER}\nnew\n{END_MARKER}\n\nAfter.\n")
+        );
+        assert_eq!(splice(&spliced, "new").unwrap(), spliced);
+    }
+
+    #[test]
+    fn test_splice_errors() {
+        assert_eq!(
+            splice("no markers", ""),
+            Err(format!("no `{START_MARKER}` line"))
+        );
+        assert_eq!(
+            splice(&format!("{START_MARKER}\n"), ""),
+            Err(format!("no `{END_MARKER}` line"))
+        );
+        assert_eq!(
+            splice(&format!("{END_MARKER}\n{START_MARKER}\n"), ""),
+            Err(format!("`{END_MARKER}` comes before `{START_MARKER}`"))
+        );
+        assert_eq!(
+            splice(&format!("{START_MARKER}\n{START_MARKER}\n{END_MARKER}"), ""),
+            Err(format!("more than one `{START_MARKER}` line"))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The step of a run that an [`Event`] records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Step {
+    /// Reading a day's input from its source.
+    Fetch,
+    /// Turning the input into the day's puzzle.
+    Parse,
+    /// Solving one part of the puzzle.
+    Solve,
+    /// Checking the answer to one part against the answers file.
+    Verify,
+}
+
+/// One line of a [`RunLog`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// When the step finished, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub event: Step,
+    pub day: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+    /// How long the step took, in nanoseconds.
+    pub duration_ns: u64,
+    /// The [`input_hash`] of the day's input, which tells runs on different inputs apart without
+    /// logging the inputs themselves.
+    pub input_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    /// Whether the answer matched the known one, for a verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct: Option<bool>,
+}
+
+impl Event {
+    /// Returns an event for a step of `day` on `input` that just finished after `duration`.
+    pub fn new(event: Step, day: u8, input: &str, duration: Duration) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis() as u64);
+        Event {
+            timestamp_ms,
+            event,
+            day,
+            part: None,
+            duration_ns: duration.as_nanos() as u64,
+            input_hash: input_hash(input),
+            answer: None,
+            correct: None,
+        }
+    }
+}
+
+/// A log of a run's steps for dashboards and other programs, written as JSON Lines: one
+/// [`Event`] object per line. The events are also kept in memory.
+pub struct RunLog {
+    out: Option<Box<dyn Write>>,
+    events: Vec<Event>,
+}
+
+impl RunLog {
+    /// Returns a log that writes to `out`.
+    pub fn new(out: impl Write + 'static) -> Self {
+        RunLog {
+            out: Some(Box::new(out)),
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns a log that only keeps its events in memory.
+    pub fn in_memory() -> Self {
+        RunLog {
+            out: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns a log that appends to the file at `path`, creating it if needed, so that one file
+    /// can collect many runs.
+    pub fn append(path: &Path) -> io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(RunLog::new(BufWriter::new(file)))
+    }
+
+    /// Writes `event` as one line, flushing it so that the log is complete even if a later step
+    /// panics.
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        self.events.push(event.clone());
+        let Some(out) = &mut self.out else {
+            return Ok(());
+        };
+        let line = serde_json::to_string(event).map_err(io::Error::other)?;
+        writeln!(out, "{line}")?;
+        out.flush()
+    }
+
+    /// Returns the events recorded so far, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// Reads the events in a log written by a [`RunLog`].
+pub fn read(path: &Path) -> Result<Vec<Event>, Box<dyn Error>> {
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: {e}", path.display(), i + 1).into())
+        })
+        .collect()
+}
+
+/// Returns the 64-bit FNV-1a hash of `input` in hex. Unlike the standard library's hashers it is
+/// the same on every platform and Rust version, so hashes from old logs stay comparable.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A writer whose output can still be read after it is moved into a [`RunLog`].
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+        assert_eq!(input_hash("foobar"), "85944171f73967e8");
+    }
+
+    #[test]
+    fn test_record() {
+        let buffer = SharedBuffer::default();
+        let mut log = RunLog::new(buffer.clone());
+        let mut event = Event::new(Step::Parse, 3, "", Duration::from_micros(5));
+        event.timestamp_ms = 1_764_547_200_000;
+        log.record(&event).unwrap();
+        event.event = Step::Solve;
+        event.part = Some(2);
+        event.answer = Some("42".to_string());
+        log.record(&event).unwrap();
+        let mut memory = RunLog::in_memory();
+        memory.record(&event).unwrap();
+        assert_eq!(memory.events(), [event]);
+        assert_eq!(
+            String::from_utf8(buffer.0.take()).unwrap(),
+            concat!(
+                r#"{"timestamp_ms":1764547200000,"event":"parse","day":3,"duration_ns":5000,"input_hash":"cbf29ce484222325"}"#,
+                "\n",
+                r#"{"timestamp_ms":1764547200000,"event":"solve","day":3,"part":2,"duration_ns":5000,"input_hash":"cbf29ce484222325","answer":"42"}"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use crate::answers::AnswerFile;
+use crate::registry::DayEntry;
+use crate::render::VizFormat;
+use crate::run_log::{self, Event, RunLog, Step};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Somewhere puzzle inputs can be read from, such as the local puzzle directory and the Advent of
+/// Code website, or an in-memory map in tests.
+pub trait InputSource {
+    /// Returns the input for the given day.
+    fn get_input(&self, day: u8) -> Result<String, Box<dyn Error>>;
+}
+
+/// The answers for one day. Every output format is rendered from these, and the JSON format is
+/// their serialization.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunResult {
+    pub day: u8,
+    pub part1: String,
+    pub part2: String,
+    /// Where the day's visualization was written, if one was asked for and the day has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visualization: Option<PathBuf>,
+}
+
+/// How results are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One `Day NN Part N: answer` line per answer.
+    Text,
+    /// A JSON array with one object per day.
+    Json,
+    /// A CSV table with a header row and one row per day.
+    Csv,
+    /// A Markdown table with one row per day.
+    Markdown,
+    /// A MessagePack array with one map per day, with the same fields as the JSON objects.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+#[cfg(not(feature = "msgpack"))]
+const FORMAT_NAMES: &str = "'text', 'json', 'csv' or 'markdown'";
+#[cfg(feature = "msgpack")]
+const FORMAT_NAMES: &str = "'text', 'json', 'csv', 'markdown' or 'msgpack'";
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" => Ok(Format::Markdown),
+            #[cfg(feature = "msgpack")]
+            "msgpack" => Ok(Format::MessagePack),
+            _ => Err(format!(
+                "unknown format '{s}' (expected one of {FORMAT_NAMES})"
+            )),
+        }
+    }
+}
+
+/// Solves each of `days` on its input from `source`, writing visualizations into `visualize_dir`
+/// when it is given. Stops at the first day whose input cannot be read.
+pub fn run(
+    days: &[DayEntry],
+    source: &impl InputSource,
+    visualize_dir: Option<&Path>,
+) -> Result<Vec<RunResult>, Box<dyn Error>> {
+    let mut log = RunLog::in_memory();
+    run_logged(days, source, visualize_dir, VizFormat::Native, &mut log)
+}
+
+/// Like [`run`], rendering the visualizations in `viz_format` and recording how long each day
+/// takes to fetch, parse and solve in `log`.
+pub fn run_logged(
+    days: &[DayEntry],
+    source: &impl InputSource,
+    visualize_dir: Option<&Path>,
+    viz_format: VizFormat,
+    log: &mut RunLog,
+) -> Result<Vec<RunResult>, Box<dyn Error>> {
+    let mut results = Vec::with_capacity(days.len());
+    for day in days {
+        let n = day.number;
+        let start = Instant::now();
+        let input = source.get_input(n).map_err(|e| format!("Day {n}: {e}"))?;
+        log.record(&Event::new(Step::Fetch, n, &input, start.elapsed()))?;
+        let start = Instant::now();
+        let puzzle = (day.create)(&input);
+        log.record(&Event::new(Step::Parse, n, &input, start.elapsed()))?;
+        let visualization = match visualize_dir
+            .and_then(|dir| Some((dir, viz_format.render(&*puzzle)?)))
+        {
+            Some((dir, visualization)) => Some(visualization.write(dir, &format!("day{n:02}"))?),
+            None => None,
+        };
+        results.push(RunResult {
+            day: n,
+            part1: solve_logged(log, n, 1, &input, || puzzle.solve_part_1())?,
+            part2: solve_logged(log, n, 2, &input, || puzzle.solve_part_2())?,
+            visualization,
+        });
+    }
+    Ok(results)
+}
+
+/// Solves `part` of `day` with `solve`, recording the answer and how long it took in `log`.
+fn solve_logged(
+    log: &mut RunLog,
+    day: u8,
+    part: u8,
+    input: &str,
+    solve: impl FnOnce() -> String,
+) -> Result<String, Box<dyn Error>> {
+    let start = Instant::now();
+    let answer = solve();
+    let mut event = Event::new(Step::Solve, day, input, start.elapsed());
+    event.part = Some(part);
+    event.answer = Some(answer.clone());
+    log.record(&event)?;
+    Ok(answer)
+}
+
+/// Checks `results` against the known answers in `answers`, recording each check in `log`, and
+/// returns a message for every wrong answer. Parts with no known answer are not checked. A day
+/// whose answers were recorded for a different input than the one in `source` fails, since its
+/// answers cannot be checked.
+pub fn verify(
+    results: &[RunResult],
+    answers: &AnswerFile,
+    source: &impl InputSource,
+    log: &mut RunLog,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut failures = Vec::new();
+    for result in results {
+        let n = result.day;
+        let Some(expected) = answers.get(n) else {
+            continue;
+        };
+        let input = source.get_input(n).map_err(|e| format!("Day {n}: {e}"))?;
+        if !expected.is_for_input(&run_log::input_hash(&input)) {
+            failures.push(format!(
+                "Day {n:02}: the known answers are for a different input"
+            ));
+            continue;
+        }
+        for (part, actual) in [(1, &result.part1), (2, &result.part2)] {
+            let Some(expected) = expected.part(part) else {
+                continue;
+            };
+            let correct = expected == actual;
+            let mut event = Event::new(Step::Verify, n, &input, Duration::ZERO);
+            event.part = Some(part);
+            event.answer = Some(actual.clone());
+            event.correct = Some(correct);
+            log.record(&event)?;
+            if !correct {
+                failures.push(format!(
+                    "Day {n:02} Part {part}: expected {expected}, got {actual}"
+                ));
+            }
+        }
+    }
+    Ok(failures)
+}
+
+/// Formats `results` as text for printing.
+///
+/// # Panics
+///
+/// If `format` is a binary format, which only [`encode`] can write.
+pub fn render(results: &[RunResult], format: Format) -> String {
+    match format {
+        Format::Text => render_text(results),
+        Format::Json => render_json(results),
+        Format::Csv => render_csv(results),
+        Format::Markdown => render_markdown(results),
+        #[cfg(feature = "msgpack")]
+        Format::MessagePack => panic!("MessagePack is a binary format; use runner::encode"),
+    }
+}
+
+/// Encodes `results` in `format` as the bytes to write out, whether the format is text or
+/// binary.
+pub fn encode(results: &[RunResult], format: Format) -> Vec<u8> {
+    #[cfg(feature = "msgpack")]
+    if format == Format::MessagePack {
+        return rmp_serde::to_vec_named(results).expect("a result is always valid MessagePack");
+    }
+    render(results, format).into_bytes()
+}
+
+fn render_text(results: &[RunResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        let n = result.day;
+        writeln!(out, "Day {n:02} Part 1: {}", result.part1).unwrap();
+        writeln!(out, "Day {n:02} Part 2: {}", result.part2).unwrap();
+        if let Some(path) = &result.visualization {
+            writeln!(out, "Day {n:02} Visualization: {}", path.display()).unwrap();
+        }
+    }
+    out
+}
+
+/// Writes one result per line, so the output is easy to diff and grep as well as to parse.
+fn render_json(results: &[RunResult]) -> String {
+    let mut out = String::from("[");
+    for (i, result) in results.iter().enumerate() {
+        out.push_str(if i == 0 { "\n  " } else { ",\n  " });
+        out.push_str(&serde_json::to_string(result).expect("a result is always valid JSON"));
+    }
+    out.push_str(if results.is_empty() { "]\n" } else { "\n]\n" });
+    out
+}
+
+fn render_csv(results: &[RunResult]) -> String {
+    let mut out = String::from("day,part1,part2,visualization\n");
+    for result in results {
+        let path = result
+            .visualization
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{},{},{},{}",
+            result.day,
+            csv_field(&result.part1),
+            csv_field(&result.part2),
+            csv_field(&path)
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// The visualization column is only included when some day has a visualization.
+fn render_markdown(results: &[RunResult]) -> String {
+    let visualized = results.iter().any(|result| result.visualization.is_some());
+    let mut out = String::new();
+    if visualized {
+        out.push_str("| Day | Part 1 | Part 2 | Visualization |\n");
+        out.push_str("|-----|--------|--------|---------------|\n");
+    } else {
+        out.push_str("| Day | Part 1 | Part 2 |\n");
+        out.push_str("|-----|--------|--------|\n");
+    }
+    for result in results {
+        write!(
+            out,
+            "| {:02} | {} | {} |",
+            result.day,
+            markdown_cell(&result.part1),
+            markdown_cell(&result.part2)
+        )
+        .unwrap();
+        if visualized {
+            let path = result
+                .visualization
+                .as_ref()
+                .map(|path| markdown_cell(&path.display().to_string()))
+                .unwrap_or_default();
+            write!(out, " {path} |").unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Quotes `s` for a CSV row if it contains a comma, quote or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Escapes `s` so it stays within one Markdown table cell.
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("markdown".parse(), Ok(Format::Markdown));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trips() {
+        assert_eq!("msgpack".parse(), Ok(Format::MessagePack));
+        let results = sample_results();
+        let bytes = encode(&results, Format::MessagePack);
+        // An array of three results, the first a map of its four fields but the visualization,
+        // starting with `day`.
+        assert_eq!(bytes[..6], [0x93, 0x83, 0xa3, b'd', b'a', b'y']);
+        let decoded: Vec<RunResult> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, results);
+    }
+
+    #[test]
+    fn test_csv_field_quotes() {
+        assert_eq!(csv_field("123"), "123");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_markdown_cell_escapes() {
+        assert_eq!(markdown_cell("a|b\nc"), "a\\|b<br>c");
+    }
+
+    /// A fixed set of results covering a visualization and a part with no answer.
+    fn sample_results() -> Vec<RunResult> {
+        vec![
+            RunResult {
+                day: 1,
+                part1: "1118".into(),
+                part2: "6289".into(),
+                visualization: None,
+            },
+            RunResult {
+                day: 11,
+                part1: "470".into(),
+                part2: "384151614084875".into(),
+                visualization: Some(PathBuf::from("out/day11.dot")),
+            },
+            RunResult {
+                day: 12,
+                part1: "519".into(),
+                part2: "Day 12 has no part 2".into(),
+                visualization: Some(PathBuf::from("out/day12.svg")),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        insta::assert_snapshot!("text", render(&sample_results(), Format::Text));
+    }
+
+    #[test]
+    fn test_snapshot_json() {
+        insta::assert_snapshot!("json", render(&sample_results(), Format::Json));
+    }
+
+    #[test]
+    fn test_snapshot_csv() {
+        insta::assert_snapshot!("csv", render(&sample_results(), Format::Csv));
+    }
+
+    #[test]
+    fn test_snapshot_markdown() {
+        insta::assert_snapshot!("markdown", render(&sample_results(), Format::Markdown));
+        insta::assert_snapshot!(
+            "markdown_without_visualizations",
+            render(&sample_results()[..1], Format::Markdown)
+        );
+    }
+
+    #[test]
+    fn test_json_round_trips() {
+        let mut results = sample_results();
+        results[0].part2 = "a\"b\\c\nd\u{1}".into();
+        let parsed: Vec<RunResult> = serde_json::from_str(&render(&results, Format::Json)).unwrap();
+        assert_eq!(parsed, results);
+    }
+
+    #[test]
+    fn test_encode_text() {
+        let results = sample_results();
+        for format in [Format::Text, Format::Json, Format::Csv, Format::Markdown] {
+            assert_eq!(
+                encode(&results, format),
+                render(&results, format).into_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render(&[], Format::Text), "");
+        assert_eq!(render(&[], Format::Json), "[]\n");
+    }
+}
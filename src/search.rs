@@ -0,0 +1,279 @@
+use ahash::AHashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A path found by a search, with its total cost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    /// The states along the path, from the start to the goal inclusive.
+    pub states: Vec<S>,
+}
+
+/// The states reached by a search, each with the index of the state it was reached from.
+struct Tree<S> {
+    states: Vec<(S, usize)>,
+    index: AHashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Tree<S> {
+    fn new(start: S) -> Self {
+        Self {
+            states: vec![(start.clone(), 0)],
+            index: AHashMap::from_iter([(start, 0)]),
+        }
+    }
+
+    /// Returns the index of `state` and whether it is new, recording `parent` for a new state.
+    fn add(&mut self, state: S, parent: usize) -> (usize, bool) {
+        if let Some(&i) = self.index.get(&state) {
+            return (i, false);
+        }
+        let i = self.states.len();
+        self.index.insert(state.clone(), i);
+        self.states.push((state, parent));
+        (i, true)
+    }
+
+    /// Returns the states from the start to the state with index `i`.
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].0.clone()];
+        while i != 0 {
+            i = self.states[i].1;
+            path.push(self.states[i].0.clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Finds a path from `start` to a state satisfying `is_goal` with the fewest steps, by
+/// breadth-first search. Returns `None` if no goal can be reached.
+///
+/// Time complexity: O(V + E) where V and E are the states and steps reachable before the goal
+/// Auxiliary space complexity: O(V)
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut tree = Tree::new(start);
+    let mut depth = vec![0];
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&tree.states[i].0) {
+            return Some(Path {
+                cost: depth[i],
+                states: tree.path_to(i),
+            });
+        }
+        for next in neighbors(&tree.states[i].0) {
+            let (j, new) = tree.add(next, i);
+            if new {
+                depth.push(depth[i] + 1);
+                queue.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+/// Finds a cheapest path from `start` to a state satisfying `is_goal`, where `neighbors` returns
+/// each state reachable in one step with the cost of that step. Costs must be non-negative, and
+/// `C::default()` must be zero. Returns `None` if no goal can be reached.
+///
+/// Time complexity: O((V + E) log V) where V and E are the states and steps reachable before the
+/// goal
+/// Auxiliary space complexity: O(V + E)
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Finds a cheapest path like [`dijkstra`], exploring states in order of their cost so far plus
+/// `heuristic`'s estimate of the cost left. The path is cheapest as long as the heuristic never
+/// overestimates; one that is also consistent (never drops by more than a step costs) expands each
+/// state at most once.
+///
+/// Time complexity: O((V + E) log V) for a consistent heuristic, where V and E are the states and
+/// steps explored
+/// Auxiliary space complexity: O(V + E)
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let zero = C::default();
+    let mut tree = Tree::new(start);
+    let mut cost = vec![zero];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&tree.states[0].0), zero, 0))]);
+    while let Some(Reverse((_, g, i))) = heap.pop() {
+        if g > cost[i] {
+            continue;
+        }
+        if is_goal(&tree.states[i].0) {
+            return Some(Path {
+                cost: g,
+                states: tree.path_to(i),
+            });
+        }
+        for (next, step) in neighbors(&tree.states[i].0) {
+            let g_next = g + step;
+            let (j, new) = tree.add(next, i);
+            if new {
+                cost.push(g_next);
+            } else if g_next < cost[j] {
+                cost[j] = g_next;
+                tree.states[j].1 = i;
+            } else {
+                continue;
+            }
+            let estimate = g_next + heuristic(&tree.states[j].0);
+            heap.push(Reverse((estimate, g_next, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// Returns the open cells next to `(r, c)` in a grid where `#` is a wall.
+    fn open_neighbors(grid: &[&str], (r, c): (usize, usize)) -> Vec<(usize, usize)> {
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .map(|(dr, dc)| (r as isize + dr, c as isize + dc))
+            .filter(|&(r, c)| {
+                r >= 0
+                    && c >= 0
+                    && grid
+                        .get(r as usize)
+                        .and_then(|row| row.as_bytes().get(c as usize))
+                        .is_some_and(|&b| b != b'#')
+            })
+            .map(|(r, c)| (r as usize, c as usize))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = ["..#....", ".##.##.", "...#...", ".#...#."];
+        let path = bfs((0, 0), |&p| open_neighbors(&grid, p), |&p| p == (0, 3)).unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.states.len(), 16);
+        assert_eq!((path.states[0], path.states[15]), ((0, 0), (0, 3)));
+        for pair in path.states.windows(2) {
+            assert!(open_neighbors(&grid, pair[0]).contains(&pair[1]));
+        }
+        let trivial = bfs((0, 0), |&p| open_neighbors(&grid, p), |_| true).unwrap();
+        assert_eq!(trivial.states, vec![(0, 0)]);
+        assert!(bfs((0, 0), |&p| open_neighbors(&grid, p), |&p| p == (0, 2)).is_none());
+    }
+
+    /// Returns the cheapest cost from node 0 to every node by relaxing every edge until nothing
+    /// changes.
+    fn bellman_ford(n: usize, edges: &[(usize, usize, u64)]) -> Vec<Option<u64>> {
+        let mut best = vec![None; n];
+        best[0] = Some(0);
+        for _ in 0..n {
+            for &(a, b, w) in edges {
+                if let Some(d) = best[a]
+                    && best[b].is_none_or(|e| d + w < e)
+                {
+                    best[b] = Some(d + w);
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_dijkstra_matches_bellman_ford() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..200 {
+            let n = rng.random_range(1..20);
+            let edges: Vec<(usize, usize, u64)> = (0..rng.random_range(0..4 * n))
+                .map(|_| {
+                    let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+                    (a, b, rng.random_range(0..10u64))
+                })
+                .collect();
+            let neighbors = |&a: &usize| {
+                edges
+                    .iter()
+                    .filter(move |e| e.0 == a)
+                    .map(|&(_, b, w)| (b, w))
+            };
+            for (goal, expected) in bellman_ford(n, &edges).into_iter().enumerate() {
+                let path = dijkstra(0, neighbors, |&a| a == goal);
+                assert_eq!(path.as_ref().map(|p| p.cost), expected, "{edges:?}");
+                if let Some(path) = path {
+                    let mut total = 0;
+                    for pair in path.states.windows(2) {
+                        total += neighbors(&pair[0])
+                            .filter(|&(b, _)| b == pair[1])
+                            .map(|(_, w)| w)
+                            .min()
+                            .unwrap();
+                    }
+                    assert_eq!(total, path.cost);
+                    assert_eq!(*path.states.last().unwrap(), goal);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let mut rng = crate::seed::rng();
+        for _ in 0..100 {
+            let (h, w) = (rng.random_range(1..12usize), rng.random_range(1..12usize));
+            // Entering a cell costs its weight, which is at least 1, so the Manhattan distance to
+            // the goal never overestimates.
+            let weights: Vec<Vec<u64>> = (0..h)
+                .map(|_| (0..w).map(|_| rng.random_range(1..10u64)).collect())
+                .collect();
+            let goal = (rng.random_range(0..h), rng.random_range(0..w));
+            let neighbors = |&(r, c): &(usize, usize)| {
+                let weights = &weights;
+                [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                    .into_iter()
+                    .map(move |(dr, dc)| (r as isize + dr, c as isize + dc))
+                    .filter(move |&(r, c)| r >= 0 && c >= 0 && r < h as isize && c < w as isize)
+                    .map(move |(r, c)| ((r as usize, c as usize), weights[r as usize][c as usize]))
+            };
+            let manhattan =
+                |&(r, c): &(usize, usize)| (r.abs_diff(goal.0) + c.abs_diff(goal.1)) as u64;
+            let by_astar = astar((0, 0), neighbors, manhattan, |&p| p == goal).unwrap();
+            let by_dijkstra = dijkstra((0, 0), neighbors, |&p| p == goal).unwrap();
+            assert_eq!(by_astar.cost, by_dijkstra.cost);
+            let entered: u64 = by_astar.states[1..]
+                .iter()
+                .map(|&(r, c)| weights[r][c])
+                .sum();
+            assert_eq!(entered, by_astar.cost);
+        }
+    }
+}
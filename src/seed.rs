@@ -0,0 +1,65 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// The environment variable that fixes the seed of the randomized tests and the input generator,
+/// so that a failure seen with one seed can be reproduced.
+pub const SEED_VAR: &str = "AOC_SEED";
+
+/// Returns the seed given by `$AOC_SEED`, or `None` if it is unset. Panics if it is not a number.
+pub fn from_env() -> Option<u64> {
+    parse_seed(std::env::var(SEED_VAR).ok().as_deref())
+}
+
+fn parse_seed(var: Option<&str>) -> Option<u64> {
+    var.map(|seed| {
+        seed.trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{SEED_VAR} must be a number, found `{seed}`"))
+    })
+}
+
+/// Returns the seed for a randomized test: `$AOC_SEED` if it is set, and otherwise a fresh random
+/// one. Either way it is printed to standard error, which the test harness shows when the test
+/// fails, so that the failure can be repeated by setting `AOC_SEED` to it.
+pub fn seed() -> u64 {
+    let seed = from_env().unwrap_or_else(|| RandomState::new().build_hasher().finish());
+    eprintln!("using seed {seed} (set {SEED_VAR}={seed} to reproduce)");
+    seed
+}
+
+/// Returns a random number generator seeded by [`seed`], for the unit tests.
+#[cfg(test)]
+pub(crate) fn rng() -> rand::rngs::StdRng {
+    rand::SeedableRng::seed_from_u64(seed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed(None), None);
+        assert_eq!(parse_seed(Some("42")), Some(42));
+        assert_eq!(parse_seed(Some(" 18446744073709551615\n")), Some(u64::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "AOC_SEED must be a number, found `abc`")]
+    fn test_parse_seed_rejects_garbage() {
+        parse_seed(Some("abc"));
+    }
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let draw = |seed| {
+            let mut rng: rand::rngs::StdRng = rand::SeedableRng::seed_from_u64(seed);
+            (0..8)
+                .map(|_| rng.random_range(0..u64::MAX))
+                .collect::<Vec<_>>()
+        };
+        let seed = seed();
+        assert_eq!(draw(seed), draw(seed));
+    }
+}
@@ -0,0 +1,157 @@
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// A whitespace-aligned table of characters, such as a worksheet of numbers laid out in columns.
+///
+/// Lines are expanded into display cells so that rows line up the way they render in a terminal,
+/// and every row is padded with spaces to the width of the widest one.
+pub struct Table {
+    rows: Vec<Vec<char>>,
+    width: usize,
+}
+
+impl Table {
+    /// Builds a table from the given lines.
+    pub fn new<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut rows: Vec<Vec<char>> = lines.into_iter().map(to_cells).collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, ' ');
+        }
+        Self { rows, width }
+    }
+
+    /// Returns the number of display columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the cells of the given row.
+    pub fn row(&self, row: usize) -> &[char] {
+        &self.rows[row]
+    }
+
+    /// Returns the maximal runs of columns in which at least one row holds an ASCII digit, in
+    /// left-to-right order.
+    ///
+    /// Time complexity: O(R * C) where R is the number of rows and C the number of columns.
+    /// Auxiliary space complexity: O(C)
+    pub fn digit_column_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut start: Option<usize> = None;
+        for col in 0..self.width {
+            let has_digit = self.rows.iter().any(|row| row[col].is_ascii_digit());
+            match (start, has_digit) {
+                (None, true) => start = Some(col),
+                (Some(s), false) => {
+                    ranges.push(s..col);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            ranges.push(s..self.width);
+        }
+        ranges
+    }
+
+    /// Returns the number each row forms from its digits within `cols`, read left-to-right, in
+    /// top-to-bottom order. Rows without any digit in `cols` are skipped.
+    ///
+    /// Returns `None` if a number does not fit in a u128.
+    pub fn row_numbers(&self, cols: Range<usize>) -> Option<Vec<u128>> {
+        self.rows
+            .iter()
+            .map(|row| &row[cols.clone()])
+            .filter(|cells| cells.iter().any(char::is_ascii_digit))
+            .map(|cells| digits_to_number(cells.iter().filter_map(|c| c.to_digit(10))))
+            .collect()
+    }
+
+    /// Returns the number formed by the digits of column `col`, read top-to-bottom.
+    ///
+    /// Returns `None` if the number does not fit in a u128.
+    pub fn column_number(&self, col: usize) -> Option<u128> {
+        digits_to_number(self.rows.iter().filter_map(|row| row[col].to_digit(10)))
+    }
+}
+
+/// Expands a line into display cells.
+///
+/// Wide characters (e.g. full-width CJK) occupy two cells, with the second cell padded by a space.
+/// Zero-width and control characters occupy none.
+pub fn to_cells(line: &str) -> Vec<char> {
+    let mut cells = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        let width = c.width().unwrap_or(0);
+        if width > 0 {
+            cells.push(c);
+            cells.extend(std::iter::repeat_n(' ', width - 1));
+        }
+    }
+    cells
+}
+
+/// Concatenates decimal digits into a number, returning `None` on u128 overflow.
+fn digits_to_number(mut digits: impl Iterator<Item = u32>) -> Option<u128> {
+    digits.try_fold(0u128, |n, d| n.checked_mul(10)?.checked_add(d as u128))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_are_padded() {
+        let table = Table::new(["12", "3", ""]);
+        assert_eq!(table.width(), 2);
+        assert_eq!(table.height(), 3);
+        assert_eq!(table.row(1), &['3', ' ']);
+        assert_eq!(table.row(2), &[' ', ' ']);
+    }
+
+    #[test]
+    fn test_wide_characters_take_two_cells() {
+        assert_eq!(to_cells("甲1"), vec!['甲', ' ', '1']);
+        assert_eq!(to_cells("a\u{0301}b"), vec!['a', 'b']);
+    }
+
+    #[test]
+    fn test_digit_column_ranges() {
+        let table = Table::new(["12  3 ", " 4 56 ", "      "]);
+        assert_eq!(table.digit_column_ranges(), vec![0..2, 3..5]);
+        let table = Table::new(["1 2", "   "]);
+        assert_eq!(table.digit_column_ranges(), vec![0..1, 2..3]);
+        let table = Table::new(["   "]);
+        assert!(table.digit_column_ranges().is_empty());
+    }
+
+    #[test]
+    fn test_row_numbers() {
+        let table = Table::new(["12 7", " 4  ", "    ", "3 5 "]);
+        assert_eq!(table.row_numbers(0..2), Some(vec![12, 4, 3]));
+        assert_eq!(table.row_numbers(3..4), Some(vec![7]));
+    }
+
+    #[test]
+    fn test_column_number() {
+        let table = Table::new(["1 ", "  ", "2 ", "3 "]);
+        assert_eq!(table.column_number(0), Some(123));
+        assert_eq!(table.column_number(1), Some(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let digits = "9".repeat(39);
+        let table = Table::new([digits.as_str()]);
+        assert_eq!(table.row_numbers(0..39), None);
+        let table = Table::new([&digits[..38]]);
+        assert!(table.row_numbers(0..38).is_some());
+    }
+}
@@ -0,0 +1,504 @@
+use std::fmt::Write;
+
+/// Generates a valid random input for `day`, or `None` if the day has no generator. The same
+/// `day`, `size` and `seed` always give the same input.
+///
+/// What `size` counts depends on the day: rotations (day 1), ranges (day 2), banks of 100
+/// batteries (day 3), rows and columns (day 4), fresh ranges (day 5), problems (day 6), columns,
+/// with one more row than that (day 7), junction boxes (day 8), red tiles, rounded up to a multiple
+/// of 4 (day 9), machines (day 10), devices (day 11) and regions (day 12).
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let mut rng = Rng::new(seed ^ (u64::from(day) << 56));
+    let size = size.max(1);
+    let input = match day {
+        1 => day01(&mut rng, size),
+        2 => day02(&mut rng, size),
+        3 => day03(&mut rng, size),
+        4 => day04(&mut rng, size),
+        5 => day05(&mut rng, size),
+        6 => day06(&mut rng, size),
+        7 => day07(&mut rng, size),
+        8 => day08(&mut rng, size),
+        9 => day09(&mut rng, size),
+        10 => day10(&mut rng, size),
+        11 => day11(&mut rng, size),
+        12 => day12(&mut rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// A SplitMix64 generator. It is written out here rather than taken from `rand` so that a seed
+/// keeps giving the same input across dependency upgrades.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut x = self.0;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+
+    /// Returns a value in `lo..=hi`.
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo + 1)
+    }
+
+    /// Returns an index in `0..n`.
+    fn index(&mut self, n: usize) -> usize {
+        self.range(0, n as u64 - 1) as usize
+    }
+
+    /// Returns true with probability `p`.
+    fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+fn day01(rng: &mut Rng, rotations: usize) -> String {
+    (0..rotations)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 'L' } else { 'R' };
+            format!("{direction}{}", rng.range(1, 999))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Disjoint ranges of IDs up to ten digits long, in random order.
+fn day02(rng: &mut Rng, ranges: usize) -> String {
+    let slot = 9_999_999_999 / ranges as u64;
+    let mut parts: Vec<String> = (0..ranges as u64)
+        .map(|i| {
+            let start = 1 + i * slot + rng.range(0, slot / 2);
+            let end = start + rng.range(0, (slot / 2).saturating_sub(1).min(1_000_000));
+            format!("{start}-{end}")
+        })
+        .collect();
+    rng.shuffle(&mut parts);
+    parts.join(",")
+}
+
+fn day03(rng: &mut Rng, banks: usize) -> String {
+    (0..banks)
+        .map(|_| {
+            (0..100)
+                .map(|_| char::from(b'0' + rng.range(1, 9) as u8))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn day04(rng: &mut Rng, side: usize) -> String {
+    (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| if rng.chance(0.6) { '@' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Possibly overlapping fresh ranges, followed by five IDs per range.
+fn day05(rng: &mut Rng, ranges: usize) -> String {
+    const MAX: u64 = 500_000_000_000_000;
+    let mut out = String::new();
+    for _ in 0..ranges {
+        let start = rng.range(1, MAX);
+        let end = start + rng.range(0, MAX / ranges as u64);
+        writeln!(out, "{start}-{end}").unwrap();
+    }
+    for _ in 0..5 * ranges {
+        write!(out, "\n{}", rng.range(1, MAX)).unwrap();
+    }
+    out
+}
+
+/// Problems of four numbers each, every number aligned to either side of its problem's columns.
+fn day06(rng: &mut Rng, problems: usize) -> String {
+    let mut rows = vec![String::new(); 5];
+    for i in 0..problems {
+        if i > 0 {
+            rows.iter_mut().for_each(|row| row.push(' '));
+        }
+        let numbers: Vec<String> = (0..4)
+            .map(|_| {
+                let digits = rng.range(1, 4) as u32;
+                rng.range(10u64.pow(digits - 1), 10u64.pow(digits) - 1)
+                    .to_string()
+            })
+            .collect();
+        let width = numbers.iter().map(String::len).max().unwrap();
+        for (row, number) in rows.iter_mut().zip(&numbers) {
+            if rng.chance(0.5) {
+                write!(row, "{number:<width$}").unwrap();
+            } else {
+                write!(row, "{number:>width$}").unwrap();
+            }
+        }
+        let op = if rng.chance(0.5) { '+' } else { '*' };
+        write!(rows[4], "{op:<width$}").unwrap();
+    }
+    rows.join("\n")
+}
+
+/// A manifold with the source centred on the top row and splitters on every other row. The
+/// splitters thin out as the manifold grows, so the number of timelines stays within a `u128`.
+fn day07(rng: &mut Rng, width: usize) -> String {
+    let height = width + 1;
+    let splitter_rows = (height / 2).max(1);
+    let p = (40.0 / splitter_rows as f64).min(0.3);
+    (0..height)
+        .map(|row| {
+            (0..width)
+                .map(|col| match row {
+                    0 if col == width / 2 => 'S',
+                    _ if row % 2 == 0 && row > 0 && rng.chance(p) => '^',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// At least two junction boxes, since part 2 needs a pair to connect.
+fn day08(rng: &mut Rng, boxes: usize) -> String {
+    (0..boxes.max(2))
+        .map(|_| {
+            let [x, y, z] = [(); 3].map(|_| rng.range(0, 99_999));
+            format!("{x},{y},{z}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A rectilinear polygon made of side-by-side columns, each spanning from a random bottom edge
+/// below the middle to a random top edge above it. Walking the bottom edges left to right and the
+/// top edges back again gives four corners per column.
+fn day09(rng: &mut Rng, tiles: usize) -> String {
+    const MID: u64 = 50_000;
+    let columns = tiles.div_ceil(4).max(1);
+    let step = 99_000 / columns as u64;
+    let xs: Vec<u64> = (0..=columns as u64)
+        .map(|i| 1 + i * step + rng.range(0, step / 4))
+        .collect();
+    let mut bottoms: Vec<u64> = Vec::with_capacity(columns);
+    let mut tops: Vec<u64> = Vec::with_capacity(columns);
+    for i in 0..columns {
+        // Neighbouring columns need different edges, or the corner between them is not a corner.
+        let mut bottom = rng.range(1, MID - 2);
+        let mut top = rng.range(MID + 1, 2 * MID - 1);
+        if i > 0 && bottom == bottoms[i - 1] {
+            bottom += 1;
+        }
+        if i > 0 && top == tops[i - 1] {
+            top -= 1;
+        }
+        bottoms.push(bottom);
+        tops.push(top);
+    }
+    let mut corners = Vec::with_capacity(4 * columns);
+    for i in 0..columns {
+        corners.push((xs[i], bottoms[i]));
+        corners.push((xs[i + 1], bottoms[i]));
+    }
+    for i in (0..columns).rev() {
+        corners.push((xs[i + 1], tops[i]));
+        corners.push((xs[i], tops[i]));
+    }
+    corners
+        .into_iter()
+        .map(|(x, y)| format!("{x},{y}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Machines whose light diagrams and joltage targets are both reachable, built by pressing random
+/// buttons a random number of times.
+fn day10(rng: &mut Rng, machines: usize) -> String {
+    let mut out = String::new();
+    for i in 0..machines {
+        let lights = rng.range(4, 10) as usize;
+        let mut buttons: Vec<Vec<usize>> = (0..rng.range(lights as u64 - 2, lights as u64 + 3))
+            .map(|_| {
+                let mut button: Vec<usize> = (0..lights).filter(|_| rng.chance(0.4)).collect();
+                if button.is_empty() {
+                    button.push(rng.index(lights));
+                }
+                button
+            })
+            .collect();
+        for light in 0..lights {
+            if !buttons.iter().any(|button| button.contains(&light)) {
+                let button = rng.index(buttons.len());
+                buttons[button].push(light);
+                buttons[button].sort_unstable();
+            }
+        }
+        let mut diagram = vec![false; lights];
+        let mut joltages = vec![0; lights];
+        for button in &buttons {
+            let presses = rng.range(0, 20);
+            let toggled = rng.chance(0.5);
+            for &light in button {
+                joltages[light] += presses;
+                diagram[light] ^= toggled;
+            }
+        }
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push('[');
+        out.extend(diagram.iter().map(|&on| if on { '#' } else { '.' }));
+        out.push(']');
+        for button in &buttons {
+            let lights: Vec<String> = button.iter().map(usize::to_string).collect();
+            write!(out, " ({})", lights.join(",")).unwrap();
+        }
+        let joltages: Vec<String> = joltages.iter().map(u64::to_string).collect();
+        write!(out, " {{{}}}", joltages.join(",")).unwrap();
+    }
+    out
+}
+
+/// A layered graph where each device feeds one or two devices of the next layer and the last
+/// layer feeds `out`. `svr` and `you` are in the first layer, and `fft` and `dac` a third and two
+/// thirds of the way down, fed by every device of the layer above so both parts have paths. There
+/// are at most 40 layers, so the path counts fit in a `usize`.
+fn day11(rng: &mut Rng, devices: usize) -> String {
+    const RESERVED: [&str; 5] = ["you", "svr", "fft", "dac", "out"];
+    let layers = devices.div_ceil(8).clamp(3, 40);
+    let mut names: Vec<String> = (0..)
+        .map(device_name)
+        .filter(|name| !RESERVED.contains(&name.as_str()))
+        .take(devices.saturating_sub(4).max(layers))
+        .collect();
+    rng.shuffle(&mut names);
+    let mut graph: Vec<Vec<String>> = vec![Vec::new(); layers];
+    for (i, name) in names.into_iter().enumerate() {
+        graph[i % layers].push(name);
+    }
+    graph[0].extend(["svr".to_string(), "you".to_string()]);
+    graph[layers / 3].push("fft".to_string());
+    graph[2 * layers / 3].push("dac".to_string());
+    let mut lines = Vec::new();
+    for (layer, devices) in graph.iter().enumerate() {
+        for device in devices {
+            let outputs: Vec<&str> = match graph.get(layer + 1) {
+                None => vec!["out"],
+                Some(next) => {
+                    let first = match next.last().map(String::as_str) {
+                        Some(waypoint @ ("fft" | "dac")) => waypoint,
+                        _ => next[rng.index(next.len())].as_str(),
+                    };
+                    let mut outputs = vec![first];
+                    let other = next[rng.index(next.len())].as_str();
+                    if rng.chance(0.5) && other != outputs[0] {
+                        outputs.push(other);
+                    }
+                    outputs
+                }
+            };
+            lines.push(format!("{device}: {}", outputs.join(" ")));
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// Returns the `i`th name of three or more lowercase letters.
+fn device_name(i: usize) -> String {
+    let mut name = Vec::new();
+    let mut i = i;
+    while name.len() < 3 || i > 0 {
+        name.push(b'a' + (i % 26) as u8);
+        i /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+/// Six random presents of seven cells, and regions that either have room for every
+/// present in its own 3x3 block or have less area than the presents cover, like the real input.
+fn day12(rng: &mut Rng, regions: usize) -> String {
+    let mut out = String::new();
+    let mut areas = Vec::new();
+    for index in 0..6 {
+        let cells = loop {
+            let mut cells = [true; 9];
+            cells[rng.index(9)] = false;
+            cells[rng.index(9)] = false;
+            if is_connected(&cells) && cells.iter().filter(|&&c| c).count() == 7 {
+                break cells;
+            }
+        };
+        areas.push(cells.iter().filter(|&&c| c).count());
+        writeln!(out, "{index}:").unwrap();
+        for row in cells.chunks(3) {
+            let row: String = row.iter().map(|&c| if c { '#' } else { '.' }).collect();
+            writeln!(out, "{row}").unwrap();
+        }
+        out.push('\n');
+    }
+    for i in 0..regions {
+        let (width, height) = (rng.range(35, 50) as usize, rng.range(35, 50) as usize);
+        let mut counts = [0usize; 6];
+        if rng.chance(0.5) {
+            let blocks = (width / 3) * (height / 3);
+            for _ in 0..rng.range(blocks as u64 / 2, blocks as u64) {
+                counts[rng.index(6)] += 1;
+            }
+        } else {
+            let mut area = 0;
+            while area <= width * height {
+                let present = rng.index(6);
+                counts[present] += 1;
+                area += areas[present];
+            }
+        }
+        if i > 0 {
+            out.push('\n');
+        }
+        let counts: Vec<String> = counts.iter().map(usize::to_string).collect();
+        write!(out, "{width}x{height}: {}", counts.join(" ")).unwrap();
+    }
+    out
+}
+
+/// Returns whether the filled cells of a 3x3 grid, in row-major order, are edge-connected.
+fn is_connected(cells: &[bool; 9]) -> bool {
+    let Some(first) = cells.iter().position(|&c| c) else {
+        return false;
+    };
+    let mut seen = [false; 9];
+    let mut stack = vec![first];
+    seen[first] = true;
+    while let Some(i) = stack.pop() {
+        let (r, c) = (i / 3, i % 3);
+        let neighbors = [
+            (r > 0).then(|| i - 3),
+            (r < 2).then(|| i + 3),
+            (c > 0).then(|| i - 1),
+            (c < 2).then(|| i + 1),
+        ];
+        for j in neighbors.into_iter().flatten() {
+            if cells[j] && !seen[j] {
+                seen[j] = true;
+                stack.push(j);
+            }
+        }
+    }
+    (0..9).all(|i| seen[i] == cells[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        let seed = crate::seed::seed();
+        for day in &registry::DAYS {
+            for size in [1, 10, 200] {
+                let input = generate(day.number, size, seed).unwrap();
+                let puzzle = (day.create)(&input);
+                // Each region that fits takes a real search, too slow for a debug build.
+                if day.number != 12 {
+                    puzzle.solve_part_1();
+                    puzzle.solve_part_2();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day11_has_paths() {
+        let seed = crate::seed::seed();
+        for i in 0..20 {
+            let day = crate::day11::Day::new(&generate(11, 300, seed.wrapping_add(i)).unwrap());
+            assert!(day.count_paths("you", "out") > 0);
+            assert!(day.count_paths("svr", "fft") > 0);
+            assert!(day.count_paths("fft", "dac") > 0);
+            assert!(day.count_paths("dac", "out") > 0);
+        }
+    }
+
+    #[test]
+    fn test_day12_regions_are_decided_by_area() {
+        let input = generate(12, 200, 4).unwrap();
+        let (shapes, regions) = input.split_at(input.find('x').unwrap() - 2);
+        assert_eq!(shapes.matches('#').count(), 6 * 7);
+        for region in regions.trim().lines() {
+            let (size, counts) = region.split_once(": ").unwrap();
+            let (w, h) = size.split_once('x').unwrap();
+            let (w, h): (usize, usize) = (w.parse().unwrap(), h.parse().unwrap());
+            let presents: usize = counts.split(' ').map(|c| c.parse::<usize>().unwrap()).sum();
+            assert!(
+                presents <= (w / 3) * (h / 3) || 7 * presents > w * h,
+                "{region}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generation_is_reproducible() {
+        for day in 1..=12 {
+            assert_eq!(generate(day, 50, 7), generate(day, 50, 7));
+            assert_ne!(generate(day, 50, 7), generate(day, 50, 8));
+        }
+        assert_eq!(generate(13, 50, 7), None);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let lines = |day| generate(day, 123, 0).unwrap().lines().count();
+        assert_eq!(lines(1), 123);
+        assert_eq!(lines(3), 123);
+        assert_eq!(lines(4), 123);
+        assert_eq!(lines(8), 123);
+        assert_eq!(lines(9), 124);
+        assert_eq!(lines(10), 123);
+        assert_eq!(generate(2, 123, 0).unwrap().split(',').count(), 123);
+    }
+
+    #[test]
+    fn test_day09_polygon_is_rectilinear() {
+        let input = generate(9, 400, 5).unwrap();
+        let corners: Vec<(u64, u64)> = input
+            .lines()
+            .map(|line| {
+                let (x, y) = line.split_once(',').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect();
+        for (i, &(x, y)) in corners.iter().enumerate() {
+            let (px, py) = corners[(i + corners.len() - 1) % corners.len()];
+            let (nx, ny) = corners[(i + 1) % corners.len()];
+            // Each corner turns: one neighbour shares its row and the other its column.
+            assert!((px == x && ny == y) != (py == y && nx == x), "{i}");
+        }
+    }
+
+    #[test]
+    fn test_device_names() {
+        assert_eq!(device_name(0), "aaa");
+        assert_eq!(device_name(27), "abb");
+        assert_eq!(device_name(26 * 26 * 26), "baaa");
+    }
+}
@@ -0,0 +1,65 @@
+use rayon::ThreadPoolBuilder;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// The environment variable that sets how many threads the solvers run on, for the benchmarks,
+/// which take no flags of their own.
+pub const THREADS_VAR: &str = "AOC_THREADS";
+
+/// The number of threads the global pool was configured with, once it has been.
+static CONFIGURED: Mutex<Option<usize>> = Mutex::new(None);
+
+/// Builds the global rayon pool that the parallel solvers, such as days 10 and 12, run on with `n`
+/// threads, or with one per core if `n` is 0. It must be called before any solver runs, as rayon
+/// starts the pool with one thread per core the first time it is used and it cannot be resized
+/// after that. Calling it again with the same `n` does nothing, and with another fails.
+pub fn configure_threads(n: usize) -> Result<(), Box<dyn Error>> {
+    let mut configured = CONFIGURED.lock().unwrap();
+    match *configured {
+        Some(threads) if threads == n => Ok(()),
+        Some(threads) => {
+            Err(format!("cannot use {n} threads, the pool was already built with {threads}").into())
+        }
+        None => {
+            ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build_global()
+                .map_err(|e| format!("cannot build a pool of {n} threads: {e}"))?;
+            *configured = Some(n);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the number of threads given by `$AOC_THREADS`, or `None` if it is unset. Panics if it
+/// is not a number.
+pub fn from_env() -> Option<usize> {
+    parse_threads(std::env::var(THREADS_VAR).ok().as_deref())
+}
+
+fn parse_threads(var: Option<&str>) -> Option<usize> {
+    var.map(|threads| {
+        threads
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{THREADS_VAR} must be a number, found `{threads}`"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_threads() {
+        assert_eq!(parse_threads(None), None);
+        assert_eq!(parse_threads(Some("4")), Some(4));
+        assert_eq!(parse_threads(Some(" 0\n")), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "AOC_THREADS must be a number, found `all`")]
+    fn test_parse_threads_rejects_garbage() {
+        parse_threads(Some("all"));
+    }
+}
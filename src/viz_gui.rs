@@ -0,0 +1,76 @@
+use crate::render::Raster;
+use minifb::{Key, Window, WindowOptions};
+use std::error::Error;
+
+/// How [`show`] plays an animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// The side of a cell on screen, in pixels.
+    pub cell: usize,
+    /// How many frames to show per second.
+    pub frames_per_second: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            cell: 4,
+            frames_per_second: 30,
+        }
+    }
+}
+
+/// Opens a window titled `title` and plays `frames` in it as they are produced, such as those of
+/// [`day04::Day::animate`](crate::day04::Day::animate). Space pauses and resumes, and the last
+/// frame stays up until the window is closed or Escape is pressed. Every frame must be the size
+/// of the first.
+pub fn show(
+    title: &str,
+    frames: impl IntoIterator<Item = Raster>,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let mut frames = frames.into_iter();
+    let first = frames.next().ok_or("there are no frames to show")?;
+    let (width, height) = (first.width() * options.cell, first.height() * options.cell);
+    let mut window = Window::new(title, width, height, WindowOptions::default())?;
+    window.set_target_fps(options.frames_per_second);
+    let mut buffer = to_buffer(&first, options.cell);
+    let mut paused = false;
+    let mut space_was_down = false;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        window.update_with_buffer(&buffer, width, height)?;
+        let space_is_down = window.is_key_down(Key::Space);
+        if space_is_down && !space_was_down {
+            paused = !paused;
+        }
+        space_was_down = space_is_down;
+        if !paused && let Some(frame) = frames.next() {
+            buffer = to_buffer(&frame, options.cell);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the pixels of `raster` drawn with cells `cell` pixels wide, as a minifb window buffer
+/// of `0RGB` values.
+fn to_buffer(raster: &Raster, cell: usize) -> Vec<u32> {
+    raster
+        .pixels(cell)
+        .into_iter()
+        .map(|[r, g, b]| u32::from_be_bytes([0, r, g, b]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_buffer() {
+        let mut raster = Raster::new(2, 1, "#ffffff");
+        raster.set(0, 0, "#102030", 0);
+        raster.set(1, 0, "#ff8000", 0);
+        assert_eq!(to_buffer(&raster, 1), [0x102030, 0xff8000]);
+        assert_eq!(to_buffer(&raster, 2).len(), 8);
+    }
+}
@@ -0,0 +1,26 @@
+use crate::registry::{self, Part};
+use wasm_bindgen::prelude::*;
+
+/// Solves part 1 or 2 of `day` on `input`, for JavaScript, throwing an `Error` for an unknown day
+/// or part, or for input that the day reports as malformed through its
+/// [`registry::DayEntry::try_create`]. The days without one panic on such input, and panics abort
+/// on `wasm32-unknown-unknown` rather than unwinding, so for them it still traps the module.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => {
+            return Err(JsError::new(&format!(
+                "unknown part {part} (expected 1 or 2)"
+            )));
+        }
+    };
+    Ok(registry::solve(day, part, input)?)
+}
+
+/// Returns the numbers of the solved days, in order.
+#[wasm_bindgen]
+pub fn days() -> Vec<u8> {
+    registry::DAYS.iter().map(|day| day.number).collect()
+}
@@ -0,0 +1,93 @@
+use aoc2025::day03::{self, max_subsequence};
+use aoc2025::day07;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// The system allocator, counting the allocations made by each thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f`, returning its result and the number of allocations (and reallocations) it made on
+/// this thread.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Returns `rows` rows of a manifold `width` columns wide, with the source in the middle of the
+/// first. Splitters scattered over the next 100 rows spread the beams out, and deflectors keep
+/// them moving on every other row after that.
+fn manifold(width: usize, rows: usize) -> String {
+    let mut grid = vec![".".repeat(width); rows];
+    grid[0].replace_range(width / 2..width / 2 + 1, "S");
+    for (row, line) in grid.iter_mut().enumerate().skip(2).step_by(2) {
+        *line = (0..width)
+            .map(|col| match (col * 7 + row * 3) % 5 {
+                0 if row < 100 => '^',
+                0 => '/',
+                1 if row >= 100 => '\\',
+                _ => '.',
+            })
+            .collect();
+    }
+    grid.join("\n")
+}
+
+#[test]
+fn test_day03_banks_allocate_nothing() {
+    let bank: Vec<u8> = (0..100).map(|i| (i * 7 % 10) as u8).collect();
+    let (sum, allocations) = count_allocations(|| {
+        (0..1000)
+            .map(|_| max_subsequence(&bank, 2) + max_subsequence(&bank, 12))
+            .sum::<u64>()
+    });
+    assert!(sum > 0);
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_day03_solve_allocates_only_the_answer() {
+    let input = vec!["3141592653589793238462643383279502884197169399375105820974944592"; 1000];
+    let puzzle = day03::Day::create(&input.join("\n"));
+    let (_, allocations) = count_allocations(|| puzzle.solve_part_1());
+    assert!(allocations <= 2, "{allocations} allocations for part 1");
+    let (_, allocations) = count_allocations(|| puzzle.solve_part_2());
+    assert!(allocations <= 2, "{allocations} allocations for part 2");
+}
+
+#[test]
+fn test_day07_rows_reuse_buffers() {
+    // The frontier's buffers grow a few times as it widens, then are reused for every row, so
+    // the number of allocations stays far below the number of rows.
+    let rows = 2000;
+    let puzzle = day07::Day::create(&manifold(200, rows));
+    let (answer, allocations) = count_allocations(|| puzzle.solve_part_2());
+    assert_ne!(answer, "0");
+    assert!(
+        allocations <= 64,
+        "{allocations} allocations for {rows} rows"
+    );
+}
@@ -0,0 +1,58 @@
+use aoc2025::registry::{self, Variant};
+use aoc2025::{examples, seed, testgen};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Asserts that `variant` and its day's default solver give the same answers for `input`.
+fn assert_agree(variant: &Variant, input: &str) {
+    let day = registry::get(variant.number).unwrap();
+    let (expected, actual) = ((day.create)(input), (variant.create)(input));
+    assert_eq!(
+        (actual.solve_part_1(), actual.solve_part_2()),
+        (expected.solve_part_1(), expected.solve_part_2()),
+        "day {} ({}) on {input:?}",
+        variant.number,
+        variant.name
+    );
+}
+
+/// Returns a day 12 input with the example's presents and a few small random regions, which both
+/// searches settle quickly.
+fn small_day12_input(rng: &mut impl Rng) -> String {
+    let example = examples::DAY12;
+    let presents = &example[..example.find("4x4").unwrap()];
+    let regions: Vec<String> = (0..5)
+        .map(|_| {
+            let (w, h) = (rng.random_range(3..8usize), rng.random_range(3..8usize));
+            let counts: Vec<String> = (0..6)
+                .map(|_| rng.random_range(0..3u8).to_string())
+                .collect();
+            format!("{w}x{h}: {}", counts.join(" "))
+        })
+        .collect();
+    format!("{presents}{}", regions.join("\n"))
+}
+
+#[test]
+fn test_variants_agree_on_examples() {
+    for variant in &registry::VARIANTS {
+        assert_agree(variant, examples::get(variant.number).unwrap());
+    }
+}
+
+#[test]
+fn test_variants_agree_on_random_inputs() {
+    let mut rng = StdRng::seed_from_u64(seed::seed());
+    for variant in &registry::VARIANTS {
+        for _ in 0..10 {
+            let input = match variant.number {
+                12 => small_day12_input(&mut rng),
+                day => {
+                    let size = rng.random_range(2..300usize);
+                    testgen::generate(day, size, rng.random_range(0..u64::MAX)).unwrap()
+                }
+            };
+            assert_agree(variant, &input);
+        }
+    }
+}
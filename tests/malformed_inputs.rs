@@ -0,0 +1,64 @@
+use aoc2025::parse::ParseError;
+use aoc2025::puzzle::Puzzle;
+use aoc2025::{day02, day05, day09, day10, examples};
+use std::panic;
+
+type TryCreate = fn(&str) -> Result<Box<dyn Puzzle>, ParseError>;
+
+/// The days whose parsers report malformed input, with the example of each.
+const DAYS: [(u8, TryCreate, &str); 4] = [
+    (2, day02::Day::try_create, examples::DAY02),
+    (5, day05::Day::try_create, examples::DAY05),
+    (9, day09::Day::try_create, examples::DAY09),
+    (10, day10::Day::try_create, examples::DAY10),
+];
+
+/// Malformed variants of `example`: empty and blank files, the example cut off partway through
+/// its last line, its delimiters swapped for others, and its first number too large for a `u64`.
+fn malformed(example: &str) -> Vec<(&'static str, String)> {
+    let last_line = example.rfind('\n').map_or(0, |i| i + 1);
+    let cut = last_line + (example.len() - last_line) / 2;
+    let digits = example.find(|c: char| c.is_ascii_digit()).unwrap();
+    let mut cases = vec![
+        ("empty", String::new()),
+        ("blank", "\n \n\n".to_string()),
+        ("truncated", format!("{}-", &example[..cut])),
+        (
+            "huge number",
+            format!(
+                "{}99999999999999999999{}",
+                &example[..digits],
+                &example[digits..]
+            ),
+        ),
+        ("stray character", format!("{example}\n?")),
+    ];
+    for (from, to) in [(',', ';'), ('-', '_'), ('\n', '|'), (' ', '/')] {
+        if example.contains(from) {
+            cases.push(("wrong delimiter", example.replace(from, &to.to_string())));
+        }
+    }
+    cases
+}
+
+#[test]
+fn test_examples_parse() {
+    for (day, try_create, example) in DAYS {
+        assert!(try_create(example).is_ok(), "day {day}");
+    }
+}
+
+#[test]
+fn test_malformed_inputs_are_diagnosed() {
+    let mut failures = Vec::new();
+    for (day, try_create, example) in DAYS {
+        for (kind, input) in malformed(example) {
+            match panic::catch_unwind(|| try_create(&input)) {
+                Ok(Err(_)) => {}
+                Ok(Ok(_)) => failures.push(format!("day {day}, {kind}: accepted {input:?}")),
+                Err(_) => failures.push(format!("day {day}, {kind}: panicked on {input:?}")),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
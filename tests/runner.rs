@@ -0,0 +1,185 @@
+use aoc2025::render::VizFormat;
+use aoc2025::run_log::{self, RunLog};
+use aoc2025::runner::{self, Format, InputSource};
+use aoc2025::{examples, registry};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Serves each day's example input from memory.
+struct MemorySource(HashMap<u8, &'static str>);
+
+impl InputSource for MemorySource {
+    fn get_input(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        match self.0.get(&day) {
+            Some(input) => Ok(input.to_string()),
+            None => Err(format!("no input for day {day}").into()),
+        }
+    }
+}
+
+fn examples() -> MemorySource {
+    MemorySource(
+        registry::DAYS
+            .iter()
+            .map(|day| (day.number, examples::get(day.number).unwrap()))
+            .collect(),
+    )
+}
+
+const EXPECTED_TEXT: &str = "\
+Day 01 Part 1: 3
+Day 01 Part 2: 6
+Day 02 Part 1: 1227775554
+Day 02 Part 2: 4174379265
+Day 03 Part 1: 357
+Day 03 Part 2: 3121910778619
+Day 04 Part 1: 13
+Day 04 Part 2: 43
+Day 05 Part 1: 3
+Day 05 Part 2: 14
+Day 06 Part 1: 4277556
+Day 06 Part 2: 3263827
+Day 07 Part 1: 21
+Day 07 Part 2: 40
+Day 08 Part 1: 20
+Day 08 Part 2: 25272
+Day 09 Part 1: 50
+Day 09 Part 2: 24
+Day 10 Part 1: 7
+Day 10 Part 2: 33
+Day 11 Part 1: 5
+Day 11 Part 2: 2
+Day 12 Part 1: 2
+Day 12 Part 2: Day 12 has no part 2
+";
+
+const EXPECTED_JSON: &str = r#"[
+  {"day":1,"part1":"3","part2":"6"},
+  {"day":2,"part1":"1227775554","part2":"4174379265"},
+  {"day":3,"part1":"357","part2":"3121910778619"},
+  {"day":4,"part1":"13","part2":"43"},
+  {"day":5,"part1":"3","part2":"14"},
+  {"day":6,"part1":"4277556","part2":"3263827"},
+  {"day":7,"part1":"21","part2":"40"},
+  {"day":8,"part1":"20","part2":"25272"},
+  {"day":9,"part1":"50","part2":"24"},
+  {"day":10,"part1":"7","part2":"33"},
+  {"day":11,"part1":"5","part2":"2"},
+  {"day":12,"part1":"2","part2":"Day 12 has no part 2"}
+]
+"#;
+
+#[test]
+fn test_every_day_runs() {
+    let results = runner::run(&registry::DAYS, &examples(), None).unwrap();
+    let days: Vec<u8> = results.iter().map(|result| result.day).collect();
+    assert_eq!(days, (1..=12).collect::<Vec<u8>>());
+    assert!(results.iter().all(|result| result.visualization.is_none()));
+}
+
+#[test]
+fn test_text_output() {
+    let results = runner::run(&registry::DAYS, &examples(), None).unwrap();
+    assert_eq!(runner::render(&results, Format::Text), EXPECTED_TEXT);
+}
+
+#[test]
+fn test_json_output() {
+    let results = runner::run(&registry::DAYS, &examples(), None).unwrap();
+    let json = runner::render(&results, Format::Json);
+    assert_eq!(json, EXPECTED_JSON);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed[11]["part1"], serde_json::Value::String("2".into()));
+}
+
+#[test]
+fn test_visualizations_are_written() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let results = runner::run(&registry::DAYS, &examples(), Some(dir.path())).unwrap();
+    let text = runner::render(&results, Format::Text);
+    let written: Vec<_> = results
+        .iter()
+        .filter_map(|result| result.visualization.as_ref())
+        .collect();
+    assert!(!written.is_empty());
+    for path in written {
+        assert!(path.starts_with(dir.path()) && path.exists());
+        assert!(text.contains(&format!("Visualization: {}", path.display())));
+    }
+    // Apart from the visualization lines, the output is unchanged.
+    let answers: String = text
+        .lines()
+        .filter(|line| !line.contains("Visualization"))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    assert_eq!(answers, EXPECTED_TEXT);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_png_visualizations() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut log = RunLog::in_memory();
+    let results = runner::run_logged(
+        &registry::DAYS,
+        &examples(),
+        Some(dir.path()),
+        VizFormat::Png,
+        &mut log,
+    )
+    .unwrap();
+    let extension = |day: usize| {
+        let path = results[day - 1].visualization.as_ref().unwrap();
+        path.extension().unwrap().to_str().unwrap().to_string()
+    };
+    // The grid-based visualizations are rasterized, and the others keep their own formats.
+    assert_eq!(extension(4), "png");
+    assert_eq!(extension(12), "png");
+    assert_eq!(extension(11), "dot");
+    let png = std::fs::read(results[3].visualization.as_ref().unwrap()).unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+#[test]
+fn test_run_log() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("run.jsonl");
+    for _ in 0..2 {
+        let mut log = RunLog::append(&path).unwrap();
+        runner::run_logged(
+            &registry::DAYS,
+            &examples(),
+            None,
+            VizFormat::Native,
+            &mut log,
+        )
+        .unwrap();
+    }
+    let log = std::fs::read_to_string(&path).unwrap();
+    let events: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // Each run appends a fetch, a parse and two solves per day.
+    assert_eq!(events.len(), 2 * 4 * registry::DAYS.len());
+    let string = |s: &str| serde_json::Value::String(s.into());
+    let day2 = &events[4..8];
+    let steps: Vec<_> = day2.iter().map(|event| event["event"].clone()).collect();
+    assert_eq!(
+        steps,
+        ["fetch", "parse", "solve", "solve"].map(string).to_vec()
+    );
+    let hash = string(&run_log::input_hash(examples::DAY02));
+    assert!(day2.iter().all(|event| event["input_hash"] == hash));
+    assert_eq!(day2[3]["part"].as_f64(), Some(2.0));
+    assert_eq!(day2[3]["answer"], string("4174379265"));
+    assert!(day2[3]["duration_ns"].as_f64().is_some());
+}
+
+#[test]
+fn test_missing_input_names_the_day() {
+    let mut source = examples();
+    source.0.remove(&7);
+    let err = runner::run(&registry::DAYS, &source, None).unwrap_err();
+    assert_eq!(err.to_string(), "Day 7: no input for day 7");
+}
@@ -0,0 +1,64 @@
+use aoc2025::{registry, testgen};
+use std::time::{Duration, Instant};
+
+/// Solves both parts of a generated input for `day` (see `testgen::generate` for what `size`
+/// counts) and asserts that parsing and solving took at most `limit`.
+///
+/// The limits are several times what an optimized build needs on one core, so they only fail when
+/// a change makes a day scale worse than its documented complexity. Debug builds are far too slow
+/// for them to mean anything, so there the test skips itself.
+fn assert_solves_within(day: u8, size: usize, limit: Duration) {
+    if cfg!(debug_assertions) {
+        eprintln!("skipping the day {day} stress test, which needs an optimized build (--release)");
+        return;
+    }
+    let input = testgen::generate(day, size, 0).unwrap();
+    let create = registry::get(day).unwrap().create;
+    let start = Instant::now();
+    let puzzle = create(&input);
+    puzzle.solve_part_1();
+    puzzle.solve_part_2();
+    let elapsed = start.elapsed();
+    assert!(
+        elapsed <= limit,
+        "day {day} took {elapsed:?} on an input of size {size}, over the limit of {limit:?}"
+    );
+}
+
+/// A 1400x1400 grid, about 100 times the cells of a real input.
+#[test]
+#[ignore = "slow; run with `cargo test --release --test stress -- --ignored`"]
+fn test_day04_stress() {
+    assert_solves_within(4, 1400, Duration::from_secs(5));
+}
+
+/// A manifold 1400 columns wide, about 100 times the cells of a real input.
+#[test]
+#[ignore = "slow; run with `cargo test --release --test stress -- --ignored`"]
+fn test_day07_stress() {
+    assert_solves_within(7, 1400, Duration::from_secs(5));
+}
+
+/// 100,000 junction boxes, 100 times a real input.
+#[test]
+#[ignore = "slow; run with `cargo test --release --test stress -- --ignored`"]
+fn test_day08_stress() {
+    assert_solves_within(8, 100_000, Duration::from_secs(10));
+}
+
+/// 5000 red tiles, 10 times a real input. Part 2 is quadratic, so this is the largest size with a
+/// short run time.
+#[test]
+#[ignore = "slow; run with `cargo test --release --test stress -- --ignored`"]
+fn test_day09_stress() {
+    assert_solves_within(9, 5000, Duration::from_secs(10));
+}
+
+/// 2000 regions, twice as many as a real input. Generated regions take about five times longer to
+/// search than real ones, so this is about 10 times the work. Regions are solved independently,
+/// so the time should grow linearly with their number.
+#[test]
+#[ignore = "slow; run with `cargo test --release --test stress -- --ignored`"]
+fn test_day12_stress() {
+    assert_solves_within(12, 2000, Duration::from_secs(300));
+}
@@ -0,0 +1,15 @@
+use aoc2025::{configure_threads, day12, examples};
+
+/// Runs in its own process, so that no solver has started the global pool before it is built.
+#[test]
+fn test_configure_threads() {
+    configure_threads(2).unwrap();
+    configure_threads(2).unwrap();
+    assert_eq!(rayon::current_num_threads(), 2);
+    assert_eq!(
+        configure_threads(3).unwrap_err().to_string(),
+        "cannot use 3 threads, the pool was already built with 2"
+    );
+    // The solvers run on the pool as built.
+    assert_eq!(day12::Day::create(examples::DAY12).solve_part_1(), "2");
+}